@@ -1,9 +1,8 @@
 use std::io::Write;
-use std::thread;
 use sonr::prelude::*;
 use sonr::errors::Result;
-use sonr::sync::queue::{ReactiveQueue, ReactiveDeque};
-use sonr::net::tcp::{ReactiveTcpListener, TcpStream, ReactiveTcpStream};
+use sonr::runtime::Runtime;
+use sonr::net::tcp::{ReactiveTcpListener, TcpStream};
 
 // -----------------------------------------------------------------------------
 // 		- Writer -
@@ -33,22 +32,15 @@ fn main() -> Result<()> {
 
     // Listen for incoming connections
     let listener = ReactiveTcpListener::bind("127.0.0.1:8000")?.map(|(s, _)| s);
-    // Connection queue for connections to be sent to another thread.
-    let mut queue = ReactiveQueue::unbounded();
-
-    for _ in 0..4 {
-        let deque = queue.deque();
-        thread::spawn(move || -> Result<()> {
-            System::init()?;
-            let deque = ReactiveDeque::new(deque)?;
-            let writer = Writer;
-            let run = deque.chain(writer);
-            System::start(run)?;
-            Ok(())
-        });
-    }
+    // Connection queue for connections to be sent to the workers.
+    let (runtime, queue) = Runtime::builder()
+        .workers(4)
+        .start_with_queue(|_, deque| Ok(deque.chain(Writer)))?;
 
     let run = listener.chain(queue);
     System::start(run)?;
+
+    runtime.stop();
+    runtime.join()?;
     Ok(())
 }
@@ -1,96 +1,42 @@
-use std::io::Write;
-use std::io::ErrorKind::WouldBlock;
-use std::net::SocketAddr;
-use std::collections::HashMap;
-use std::thread;
-
-use sonr::{Token, Event};
 use sonr::errors::Result;
-use sonr::net::tcp::{ReactiveTcpListener, ReactiveTcpStream, TcpStream};
-use sonr::reactor::{Reactor, Reaction};
+use sonr::net::connections::{Connections, Handled};
+use sonr::net::http::{HttpConnection, Response};
+use sonr::net::tcp::{ReactiveTcpListener, ReactiveTcpStream};
+use sonr::reactor::Reactor;
+use sonr::runtime::Runtime;
 use sonr::system::System;
-use sonr::sync::queue::{ReactiveDeque, ReactiveQueue};
-
-// -----------------------------------------------------------------------------
-// 		- Disclaimer -
-// 		This example will simply write the response to the
-// 		underlying tcp stream until it either blocks
-// 		or errors out. This is not how a web server should behave,
-// 		but it's useful for testing.
-// -----------------------------------------------------------------------------
-
-static RESPONSE: &'static [u8] = b"HTTP/1.1 200 OK\nContent-Length: 13\n\nHello World\n\n";
-
-struct Connections {
-    inner: HashMap<Token, ReactiveTcpStream>
-}
-
-impl Connections {
-    fn handle_connection_event(&mut self, event: Event) -> bool {
-        let reacting = self.inner.get(&event.token()).is_some();
 
-        let stream = self.inner.remove(&event.token()).map(|mut stream| {
-            stream.react(Reaction::Event(event));
-            while stream.writable() {
-                match stream.write(&RESPONSE) { 
-                    Ok(_) => {},
-                    Err(ref e) if e.kind() == WouldBlock => return Some(stream),
-                    Err(e) => return None,
-                }
-            }
-            return None
-        });
+// Run with `cargo run --example webserver --features http`
 
-        match stream {
-            Some(Some(stream)) => { self.inner.insert(stream.token(), stream); }
-            _ => {}
+fn respond(conn: &mut HttpConnection) -> Handled<()> {
+    while let Some(_request) = conn.next_request() {
+        let response = Response::new(200)
+            .header("Content-Type", "text/plain")
+            .body("Hello World\n");
+        if conn.respond(response).is_err() {
+            return Handled::Close;
         }
-
-        reacting
     }
-}
-
-impl Reactor for Connections {
-    type Input = (TcpStream, SocketAddr);
-    type Output = ();
 
-    fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
-        match reaction {
-            Reaction::Value(input) => {
-                let (stream, _) = input; // ignore address 
-                let tcp_stream = ReactiveTcpStream::new(stream).unwrap(); 
-                self.inner.insert(tcp_stream.token(), tcp_stream);
-                Reaction::Continue
-            }
-            Reaction::Event(event) => {
-                match self.handle_connection_event(event) {
-                    true => Reaction::Continue,
-                    false => Reaction::Event(event)
-                }
-            }
-            Reaction::Continue => Reaction::Continue,
-        } 
+    match conn.is_closed() {
+        true => Handled::Close,
+        false => Handled::Continue,
     }
 }
 
 fn main() -> Result<()> {
-    System::init();
+    System::init()?;
     let listener = ReactiveTcpListener::bind("127.0.0.1:5555")?;
-    let mut stream_q = ReactiveQueue::unbounded();
+    let (runtime, stream_q) = Runtime::builder().workers(8).start_with_queue(|_, deque| {
+        let incoming_streams = deque.map(|(stream, _)| HttpConnection::new(ReactiveTcpStream::new(stream).unwrap()));
+        let connections = Connections::new(|_, conn: &mut HttpConnection| respond(conn));
+        Ok(incoming_streams.chain(connections))
+    })?;
 
-    for _ in 0..8 {
-        let deque = stream_q.deque();
-        thread::spawn(move || {
-            System::init();
-            let incoming_streams = ReactiveDeque::new(deque).unwrap();
-            let connections = Connections { inner: HashMap::new() };
-
-            let streams = incoming_streams.chain(connections);
-            System::start(streams);
-        });
-    }
-        
     let server = listener.chain(stream_q);
-    System::start(server);
+    System::start(server)?;
+
+    runtime.stop();
+    runtime.join()?;
     Ok(())
 }
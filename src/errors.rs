@@ -46,6 +46,19 @@ pub enum Error {
 
     /// From UTF8 error
     FromUtf8Error(FromUtf8Error),
+
+    /// The System a [`SystemHandle`] belongs to no longer exists
+    ///
+    /// [`SystemHandle`]: ../system/struct.SystemHandle.html
+    SystemGone,
+
+    /// TLS error
+    #[cfg(feature = "tls")]
+    Tls(rustls::Error),
+
+    /// A request that could not be parsed
+    #[cfg(feature = "http")]
+    Http(crate::net::http::ParseError),
 }
 
 
@@ -94,3 +107,22 @@ impl From<FromUtf8Error> for Error {
     }
 }
 
+// -----------------------------------------------------------------------------
+// 		- TLS error -
+// -----------------------------------------------------------------------------
+#[cfg(feature = "tls")]
+impl From<rustls::Error> for Error {
+    fn from(err: rustls::Error) -> Error {
+        Error::Tls(err)
+    }
+}
+
+// -----------------------------------------------------------------------------
+// 		- HTTP parse error -
+// -----------------------------------------------------------------------------
+#[cfg(feature = "http")]
+impl From<crate::net::http::ParseError> for Error {
+    fn from(err: crate::net::http::ParseError) -> Error {
+        Error::Http(err)
+    }
+}
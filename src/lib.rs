@@ -73,17 +73,29 @@ pub mod sync;
 #[deny(missing_docs)]
 pub mod errors;
 
+#[deny(missing_docs)]
+pub mod runtime;
+
+#[deny(missing_docs)]
+pub mod metrics;
+
 #[deny(missing_docs)]
 mod prevec;
 
-pub use prevec::PreVec;
+#[deny(missing_docs)]
+mod wheel;
+
+#[deny(missing_docs)]
+mod route;
+
+pub use prevec::{Growth, PreVec};
 
 // Re-exports
 pub use mio::{Token, Event, Evented, PollOpt, Poll, Ready, SetReadiness, Registration};
 
 pub mod prelude {
     pub use mio::{Token, Event};
-    pub use crate::reactor::{Reaction, Reactor};
+    pub use crate::reactor::{Reaction, Reactor, TryReactor};
     pub use crate::system::{SystemEvent, System};
     pub use crate::net::stream::Stream;
 }
@@ -0,0 +1,756 @@
+//! Counters, gauges and histograms describing running [`System`]s, listeners and queues.
+//!
+//! Every [`System`] records its own metrics (labelled with the name of the System),
+//! and listeners and queues record metrics once they are given a name with
+//! `register_metrics`.
+//!
+//! Metrics are registered with the global [`Registry`], and can be read
+//! from any thread with [`snapshot`]. A [`Snapshot`] can be rendered in the
+//! Prometheus text format, and served over HTTP by a [`MetricsEndpoint`].
+//!
+//! A metric is removed from the registry once every handle to it has been dropped
+//! (e.g. when the listener it belongs to is dropped).
+//!
+//!```no_run
+//! # use sonr::prelude::*;
+//! # use sonr::errors::Result;
+//! use sonr::metrics::MetricsEndpoint;
+//! use sonr::net::tcp::ReactiveTcpListener;
+//!
+//! fn main() -> Result<()> {
+//!     System::init()?;
+//!
+//!     let mut listener = ReactiveTcpListener::bind("127.0.0.1:8000")?;
+//!     listener.register_metrics("public");
+//!
+//!     // Serve the metrics on http://127.0.0.1:9100/metrics
+//!     System::spawn(MetricsEndpoint::bind("127.0.0.1:9100")?);
+//!
+//!     System::start(listener)?;
+//!     Ok(())
+//! }
+//! ```
+//!
+//! [`System`]: ../system/struct.System.html
+//! [`Registry`]: struct.Registry.html
+//! [`snapshot`]: fn.snapshot.html
+//! [`Snapshot`]: struct.Snapshot.html
+//! [`MetricsEndpoint`]: struct.MetricsEndpoint.html
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter, Write as _};
+use std::io::ErrorKind::WouldBlock;
+use std::io::{Read, Write};
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use mio::Token;
+use parking_lot::Mutex;
+
+use crate::errors::Result;
+use crate::net::connections::{Connections, Handled, Handler};
+use crate::net::tcp::{ReactiveTcpListener, ReactiveTcpStream};
+use crate::reactor::{Reaction, Reactor};
+
+/// Buckets (in seconds) used for durations, from one microsecond to one second
+pub const DURATION_BUCKETS: &[f64] = &[
+    0.000_001, 0.000_005, 0.000_01, 0.000_05, 0.000_1, 0.000_5, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0,
+];
+
+/// Buckets used for the number of events per poll
+pub const EVENT_BUCKETS: &[f64] = &[0.0, 1.0, 2.0, 4.0, 8.0, 16.0, 32.0, 64.0, 128.0, 256.0, 512.0, 1024.0];
+
+// -----------------------------------------------------------------------------
+// 		- Counter -
+// -----------------------------------------------------------------------------
+/// A value that only goes up, e.g. the number of accepted connections.
+#[derive(Debug, Clone, Default)]
+pub struct Counter(Arc<AtomicU64>);
+
+impl Counter {
+    /// Create a counter that is not registered
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Increment the counter by one
+    pub fn inc(&self) {
+        self.add(1);
+    }
+
+    /// Increment the counter by `n`
+    pub fn add(&self, n: u64) {
+        self.0.fetch_add(n, Ordering::Relaxed);
+    }
+
+    /// The current value
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+// -----------------------------------------------------------------------------
+// 		- Gauge -
+// -----------------------------------------------------------------------------
+/// A value that can go up and down, e.g. the number of values in a queue.
+#[derive(Debug, Clone, Default)]
+pub struct Gauge(Arc<AtomicI64>);
+
+impl Gauge {
+    /// Create a gauge that is not registered
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the value
+    pub fn set(&self, value: i64) {
+        self.0.store(value, Ordering::Relaxed);
+    }
+
+    /// Increment the value by one
+    pub fn inc(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Decrement the value by one
+    pub fn dec(&self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    /// The current value
+    pub fn get(&self) -> i64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+// -----------------------------------------------------------------------------
+// 		- Histogram -
+// -----------------------------------------------------------------------------
+#[derive(Debug)]
+struct Buckets {
+    bounds: Vec<f64>,
+    // One more than the number of bounds, the last one being `+Inf`
+    counts: Vec<AtomicU64>,
+    sum: AtomicU64,
+}
+
+/// Count observed values in buckets, e.g. the time spent reacting to an event.
+///
+/// A value is counted in the first bucket with an upper bound greater than or
+/// equal to the value, or in the implicit `+Inf` bucket.
+#[derive(Debug, Clone)]
+pub struct Histogram(Arc<Buckets>);
+
+impl Histogram {
+    /// Create a histogram that is not registered, with the upper bounds
+    /// of the buckets in increasing order.
+    pub fn new(bounds: &[f64]) -> Self {
+        Self(Arc::new(Buckets {
+            bounds: bounds.to_vec(),
+            counts: (0..=bounds.len()).map(|_| AtomicU64::new(0)).collect(),
+            sum: AtomicU64::new(0f64.to_bits()),
+        }))
+    }
+
+    /// Count a value
+    pub fn observe(&self, value: f64) {
+        let buckets = &self.0;
+        let index = buckets.bounds.iter().position(|bound| value <= *bound).unwrap_or(buckets.bounds.len());
+        buckets.counts[index].fetch_add(1, Ordering::Relaxed);
+
+        let mut sum = buckets.sum.load(Ordering::Relaxed);
+        loop {
+            let new_sum = (f64::from_bits(sum) + value).to_bits();
+            match buckets.sum.compare_exchange_weak(sum, new_sum, Ordering::Relaxed, Ordering::Relaxed) {
+                Ok(_) => break,
+                Err(current) => sum = current,
+            }
+        }
+    }
+
+    /// Count a duration, in seconds
+    pub fn observe_duration(&self, duration: Duration) {
+        self.observe(duration.as_secs_f64());
+    }
+
+    /// The current state of the histogram
+    pub fn get(&self) -> HistogramSnapshot {
+        let buckets = &self.0;
+        let mut count = 0;
+        let mut cumulative = Vec::with_capacity(buckets.bounds.len());
+        for (bound, bucket_count) in buckets.bounds.iter().zip(&buckets.counts) {
+            count += bucket_count.load(Ordering::Relaxed);
+            cumulative.push((*bound, count));
+        }
+        count += buckets.counts[buckets.bounds.len()].load(Ordering::Relaxed);
+
+        HistogramSnapshot {
+            buckets: cumulative,
+            count,
+            sum: f64::from_bits(buckets.sum.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+/// The state of a [`Histogram`] when the snapshot was taken.
+///
+/// [`Histogram`]: struct.Histogram.html
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistogramSnapshot {
+    /// The upper bound of each bucket, and the number of values
+    /// less than or equal to the bound (excluding the `+Inf` bucket)
+    pub buckets: Vec<(f64, u64)>,
+
+    /// Number of observed values
+    pub count: u64,
+
+    /// Sum of the observed values
+    pub sum: f64,
+}
+
+// -----------------------------------------------------------------------------
+// 		- Registry -
+// -----------------------------------------------------------------------------
+/// A registered counter, gauge or histogram
+#[derive(Debug, Clone)]
+pub enum Metric {
+    /// A counter
+    Counter(Counter),
+    /// A gauge
+    Gauge(Gauge),
+    /// A histogram
+    Histogram(Histogram),
+}
+
+impl Metric {
+    // Only held by the registry
+    fn is_orphan(&self) -> bool {
+        match self {
+            Metric::Counter(counter) => Arc::strong_count(&counter.0) == 1,
+            Metric::Gauge(gauge) => Arc::strong_count(&gauge.0) == 1,
+            Metric::Histogram(histogram) => Arc::strong_count(&histogram.0) == 1,
+        }
+    }
+
+    fn value(&self) -> Value {
+        match self {
+            Metric::Counter(counter) => Value::Counter(counter.get()),
+            Metric::Gauge(gauge) => Value::Gauge(gauge.get()),
+            Metric::Histogram(histogram) => Value::Histogram(histogram.get()),
+        }
+    }
+}
+
+impl From<Counter> for Metric {
+    fn from(counter: Counter) -> Self {
+        Metric::Counter(counter)
+    }
+}
+
+impl From<Gauge> for Metric {
+    fn from(gauge: Gauge) -> Self {
+        Metric::Gauge(gauge)
+    }
+}
+
+impl From<Histogram> for Metric {
+    fn from(histogram: Histogram) -> Self {
+        Metric::Histogram(histogram)
+    }
+}
+
+#[derive(Debug)]
+struct Entry {
+    name: String,
+    help: String,
+    labels: Vec<(String, String)>,
+    metric: Metric,
+}
+
+/// A collection of named metrics, that can be read from any thread.
+///
+/// The metrics recorded by sonr are registered with the [`global`] registry.
+///
+/// [`global`]: struct.Registry.html#method.global
+#[derive(Debug, Default)]
+pub struct Registry {
+    entries: Mutex<Vec<Entry>>,
+}
+
+impl Registry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The registry used by the [`System`], listeners and queues.
+    ///
+    /// [`System`]: ../system/struct.System.html
+    pub fn global() -> &'static Registry {
+        static GLOBAL: OnceLock<Registry> = OnceLock::new();
+        GLOBAL.get_or_init(Registry::new)
+    }
+
+    /// Register a metric.
+    /// A metric already registered with the same name and labels is replaced.
+    pub fn register(&self, name: &str, help: &str, labels: &[(&str, &str)], metric: impl Into<Metric>) {
+        let labels: Vec<(String, String)> = labels.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        let mut entries = self.entries.lock();
+        entries.retain(|entry| !entry.metric.is_orphan() && (entry.name != name || entry.labels != labels));
+        entries.push(Entry {
+            name: name.into(),
+            help: help.into(),
+            labels,
+            metric: metric.into(),
+        });
+    }
+
+    /// Create and register a counter
+    pub fn counter(&self, name: &str, help: &str, labels: &[(&str, &str)]) -> Counter {
+        let counter = Counter::new();
+        self.register(name, help, labels, counter.clone());
+        counter
+    }
+
+    /// Create and register a gauge
+    pub fn gauge(&self, name: &str, help: &str, labels: &[(&str, &str)]) -> Gauge {
+        let gauge = Gauge::new();
+        self.register(name, help, labels, gauge.clone());
+        gauge
+    }
+
+    /// Create and register a histogram
+    pub fn histogram(&self, name: &str, help: &str, labels: &[(&str, &str)], bounds: &[f64]) -> Histogram {
+        let histogram = Histogram::new(bounds);
+        self.register(name, help, labels, histogram.clone());
+        histogram
+    }
+
+    /// Read the current value of every metric
+    pub fn snapshot(&self) -> Snapshot {
+        let mut entries = self.entries.lock();
+        entries.retain(|entry| !entry.metric.is_orphan());
+
+        let mut samples: Vec<Sample> = entries
+            .iter()
+            .map(|entry| Sample {
+                name: entry.name.clone(),
+                help: entry.help.clone(),
+                labels: entry.labels.clone(),
+                value: entry.metric.value(),
+            })
+            .collect();
+        drop(entries);
+
+        // Samples of the same metric are grouped together
+        samples.sort_by(|a, b| a.name.cmp(&b.name));
+        Snapshot { samples }
+    }
+}
+
+/// Read the current value of every metric in the [`global`] registry.
+///
+/// [`global`]: struct.Registry.html#method.global
+pub fn snapshot() -> Snapshot {
+    Registry::global().snapshot()
+}
+
+// -----------------------------------------------------------------------------
+// 		- Snapshot -
+// -----------------------------------------------------------------------------
+/// The value of a metric when the snapshot was taken
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// The value of a counter
+    Counter(u64),
+    /// The value of a gauge
+    Gauge(i64),
+    /// The buckets of a histogram
+    Histogram(HistogramSnapshot),
+}
+
+/// A metric and its value
+#[derive(Debug, Clone)]
+pub struct Sample {
+    /// Name of the metric
+    pub name: String,
+    /// Description of the metric
+    pub help: String,
+    /// Labels, as `(name, value)`
+    pub labels: Vec<(String, String)>,
+    /// The value
+    pub value: Value,
+}
+
+/// The values of the metrics in a [`Registry`] at one point in time.
+///
+/// `Display` renders the snapshot in the Prometheus text format.
+///
+/// [`Registry`]: struct.Registry.html
+#[derive(Debug, Clone, Default)]
+pub struct Snapshot {
+    samples: Vec<Sample>,
+}
+
+impl Snapshot {
+    /// The samples, ordered by name
+    pub fn samples(&self) -> &[Sample] {
+        &self.samples
+    }
+
+    /// The value of the first metric with the name, and (at least) the labels
+    pub fn get(&self, name: &str, labels: &[(&str, &str)]) -> Option<&Value> {
+        self.samples
+            .iter()
+            .find(|sample| {
+                sample.name == name
+                    && labels.iter().all(|(k, v)| sample.labels.iter().any(|(lk, lv)| lk == k && lv == v))
+            })
+            .map(|sample| &sample.value)
+    }
+
+    /// Render the snapshot in the Prometheus text format
+    pub fn to_prometheus(&self) -> String {
+        self.to_string()
+    }
+}
+
+fn write_labels(f: &mut Formatter, labels: &[(String, String)], le: Option<&str>) -> fmt::Result {
+    let le = le.map(|le| ("le", le));
+    let labels = labels.iter().map(|(k, v)| (k.as_str(), v.as_str())).chain(le);
+    let mut first = true;
+    for (name, value) in labels {
+        f.write_str(if first { "{" } else { "," })?;
+        first = false;
+        write!(f, "{}=\"", name)?;
+        for c in value.chars() {
+            match c {
+                '\\' => f.write_str("\\\\")?,
+                '"' => f.write_str("\\\"")?,
+                '\n' => f.write_str("\\n")?,
+                c => f.write_char(c)?,
+            }
+        }
+        f.write_char('"')?;
+    }
+    if !first {
+        f.write_char('}')?;
+    }
+    Ok(())
+}
+
+impl Display for Snapshot {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let mut previous: Option<&str> = None;
+        for sample in &self.samples {
+            if previous != Some(sample.name.as_str()) {
+                let kind = match sample.value {
+                    Value::Counter(_) => "counter",
+                    Value::Gauge(_) => "gauge",
+                    Value::Histogram(_) => "histogram",
+                };
+                writeln!(f, "# HELP {} {}", sample.name, sample.help.replace('\n', " "))?;
+                writeln!(f, "# TYPE {} {}", sample.name, kind)?;
+                previous = Some(sample.name.as_str());
+            }
+
+            match sample.value {
+                Value::Counter(value) => {
+                    f.write_str(&sample.name)?;
+                    write_labels(f, &sample.labels, None)?;
+                    writeln!(f, " {}", value)?;
+                }
+                Value::Gauge(value) => {
+                    f.write_str(&sample.name)?;
+                    write_labels(f, &sample.labels, None)?;
+                    writeln!(f, " {}", value)?;
+                }
+                Value::Histogram(ref histogram) => {
+                    let buckets = histogram.buckets.iter().map(|(bound, count)| (bound.to_string(), *count));
+                    for (le, count) in buckets.chain(Some(("+Inf".to_string(), histogram.count))) {
+                        write!(f, "{}_bucket", sample.name)?;
+                        write_labels(f, &sample.labels, Some(&le))?;
+                        writeln!(f, " {}", count)?;
+                    }
+                    write!(f, "{}_sum", sample.name)?;
+                    write_labels(f, &sample.labels, None)?;
+                    writeln!(f, " {}", histogram.sum)?;
+                    write!(f, "{}_count", sample.name)?;
+                    write_labels(f, &sample.labels, None)?;
+                    writeln!(f, " {}", histogram.count)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// -----------------------------------------------------------------------------
+// 		- System metrics -
+// -----------------------------------------------------------------------------
+// Metrics recorded by each System
+#[derive(Debug, Clone)]
+pub(crate) struct SystemMetrics {
+    pub(crate) polls: Counter,
+    pub(crate) events: Counter,
+    pub(crate) events_per_poll: Histogram,
+    pub(crate) react_seconds: Histogram,
+    pub(crate) tokens: Gauge,
+    pub(crate) timers: Gauge,
+    pub(crate) spawned: Gauge,
+    pub(crate) in_flight: Gauge,
+}
+
+impl SystemMetrics {
+    pub(crate) fn new(system: &str) -> Self {
+        let registry = Registry::global();
+        let labels = &[("system", system)];
+        Self {
+            polls: registry.counter("sonr_system_polls_total", "Number of polls", labels),
+            events: registry.counter("sonr_system_events_total", "Number of events polled", labels),
+            events_per_poll: registry.histogram(
+                "sonr_system_events_per_poll",
+                "Number of events returned by each poll",
+                labels,
+                EVENT_BUCKETS,
+            ),
+            react_seconds: registry.histogram(
+                "sonr_system_react_seconds",
+                "Time spent reacting to each event",
+                labels,
+                DURATION_BUCKETS,
+            ),
+            tokens: registry.gauge("sonr_system_tokens", "Number of reserved tokens", labels),
+            timers: registry.gauge("sonr_system_timers", "Number of scheduled timers", labels),
+            spawned: registry.gauge("sonr_system_spawned", "Number of spawned reactors", labels),
+            in_flight: registry.gauge("sonr_system_in_flight", "Number of in-flight streams", labels),
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// 		- Listener metrics -
+// -----------------------------------------------------------------------------
+// Metrics recorded by a listener once it's named
+#[derive(Debug, Clone)]
+pub(crate) struct ListenerMetrics {
+    pub(crate) accepted: Counter,
+    pub(crate) errors: Counter,
+}
+
+impl ListenerMetrics {
+    pub(crate) fn new(listener: &str) -> Self {
+        let registry = Registry::global();
+        let labels = &[("listener", listener)];
+        Self {
+            accepted: registry.counter("sonr_listener_accepted_total", "Number of accepted connections", labels),
+            errors: registry.counter("sonr_listener_errors_total", "Number of failed accepts", labels),
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// 		- Metrics endpoint -
+// -----------------------------------------------------------------------------
+const MAX_REQUEST_SIZE: usize = 8 * 1024;
+
+#[derive(Default)]
+struct Exchange {
+    request: Vec<u8>,
+    response: Vec<u8>,
+    written: usize,
+}
+
+// Respond to each request with the snapshot, and close the connection
+#[derive(Default)]
+struct Exporter {
+    exchanges: HashMap<Token, Exchange>,
+}
+
+impl Exporter {
+    fn respond(request: &[u8]) -> Vec<u8> {
+        let (status, body) = if request.starts_with(b"GET ") {
+            ("200 OK", snapshot().to_prometheus())
+        } else {
+            ("405 Method Not Allowed", String::new())
+        };
+
+        format!(
+            "HTTP/1.1 {}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        )
+        .into_bytes()
+    }
+
+    fn exchange(exchange: &mut Exchange, stream: &mut ReactiveTcpStream) -> Handled<()> {
+        if exchange.response.is_empty() {
+            let mut buf = [0u8; 1024];
+            let mut complete = false;
+            while stream.readable() && !complete {
+                match stream.read(&mut buf) {
+                    Ok(0) => return Handled::Close,
+                    Ok(n) => exchange.request.extend_from_slice(&buf[..n]),
+                    Err(ref e) if e.kind() == WouldBlock => break,
+                    Err(_) => return Handled::Close,
+                }
+
+                complete = exchange.request.windows(4).any(|w| w == b"\r\n\r\n");
+                if !complete && exchange.request.len() > MAX_REQUEST_SIZE {
+                    return Handled::Close;
+                }
+            }
+
+            if !complete {
+                return Handled::Continue;
+            }
+            exchange.response = Self::respond(&exchange.request);
+        }
+
+        while exchange.written < exchange.response.len() {
+            match stream.write(&exchange.response[exchange.written..]) {
+                Ok(n) => exchange.written += n,
+                Err(ref e) if e.kind() == WouldBlock => return Handled::Continue,
+                Err(_) => return Handled::Close,
+            }
+        }
+
+        Handled::Close
+    }
+}
+
+impl Handler<ReactiveTcpStream> for Exporter {
+    type Output = ();
+
+    fn handle(&mut self, token: Token, stream: &mut ReactiveTcpStream) -> Handled<()> {
+        let exchange = self.exchanges.entry(token).or_default();
+        let handled = Self::exchange(exchange, stream);
+        if let Handled::Close = handled {
+            self.exchanges.remove(&token);
+        }
+        handled
+    }
+}
+
+/// Serve a [`snapshot`] of the global registry in the Prometheus text format,
+/// over HTTP.
+///
+/// Every `GET` request is answered with the snapshot (regardless of the path),
+/// after which the connection is closed.
+///
+/// [`snapshot`]: fn.snapshot.html
+pub struct MetricsEndpoint {
+    listener: ReactiveTcpListener,
+    connections: Connections<ReactiveTcpStream, Exporter>,
+}
+
+impl MetricsEndpoint {
+    /// Serve the metrics on the listener
+    pub fn new(listener: ReactiveTcpListener) -> Self {
+        Self {
+            listener,
+            connections: Connections::new(Exporter::default()),
+        }
+    }
+
+    /// Serve the metrics on an address, e.g. `"127.0.0.1:9100"`
+    pub fn bind(addr: &str) -> Result<Self> {
+        Ok(Self::new(ReactiveTcpListener::bind(addr)?))
+    }
+
+    fn accept(&mut self, stream: mio::net::TcpStream) {
+        // A stream that can't be registered is dropped
+        if let Ok(stream) = ReactiveTcpStream::new(stream) {
+            let _ = self.connections.react(Reaction::Value(stream));
+        }
+    }
+}
+
+impl Reactor for MetricsEndpoint {
+    type Input = ();
+    type Output = ();
+
+    fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
+        let event = match reaction {
+            Reaction::Event(event) => event,
+            _ => return Reaction::Continue,
+        };
+
+        match self.listener.react(Reaction::Event(event)) {
+            Reaction::Value((stream, _)) => {
+                self.accept(stream);
+                while let Reaction::Value((stream, _)) = self.listener.react(Reaction::Continue) {
+                    self.accept(stream);
+                }
+                Reaction::Continue
+            }
+            Reaction::Event(event) => match self.connections.react(Reaction::Event(event)) {
+                Reaction::Event(event) => Reaction::Event(event),
+                _ => Reaction::Continue,
+            },
+            Reaction::Continue => Reaction::Continue,
+        }
+    }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        self.listener.tokens(tokens);
+        self.connections.tokens(tokens);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_buckets() {
+        let histogram = Histogram::new(&[1.0, 5.0]);
+        histogram.observe(0.5);
+        histogram.observe(1.0);
+        histogram.observe(3.0);
+        histogram.observe(10.0);
+
+        let snapshot = histogram.get();
+        assert_eq!(snapshot.buckets, vec![(1.0, 2), (5.0, 3)]);
+        assert_eq!(snapshot.count, 4);
+        assert_eq!(snapshot.sum, 14.5);
+    }
+
+    #[test]
+    fn test_dropped_metrics_are_removed() {
+        let registry = Registry::new();
+        let counter = registry.counter("dropped", "dropped", &[]);
+        assert!(registry.snapshot().get("dropped", &[]).is_some());
+
+        drop(counter);
+        assert!(registry.snapshot().get("dropped", &[]).is_none());
+    }
+
+    #[test]
+    fn test_prometheus_format() {
+        let registry = Registry::new();
+        let counter = registry.counter("requests_total", "Requests", &[("path", "/\"a\"")]);
+        let gauge = registry.gauge("depth", "Depth", &[]);
+        let histogram = registry.histogram("latency", "Latency", &[("a", "b")], &[0.5]);
+        counter.add(3);
+        gauge.set(-2);
+        histogram.observe(0.25);
+
+        let expected = "\
+# HELP depth Depth
+# TYPE depth gauge
+depth -2
+# HELP latency Latency
+# TYPE latency histogram
+latency_bucket{a=\"b\",le=\"0.5\"} 1
+latency_bucket{a=\"b\",le=\"+Inf\"} 1
+latency_sum{a=\"b\"} 0.25
+latency_count{a=\"b\"} 1
+# HELP requests_total Requests
+# TYPE requests_total counter
+requests_total{path=\"/\\\"a\\\"\"} 3
+";
+        assert_eq!(registry.snapshot().to_prometheus(), expected);
+    }
+}
@@ -0,0 +1,627 @@
+//! Frame based reading and writing on top of a [`Stream`].
+//!
+//! A [`Framed`] owns a read buffer and a write buffer, reads from the stream
+//! when it becomes readable, and outputs each decoded frame as a `Reaction::Value`.
+//!
+//! There are three codecs:
+//! *  [`LineCodec`]
+//! *  [`LengthDelimited`]
+//! *  [`FixedSize`]
+//!
+//! A custom codec implements both [`Decoder`] and [`Encoder`].
+//!
+//! [`Stream`]: ../stream/struct.Stream.html
+//! [`Framed`]: struct.Framed.html
+//! [`LineCodec`]: struct.LineCodec.html
+//! [`LengthDelimited`]: struct.LengthDelimited.html
+//! [`FixedSize`]: struct.FixedSize.html
+//! [`Decoder`]: trait.Decoder.html
+//! [`Encoder`]: trait.Encoder.html
+use std::io::{self, Read, Write};
+use std::io::ErrorKind::WouldBlock;
+
+use mio::Token;
+
+use crate::errors::{Error, Result};
+use crate::net::stream::{Stream, StreamRef};
+use crate::reactor::{Reaction, Reactor, TakeError};
+
+const READ_CHUNK: usize = 4096;
+
+fn invalid_data(msg: &str) -> Error {
+    Error::Io(io::Error::new(io::ErrorKind::InvalidData, msg))
+}
+
+/// Decode frames from a buffer of bytes.
+pub trait Decoder {
+    /// A decoded frame
+    type Item;
+
+    /// Decode the next frame from the start of the buffer, removing the bytes of the
+    /// frame from the buffer.
+    ///
+    /// Returns `Ok(None)` if the buffer does not yet contain a complete frame.
+    fn decode(&mut self, buf: &mut Vec<u8>) -> Result<Option<Self::Item>>;
+}
+
+/// Encode frames into a buffer of bytes.
+pub trait Encoder {
+    /// The frame to encode
+    type Item;
+
+    /// Append the encoded frame to the end of the buffer.
+    fn encode(&mut self, item: Self::Item, buf: &mut Vec<u8>) -> Result<()>;
+}
+
+// -----------------------------------------------------------------------------
+// 		- Framed -
+// -----------------------------------------------------------------------------
+/// Read and write frames on a stream using a codec.
+///
+/// When the stream is readable the `Framed` reads until a frame can be decoded
+/// (or the stream would block), and outputs the frame as a `Reaction::Value`.
+/// Reacting to `Reaction::Continue` outputs the next frame, which makes
+/// it possible to drive a `Framed` from a [`Connections`] handler.
+///
+/// The frame is decoded after each read, so the read buffer never holds much
+/// more than one frame, and a codec with a limit (e.g. [`LineCodec::with_max_length`])
+/// errors as soon as the limit is exceeded.
+/// Frames received as `Reaction::Value` are encoded and written to the stream.
+///
+/// Once the peer closes the stream, or the stream errors, the `Framed` is closed and
+/// should be dropped (see [`is_closed`]).
+///
+///```
+/// # use sonr::prelude::*;
+/// # use sonr::errors::Result;
+/// use sonr::net::tcp::{ReactiveTcpStream, TcpStream};
+/// use sonr::net::codec::{Framed, LineCodec};
+///
+/// fn echo(stream: TcpStream) -> Result<Framed<ReactiveTcpStream, LineCodec>> {
+///     let stream = ReactiveTcpStream::new(stream)?;
+///     Ok(Framed::new(stream, LineCodec::new()))
+/// }
+/// # fn main() {
+/// # }
+///```
+///
+/// [`is_closed`]: struct.Framed.html#method.is_closed
+/// [`Connections`]: ../connections/struct.Connections.html
+/// [`LineCodec::with_max_length`]: struct.LineCodec.html#method.with_max_length
+pub struct Framed<S, C> {
+    stream: S,
+    codec: C,
+    read_buf: Vec<u8>,
+    write_buf: Vec<u8>,
+    closed: bool,
+    error: Option<Error>,
+}
+
+impl<S, C> Framed<S, C>
+where
+    S: StreamRef + Read + Write,
+    C: Decoder + Encoder,
+{
+    /// Create a new `Framed` from a stream and a codec.
+    pub fn new(stream: S, codec: C) -> Self {
+        Self {
+            stream,
+            codec,
+            read_buf: Vec::new(),
+            write_buf: Vec::new(),
+            closed: false,
+            error: None,
+        }
+    }
+
+    /// Encode the frame and write it to the stream.
+    /// Anything that can't be written before the stream blocks is
+    /// written once the stream becomes writable again.
+    pub fn send(&mut self, item: <C as Encoder>::Item) -> Result<()> {
+        self.codec.encode(item, &mut self.write_buf)?;
+        self.flush();
+        Ok(())
+    }
+
+    /// Write as much of the write buffer as possible.
+    pub fn flush(&mut self) {
+        while self.stream.stream_ref().writable() && !self.write_buf.is_empty() {
+            match self.stream.write(&self.write_buf) {
+                Ok(0) => {
+                    self.closed = true;
+                    break;
+                }
+                Ok(n) => { self.write_buf.drain(..n); }
+                Err(ref e) if e.kind() == WouldBlock => break,
+                Err(e) => {
+                    self.close(e.into());
+                    break;
+                }
+            }
+        }
+
+        // Streams that buffer writes of their own (e.g. a `TlsStream`)
+        // write out what they can.
+        match self.stream.flush() {
+            Err(ref e) if e.kind() == WouldBlock => {}
+            Err(e) => self.close(e.into()),
+            Ok(()) => {}
+        }
+    }
+
+    // Read a chunk, unless the stream would block.
+    // Returns `true` if anything was read.
+    fn fill(&mut self) -> bool {
+        if self.closed || !self.stream.stream_ref().readable() {
+            return false;
+        }
+
+        let mut chunk = [0u8; READ_CHUNK];
+        match self.stream.read(&mut chunk) {
+            Ok(0) => {
+                self.closed = true;
+                false
+            }
+            Ok(n) => {
+                self.read_buf.extend_from_slice(&chunk[..n]);
+                true
+            }
+            Err(ref e) if e.kind() == WouldBlock => false,
+            Err(e) => {
+                self.close(e.into());
+                false
+            }
+        }
+    }
+
+    // Decode the next frame, reading more only if the buffer
+    // doesn't hold a complete frame.
+    fn next_frame(&mut self) -> Reaction<<C as Decoder>::Item> {
+        loop {
+            if self.error.is_some() {
+                return Reaction::Continue;
+            }
+
+            match self.codec.decode(&mut self.read_buf) {
+                Ok(Some(frame)) => return Reaction::Value(frame),
+                Ok(None) => {}
+                Err(e) => {
+                    self.close(e);
+                    return Reaction::Continue;
+                }
+            }
+
+            if !self.fill() {
+                return Reaction::Continue;
+            }
+        }
+    }
+
+    fn close(&mut self, error: Error) {
+        self.closed = true;
+        self.error = Some(error);
+    }
+
+    /// `true` if the stream was closed by the peer or failed.
+    /// Frames that were already read can still be decoded.
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// The error that closed the stream, if any.
+    pub fn take_error(&mut self) -> Option<Error> {
+        self.error.take()
+    }
+
+    /// The `Token` of the underlying stream
+    pub fn token(&self) -> Token {
+        self.stream.token()
+    }
+
+    /// Bytes waiting to be written
+    pub fn pending_writes(&self) -> usize {
+        self.write_buf.len()
+    }
+
+    /// Bytes read but not yet decoded
+    pub fn pending_reads(&self) -> usize {
+        self.read_buf.len()
+    }
+
+    /// Reference to the codec
+    pub fn codec(&self) -> &C {
+        &self.codec
+    }
+
+    /// Mutable reference to the codec
+    pub fn codec_mut(&mut self) -> &mut C {
+        &mut self.codec
+    }
+
+    /// Consume the `Framed` and return the underlying stream.
+    /// Any buffered data is lost.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<S, C> StreamRef for Framed<S, C>
+where
+    S: StreamRef,
+{
+    type Evented = S::Evented;
+
+    fn stream_ref(&self) -> &Stream<Self::Evented> {
+        self.stream.stream_ref()
+    }
+
+    fn stream_mut(&mut self) -> &mut Stream<Self::Evented> {
+        self.stream.stream_mut()
+    }
+}
+
+impl<S, C> Reactor for Framed<S, C>
+where
+    S: StreamRef + Read + Write,
+    C: Decoder + Encoder,
+{
+    type Input = <C as Encoder>::Item;
+    type Output = <C as Decoder>::Item;
+
+    fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
+        match reaction {
+            Reaction::Event(event) => {
+                if event.token() != self.token() {
+                    return event.into();
+                }
+
+                self.stream.stream_mut().react(event.into());
+                self.flush();
+                self.next_frame()
+            }
+            Reaction::Value(item) => {
+                if let Err(e) = self.send(item) {
+                    self.close(e);
+                }
+                Reaction::Continue
+            }
+            Reaction::Continue => {
+                self.flush();
+                self.next_frame()
+            }
+        }
+    }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        tokens.push(self.token());
+    }
+}
+
+impl<S, C> TakeError for Framed<S, C>
+where
+    S: StreamRef + Read + Write,
+    C: Decoder + Encoder,
+{
+    fn take_error(&mut self) -> Option<Error> {
+        self.error.take()
+    }
+}
+
+// -----------------------------------------------------------------------------
+// 		- Line codec -
+// -----------------------------------------------------------------------------
+/// Newline delimited utf8 strings.
+///
+/// Decoded lines do not include the trailing `\n` (or `\r\n`),
+/// and a newline is appended to each encoded line.
+#[derive(Debug, Clone, Default)]
+pub struct LineCodec {
+    max_length: Option<usize>,
+    searched: usize,
+}
+
+impl LineCodec {
+    /// Create a new `LineCodec` without a maximum line length.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new `LineCodec` where lines longer than `max_length`
+    /// (excluding the newline) are treated as an error.
+    pub fn with_max_length(max_length: usize) -> Self {
+        Self {
+            max_length: Some(max_length),
+            searched: 0,
+        }
+    }
+}
+
+impl Decoder for LineCodec {
+    type Item = String;
+
+    fn decode(&mut self, buf: &mut Vec<u8>) -> Result<Option<String>> {
+        // Don't search the same bytes twice
+        let newline = buf[self.searched..].iter().position(|b| *b == b'\n');
+
+        let pos = match newline {
+            Some(pos) => self.searched + pos,
+            None => {
+                self.searched = buf.len();
+                if let Some(max) = self.max_length {
+                    if buf.len() > max {
+                        return Err(invalid_data("line too long"));
+                    }
+                }
+                return Ok(None);
+            }
+        };
+
+        self.searched = 0;
+        let mut line: Vec<u8> = buf.drain(..=pos).collect();
+        line.pop();
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+
+        if let Some(max) = self.max_length {
+            if line.len() > max {
+                return Err(invalid_data("line too long"));
+            }
+        }
+
+        Ok(Some(String::from_utf8(line)?))
+    }
+}
+
+impl Encoder for LineCodec {
+    type Item = String;
+
+    fn encode(&mut self, item: String, buf: &mut Vec<u8>) -> Result<()> {
+        buf.extend_from_slice(item.as_bytes());
+        buf.push(b'\n');
+        Ok(())
+    }
+}
+
+// -----------------------------------------------------------------------------
+// 		- Length delimited codec -
+// -----------------------------------------------------------------------------
+/// The size and byte order of the length prefix of a [`LengthDelimited`] frame.
+///
+/// [`LengthDelimited`]: struct.LengthDelimited.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthField {
+    /// u16, big endian
+    U16Be,
+    /// u16, little endian
+    U16Le,
+    /// u32, big endian
+    U32Be,
+    /// u32, little endian
+    U32Le,
+}
+
+impl LengthField {
+    fn size(self) -> usize {
+        match self {
+            LengthField::U16Be | LengthField::U16Le => 2,
+            LengthField::U32Be | LengthField::U32Le => 4,
+        }
+    }
+
+    fn max(self) -> usize {
+        match self {
+            LengthField::U16Be | LengthField::U16Le => u16::MAX as usize,
+            LengthField::U32Be | LengthField::U32Le => u32::MAX as usize,
+        }
+    }
+
+    fn read(self, buf: &[u8]) -> usize {
+        match self {
+            LengthField::U16Be => u16::from_be_bytes([buf[0], buf[1]]) as usize,
+            LengthField::U16Le => u16::from_le_bytes([buf[0], buf[1]]) as usize,
+            LengthField::U32Be => u32::from_be_bytes([buf[0], buf[1], buf[2], buf[3]]) as usize,
+            LengthField::U32Le => u32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]) as usize,
+        }
+    }
+
+    fn write(self, len: usize, buf: &mut Vec<u8>) {
+        match self {
+            LengthField::U16Be => buf.extend_from_slice(&(len as u16).to_be_bytes()),
+            LengthField::U16Le => buf.extend_from_slice(&(len as u16).to_le_bytes()),
+            LengthField::U32Be => buf.extend_from_slice(&(len as u32).to_be_bytes()),
+            LengthField::U32Le => buf.extend_from_slice(&(len as u32).to_le_bytes()),
+        }
+    }
+}
+
+/// Frames prefixed with their length.
+///
+/// The length prefix does not include the size of the prefix itself.
+#[derive(Debug, Clone)]
+pub struct LengthDelimited {
+    field: LengthField,
+    max_frame_length: usize,
+}
+
+impl LengthDelimited {
+    /// Create a new `LengthDelimited` codec.
+    /// The maximum frame length is the largest value the length field can hold.
+    pub fn new(field: LengthField) -> Self {
+        Self {
+            field,
+            max_frame_length: field.max(),
+        }
+    }
+
+    /// Set the maximum frame length.
+    /// Frames longer than `max` are treated as an error.
+    pub fn max_frame_length(mut self, max: usize) -> Self {
+        self.max_frame_length = max.min(self.field.max());
+        self
+    }
+}
+
+impl Decoder for LengthDelimited {
+    type Item = Vec<u8>;
+
+    fn decode(&mut self, buf: &mut Vec<u8>) -> Result<Option<Vec<u8>>> {
+        let prefix = self.field.size();
+        if buf.len() < prefix {
+            return Ok(None);
+        }
+
+        let len = self.field.read(buf);
+        if len > self.max_frame_length {
+            return Err(invalid_data("frame too large"));
+        }
+
+        if buf.len() < prefix + len {
+            return Ok(None);
+        }
+
+        let frame = buf[prefix..prefix + len].to_vec();
+        buf.drain(..prefix + len);
+        Ok(Some(frame))
+    }
+}
+
+impl Encoder for LengthDelimited {
+    type Item = Vec<u8>;
+
+    fn encode(&mut self, item: Vec<u8>, buf: &mut Vec<u8>) -> Result<()> {
+        if item.len() > self.max_frame_length {
+            return Err(invalid_data("frame too large"));
+        }
+
+        self.field.write(item.len(), buf);
+        buf.extend_from_slice(&item);
+        Ok(())
+    }
+}
+
+// -----------------------------------------------------------------------------
+// 		- Fixed size codec -
+// -----------------------------------------------------------------------------
+/// Frames of a fixed number of bytes.
+#[derive(Debug, Clone)]
+pub struct FixedSize {
+    size: usize,
+}
+
+impl FixedSize {
+    /// Create a new `FixedSize` codec with frames of `size` bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero, as an empty frame would be decoded forever
+    /// without consuming anything.
+    pub fn new(size: usize) -> Self {
+        assert!(size > 0, "frame size must be greater than zero");
+        Self { size }
+    }
+}
+
+impl Decoder for FixedSize {
+    type Item = Vec<u8>;
+
+    fn decode(&mut self, buf: &mut Vec<u8>) -> Result<Option<Vec<u8>>> {
+        if buf.len() < self.size {
+            return Ok(None);
+        }
+
+        Ok(Some(buf.drain(..self.size).collect()))
+    }
+}
+
+impl Encoder for FixedSize {
+    type Item = Vec<u8>;
+
+    fn encode(&mut self, item: Vec<u8>, buf: &mut Vec<u8>) -> Result<()> {
+        if item.len() != self.size {
+            return Err(Error::Io(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "frame size does not match",
+            )));
+        }
+
+        buf.extend_from_slice(&item);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_lines() {
+        let mut codec = LineCodec::new();
+        let mut buf = b"first\r\nsecond\nthi".to_vec();
+        assert_eq!(codec.decode(&mut buf).unwrap(), Some("first".to_string()));
+        assert_eq!(codec.decode(&mut buf).unwrap(), Some("second".to_string()));
+        assert_eq!(codec.decode(&mut buf).unwrap(), None);
+        buf.extend_from_slice(b"rd\n");
+        assert_eq!(codec.decode(&mut buf).unwrap(), Some("third".to_string()));
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn line_too_long() {
+        let mut codec = LineCodec::with_max_length(4);
+        let mut buf = b"too long".to_vec();
+        assert!(codec.decode(&mut buf).is_err());
+    }
+
+    #[test]
+    fn length_delimited_round_trip() {
+        for field in &[LengthField::U16Be, LengthField::U16Le, LengthField::U32Be, LengthField::U32Le] {
+            let mut codec = LengthDelimited::new(*field);
+            let mut buf = Vec::new();
+            codec.encode(b"hello".to_vec(), &mut buf).unwrap();
+            codec.encode(b"world".to_vec(), &mut buf).unwrap();
+            assert_eq!(buf.len(), (field.size() + 5) * 2);
+
+            let mut partial = buf.split_off(3);
+            assert_eq!(codec.decode(&mut buf).unwrap(), None);
+            buf.append(&mut partial);
+            assert_eq!(codec.decode(&mut buf).unwrap(), Some(b"hello".to_vec()));
+            assert_eq!(codec.decode(&mut buf).unwrap(), Some(b"world".to_vec()));
+            assert!(buf.is_empty());
+        }
+    }
+
+    #[test]
+    fn length_delimited_byte_order() {
+        let mut buf = Vec::new();
+        LengthDelimited::new(LengthField::U16Be).encode(vec![0; 2], &mut buf).unwrap();
+        assert_eq!(&buf[..2], &[0, 2]);
+
+        let mut buf = Vec::new();
+        LengthDelimited::new(LengthField::U32Le).encode(vec![0; 2], &mut buf).unwrap();
+        assert_eq!(&buf[..4], &[2, 0, 0, 0]);
+    }
+
+    #[test]
+    fn length_delimited_too_large() {
+        let mut codec = LengthDelimited::new(LengthField::U16Be).max_frame_length(3);
+        let mut buf = vec![0, 4, 1, 2, 3, 4];
+        assert!(codec.decode(&mut buf).is_err());
+        assert!(codec.encode(vec![0; 4], &mut buf).is_err());
+    }
+
+    #[test]
+    fn fixed_size() {
+        let mut codec = FixedSize::new(2);
+        let mut buf = vec![1, 2, 3];
+        assert_eq!(codec.decode(&mut buf).unwrap(), Some(vec![1, 2]));
+        assert_eq!(codec.decode(&mut buf).unwrap(), None);
+        assert!(codec.encode(vec![1], &mut buf).is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn fixed_size_zero() {
+        FixedSize::new(0);
+    }
+}
@@ -0,0 +1,389 @@
+//! Connect to remote addresses.
+//!
+//! See [`TcpConnector`].
+//!
+//! [`TcpConnector`]: struct.TcpConnector.html
+use std::collections::VecDeque;
+use std::error::Error as StdError;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::io;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+use mio::{Ready, Token};
+
+use crate::errors::Error;
+use crate::net::stream;
+use crate::net::tcp::ReactiveTcpStream;
+use crate::reactor::timer::TimerToken;
+use crate::reactor::{EventedReactor, Reaction, Reactor};
+
+/// Why a connection could not be established
+#[derive(Debug)]
+pub enum ConnectError {
+    /// There were no addresses to connect to
+    NoAddresses,
+
+    /// The connection was not established before the connect timeout.
+    /// Contains the errors of the attempts that failed before the timeout.
+    TimedOut(Vec<(SocketAddr, io::Error)>),
+
+    /// The connection failed on every address, with the error of each attempt
+    Failed(Vec<(SocketAddr, io::Error)>),
+}
+
+impl Display for ConnectError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ConnectError::NoAddresses => write!(f, "no addresses to connect to"),
+            ConnectError::TimedOut(_) => write!(f, "connect timed out"),
+            ConnectError::Failed(errors) => {
+                write!(f, "connect failed")?;
+                for (addr, e) in errors {
+                    write!(f, "; {}: {}", addr, e)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl StdError for ConnectError {}
+
+/// The outcome of connecting: a connected stream or the reason the connection failed
+pub type Connect = std::result::Result<ReactiveTcpStream, ConnectError>;
+
+// Errors that are not io errors (e.g. no tokens left) are reported as io errors,
+// so each failed attempt has the same type of error.
+fn io_error(e: Error) -> io::Error {
+    match e {
+        Error::Io(e) => e,
+        e => io::Error::other(format!("{:?}", e)),
+    }
+}
+
+// A socket waiting for the connection to complete
+struct Attempt {
+    addr: SocketAddr,
+    socket: EventedReactor<mio::net::TcpStream>,
+}
+
+// Connect to one of a number of addresses
+struct Request {
+    remaining: VecDeque<SocketAddr>,
+    attempts: Vec<Attempt>,
+    errors: Vec<(SocketAddr, io::Error)>,
+    timer: TimerToken,
+    deadline: Option<Instant>,
+    // `None` if the next attempt waits for the current ones to fail
+    next_attempt: Option<Instant>,
+}
+
+impl Request {
+    fn owns(&self, token: Token) -> bool {
+        self.timer.token() == token || self.attempts.iter().any(|a| a.socket.token() == token)
+    }
+
+    // Start the next attempt, skipping addresses that fail right away
+    fn start_next(&mut self, attempt_delay: Duration) {
+        while let Some(addr) = self.remaining.pop_front() {
+            let socket = mio::net::TcpStream::connect(&addr)
+                .map_err(Error::from)
+                .and_then(|stream| EventedReactor::new(stream, Ready::readable() | Ready::writable()));
+
+            match socket {
+                Ok(socket) => {
+                    self.attempts.push(Attempt { addr, socket });
+                    self.next_attempt = Instant::now().checked_add(attempt_delay);
+                    break;
+                }
+                Err(e) => self.errors.push((addr, io_error(e))),
+            }
+        }
+        self.schedule();
+    }
+
+    // Wake up at the connect timeout, or when the next attempt is due
+    fn schedule(&self) {
+        let next_attempt = match self.remaining.is_empty() {
+            true => None,
+            false => self.next_attempt,
+        };
+        let wake_up = match (self.deadline, next_attempt) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+
+        match wake_up {
+            Some(wake_up) => self.timer.schedule(wake_up),
+            None => self.timer.cancel(),
+        }
+    }
+
+    // React to an event for one of the tokens of the request,
+    // returning the outcome once the request is done.
+    fn react(&mut self, token: Token, readiness: Ready, attempt_delay: Duration) -> Option<Connect> {
+        if token == self.timer.token() {
+            let now = Instant::now();
+            if self.deadline.is_some_and(|deadline| deadline <= now) {
+                return Some(Err(ConnectError::TimedOut(self.errors.drain(..).collect())));
+            }
+            if !self.remaining.is_empty() && matches!(self.next_attempt, Some(next) if next <= now) {
+                self.start_next(attempt_delay);
+            } else {
+                self.schedule();
+            }
+            return self.failed();
+        }
+
+        let index = self.attempts.iter().position(|a| a.socket.token() == token)?;
+        let socket = self.attempts[index].socket.inner();
+        let error = match stream::is_connected(socket.take_error(), socket.peer_addr()) {
+            Ok(true) => None,
+            Ok(false) => return None,
+            Err(e) => Some(e),
+        };
+
+        let attempt = self.attempts.remove(index);
+        match error {
+            None => {
+                let mut socket = attempt.socket;
+                socket.is_writable = true;
+                socket.is_readable = readiness.is_readable();
+                Some(Ok(ReactiveTcpStream::from(socket)))
+            }
+            Some(e) => {
+                self.errors.push((attempt.addr, e));
+                // Don't wait for the attempt delay once an attempt fails
+                self.start_next(attempt_delay);
+                self.failed()
+            }
+        }
+    }
+
+    fn failed(&mut self) -> Option<Connect> {
+        match self.attempts.is_empty() && self.remaining.is_empty() {
+            true => Some(Err(ConnectError::Failed(self.errors.drain(..).collect()))),
+            false => None,
+        }
+    }
+}
+
+// Alternate between address families, starting with the family of the first address
+fn interleave(addrs: Vec<SocketAddr>) -> VecDeque<SocketAddr> {
+    let first_v6 = addrs.first().is_some_and(|addr| addr.is_ipv6());
+    let (mut first, mut second): (VecDeque<_>, VecDeque<_>) = addrs.into_iter().partition(|addr| addr.is_ipv6() == first_v6);
+
+    let mut interleaved = VecDeque::with_capacity(first.len() + second.len());
+    loop {
+        match (first.pop_front(), second.pop_front()) {
+            (None, None) => break interleaved,
+            (a, b) => interleaved.extend(a.into_iter().chain(b)),
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// 		- Tcp connector -
+// -----------------------------------------------------------------------------
+/// Connect to the addresses received as input, and output a connected
+/// [`ReactiveTcpStream`] or a [`ConnectError`].
+///
+/// A connection is established once the socket becomes writable and
+/// no error is reported for the socket (`SO_ERROR`).
+///
+/// Each input is a list of addresses for the same destination (e.g. the resolved
+/// addresses of a host name). The addresses are tried "happy eyeballs" style:
+/// alternating between IPv6 and IPv4, the next address is tried if the current
+/// attempt hasn't completed within the attempt delay (or as soon as an attempt
+/// fails), and the first connection to complete wins.
+///
+///```no_run
+/// # use std::io::Write;
+/// # use std::time::Duration;
+/// # use sonr::prelude::*;
+/// # use sonr::errors::Result;
+/// use sonr::net::connect::{Connect, TcpConnector};
+/// use sonr::reactor::producers::Mono;
+///
+/// fn main() -> Result<()> {
+///     System::init()?;
+///
+///     let addrs = vec!["[::1]:8000".parse()?, "127.0.0.1:8000".parse()?];
+///     let connector = TcpConnector::new().timeout(Duration::from_secs(2));
+///
+///     let run = Mono::new(addrs)?.chain(connector).map(|connect: Connect| {
+///         match connect {
+///             Ok(mut stream) => { let _ = stream.write(b"hello"); }
+///             Err(e) => eprintln!("{}", e),
+///         }
+///         System::send(SystemEvent::Stop);
+///     });
+///
+///     System::start(run)?;
+///     Ok(())
+/// }
+/// ```
+///
+/// [`ReactiveTcpStream`]: ../tcp/type.ReactiveTcpStream.html
+/// [`ConnectError`]: enum.ConnectError.html
+pub struct TcpConnector {
+    requests: Vec<Request>,
+    done: VecDeque<Connect>,
+    timeout: Option<Duration>,
+    attempt_delay: Duration,
+}
+
+impl Default for TcpConnector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TcpConnector {
+    /// Create a connector with a connect timeout of ten seconds,
+    /// and an attempt delay of 250 ms.
+    pub fn new() -> Self {
+        Self {
+            requests: Vec::new(),
+            done: VecDeque::new(),
+            timeout: Some(Duration::from_secs(10)),
+            attempt_delay: Duration::from_millis(250),
+        }
+    }
+
+    /// Give up on connecting after `timeout`, across all addresses.
+    /// A timeout too large to represent (e.g. `Duration::MAX`) never expires.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Never give up on connecting (the operating system's own timeout still applies)
+    pub fn no_timeout(mut self) -> Self {
+        self.timeout = None;
+        self
+    }
+
+    /// How long to wait for an attempt to complete before trying the next address.
+    /// With a delay too large to represent the next address is only tried
+    /// once the current attempt fails.
+    pub fn attempt_delay(mut self, delay: Duration) -> Self {
+        self.attempt_delay = delay;
+        self
+    }
+
+    /// Number of connections in progress
+    pub fn len(&self) -> usize {
+        self.requests.len()
+    }
+
+    /// Returns `true` if no connections are in progress
+    pub fn is_empty(&self) -> bool {
+        self.requests.is_empty()
+    }
+
+    fn connect(&mut self, addrs: Vec<SocketAddr>) {
+        if addrs.is_empty() {
+            self.done.push_back(Err(ConnectError::NoAddresses));
+            return;
+        }
+
+        let timer = match TimerToken::new() {
+            Ok(timer) => timer,
+            Err(e) => {
+                self.done.push_back(Err(ConnectError::Failed(vec![(addrs[0], io_error(e))])));
+                return;
+            }
+        };
+
+        let now = Instant::now();
+        let mut request = Request {
+            remaining: interleave(addrs),
+            attempts: Vec::new(),
+            errors: Vec::new(),
+            timer,
+            deadline: self.timeout.and_then(|timeout| now.checked_add(timeout)),
+            next_attempt: Some(now),
+        };
+
+        request.start_next(self.attempt_delay);
+        match request.failed() {
+            Some(failed) => self.done.push_back(failed),
+            None => self.requests.push(request),
+        }
+    }
+
+    fn next_done(&mut self) -> Reaction<Connect> {
+        match self.done.pop_front() {
+            Some(connect) => Reaction::Value(connect),
+            None => Reaction::Continue,
+        }
+    }
+}
+
+impl Reactor for TcpConnector {
+    type Input = Vec<SocketAddr>;
+    type Output = Connect;
+
+    fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
+        match reaction {
+            // Outcomes are output once the connector reacts to `Reaction::Continue`
+            // (the output of reacting to a value is discarded when chained).
+            Reaction::Value(addrs) => {
+                self.connect(addrs);
+                Reaction::Continue
+            }
+            Reaction::Event(event) => {
+                let token = event.token();
+                let index = match self.requests.iter().position(|request| request.owns(token)) {
+                    Some(index) => index,
+                    None => return Reaction::Event(event),
+                };
+
+                let attempt_delay = self.attempt_delay;
+                if let Some(connect) = self.requests[index].react(token, event.readiness(), attempt_delay) {
+                    // Dropping the request drops the remaining attempts
+                    self.requests.swap_remove(index);
+                    self.done.push_back(connect);
+                }
+                self.next_done()
+            }
+            Reaction::Continue => self.next_done(),
+        }
+    }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        for request in &self.requests {
+            tokens.push(request.timer.token());
+            tokens.extend(request.attempts.iter().map(|attempt| attempt.socket.token()));
+        }
+    }
+}
+
+impl Debug for TcpConnector {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("TcpConnector")
+            .field("connecting", &self.requests.len())
+            .field("timeout", &self.timeout)
+            .field("attempt_delay", &self.attempt_delay)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interleave() {
+        let addrs: Vec<SocketAddr> = vec![
+            "[::1]:1".parse().unwrap(),
+            "[::1]:2".parse().unwrap(),
+            "[::1]:3".parse().unwrap(),
+            "127.0.0.1:4".parse().unwrap(),
+        ];
+        let ports: Vec<u16> = interleave(addrs).iter().map(|addr| addr.port()).collect();
+        assert_eq!(ports, vec![1, 4, 2, 3]);
+    }
+}
@@ -0,0 +1,332 @@
+//! Manage a collection of connections.
+//!
+//! See [`Connections`].
+//!
+//! [`Connections`]: struct.Connections.html
+use std::panic::{self, AssertUnwindSafe};
+
+use mio::{Event, Token};
+
+use crate::PreVec;
+use crate::errors::{Error, Result};
+use crate::net::stream::StreamRef;
+use crate::reactor::{Panic, PanicHook, Reaction, Reactor, TakeError};
+use crate::system::System;
+
+/// The result of handling a connection.
+#[derive(Debug)]
+pub enum Handled<T> {
+    /// Output a value, tagged with the connection token.
+    /// The handler is called again until it returns
+    /// `Handled::Continue` or `Handled::Close`.
+    Value(T),
+
+    /// Nothing more to do until the next event
+    Continue,
+
+    /// Close and remove the connection
+    Close,
+}
+
+/// Handle events for the connections in a [`Connections`].
+///
+/// `Handler` is implemented for every `FnMut(Token, &mut S) -> Handled<T>`.
+///
+/// [`Connections`]: struct.Connections.html
+pub trait Handler<S> {
+    /// Output of the handler
+    type Output;
+
+    /// Called once the stream has reacted to an event, and again after
+    /// each `Handled::Value` until the handler returns
+    /// `Handled::Continue` or `Handled::Close`.
+    fn handle(&mut self, token: Token, stream: &mut S) -> Handled<Self::Output>;
+}
+
+impl<S, F, T> Handler<S> for F
+where
+    F: FnMut(Token, &mut S) -> Handled<T>,
+{
+    type Output = T;
+
+    fn handle(&mut self, token: Token, stream: &mut S) -> Handled<T> {
+        (self)(token, stream)
+    }
+}
+
+// -----------------------------------------------------------------------------
+// 		- Connections -
+// -----------------------------------------------------------------------------
+/// A collection of connections, stored by their `Token`.
+///
+/// Streams are received as input. When an event belongs to one of the streams,
+/// the stream reacts to the event and the handler is called with the stream.
+/// Every value returned by the handler is output together with the token of the
+/// connection.
+///
+/// A connection is removed (and the stream dropped) when the handler returns
+/// `Handled::Close`, or when the stream has timed out (see [`Stream::timed_out`]).
+///
+/// Events that don't belong to any of the connections are passed on.
+///
+/// A stream that can't be stored is dropped, and the error is available
+/// through [`TakeError::take_error`].
+///
+/// When the [`System`] is shutting down, the handler is called once for every connection
+/// (see [`System::is_shutting_down`]) before the shutdown notification is passed on,
+/// giving each connection a chance to flush and close.
+///
+///```no_run
+/// # use std::io::{Read, Write};
+/// # use std::io::ErrorKind::WouldBlock;
+/// # use sonr::prelude::*;
+/// # use sonr::errors::Result;
+/// use sonr::net::tcp::{ReactiveTcpListener, ReactiveTcpStream};
+/// use sonr::net::connections::{Connections, Handled};
+///
+/// fn main() -> Result<()> {
+///     System::init()?;
+///
+///     let listener = ReactiveTcpListener::bind("127.0.0.1:8000")?
+///         .map(|(stream, _)| ReactiveTcpStream::new(stream).unwrap());
+///
+///     // Echo everything back
+///     let connections = Connections::new(|_, stream: &mut ReactiveTcpStream| {
+///         let mut buf = [0u8; 1024];
+///         while stream.readable() {
+///             match stream.read(&mut buf) {
+///                 Ok(0) => return Handled::Close,
+///                 Ok(n) => { let _ = stream.write(&buf[..n]); }
+///                 Err(ref e) if e.kind() == WouldBlock => break,
+///                 Err(_) => return Handled::Close,
+///             }
+///         }
+///         Handled::<()>::Continue
+///     });
+///
+///     System::start(listener.chain(connections))?;
+///     Ok(())
+/// }
+/// ```
+///
+/// [`Stream::timed_out`]: ../stream/struct.Stream.html#method.timed_out
+/// [`TakeError::take_error`]: ../../reactor/trait.TakeError.html#tymethod.take_error
+/// [`System`]: ../../system/struct.System.html
+/// [`System::is_shutting_down`]: ../../system/struct.System.html#method.is_shutting_down
+pub struct Connections<S, H> {
+    streams: PreVec<S>,
+    handler: H,
+    current: Option<Token>,
+    pending: Vec<Token>,
+    shutdown: Option<Event>,
+    on_panic: Option<PanicHook>,
+    error: Option<Error>,
+}
+
+impl<S, H> Connections<S, H>
+where
+    S: StreamRef,
+    H: Handler<S>,
+{
+    /// Create a new `Connections` with a handler.
+    pub fn new(handler: H) -> Self {
+        Self {
+            streams: PreVec::with_capacity(1024),
+            handler,
+            current: None,
+            pending: Vec::new(),
+            shutdown: None,
+            on_panic: None,
+            error: None,
+        }
+    }
+
+    /// Catch panics in the handler.
+    /// The connection the handler panicked on is closed, and the panic is
+    /// passed to `hook`, rather than unwinding and taking down every connection.
+    ///
+    /// To catch panics in any reactor see [`Reactor::isolate`].
+    ///
+    /// [`Reactor::isolate`]: ../../reactor/trait.Reactor.html#method.isolate
+    pub fn drop_on_panic<F: FnMut(&Panic) + 'static>(mut self, hook: F) -> Self {
+        self.on_panic = Some(Box::new(hook));
+        self
+    }
+
+    /// Insert a stream.
+    /// Any existing stream with the same token is returned.
+    /// If the stream can't be stored it is dropped, and an error is returned.
+    pub fn insert(&mut self, stream: S) -> Result<Option<S>> {
+        let token = stream.token();
+        self.streams.insert_at(token.0, stream)
+    }
+
+    /// Remove a stream
+    pub fn remove(&mut self, token: Token) -> Option<S> {
+        if self.current == Some(token) {
+            self.current = None;
+        }
+
+        self.streams.get(token.0)?;
+        self.streams.remove(token.0)
+    }
+
+    // Handle the connections waiting for the shutdown notification,
+    // then pass the notification on.
+    fn next_pending(&mut self) -> Reaction<(Token, H::Output)> {
+        while let Some(token) = self.pending.pop() {
+            if let Reaction::Value(val) = self.handle(token) {
+                return Reaction::Value(val);
+            }
+        }
+
+        match self.shutdown.take() {
+            Some(event) => Reaction::Event(event),
+            None => Reaction::Continue,
+        }
+    }
+
+    /// Get a reference to a stream
+    pub fn get(&self, token: Token) -> Option<&S> {
+        self.streams.get(token.0)
+    }
+
+    /// Get a mutable reference to a stream
+    pub fn get_mut(&mut self, token: Token) -> Option<&mut S> {
+        self.streams.get_mut(token.0)
+    }
+
+    /// Number of connections
+    pub fn len(&self) -> usize {
+        self.streams.len()
+    }
+
+    /// Returns `true` if there are no connections
+    pub fn is_empty(&self) -> bool {
+        self.streams.is_empty()
+    }
+
+    fn handle(&mut self, token: Token) -> Reaction<(Token, H::Output)> {
+        let handled = match self.streams.get_mut(token.0) {
+            Some(stream) => {
+                let handler = &mut self.handler;
+                let handled = match self.on_panic {
+                    Some(ref mut hook) => {
+                        match panic::catch_unwind(AssertUnwindSafe(|| handler.handle(token, stream))) {
+                            Ok(handled) => handled,
+                            Err(payload) => {
+                                hook(&Panic::new(payload.as_ref(), Some(token)));
+                                Handled::Close
+                            }
+                        }
+                    }
+                    None => handler.handle(token, stream),
+                };
+
+                match handled {
+                    Handled::Continue if stream.stream_ref().timed_out().is_some() => Handled::Close,
+                    handled => handled,
+                }
+            }
+            None => Handled::Continue,
+        };
+
+        match handled {
+            Handled::Value(val) => {
+                self.current = Some(token);
+                Reaction::Value((token, val))
+            }
+            Handled::Continue => {
+                self.current = None;
+                Reaction::Continue
+            }
+            Handled::Close => {
+                self.remove(token);
+                Reaction::Continue
+            }
+        }
+    }
+}
+
+impl<S, H> Reactor for Connections<S, H>
+where
+    S: StreamRef,
+    H: Handler<S>,
+{
+    type Input = S;
+    type Output = (Token, H::Output);
+
+    fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
+        match reaction {
+            Reaction::Value(stream) => {
+                if let Err(e) = self.insert(stream) {
+                    self.error = Some(e);
+                }
+                Reaction::Continue
+            }
+            Reaction::Event(event) if System::is_shutdown(&event) => {
+                self.pending = self.streams.iter().map(|(index, _)| Token(index)).collect();
+                self.shutdown = Some(event);
+                self.next_pending()
+            }
+            Reaction::Event(event) => {
+                let token = event.token();
+                match self.streams.get_mut(token.0) {
+                    Some(stream) => {
+                        stream.stream_mut().react(event.into());
+                        self.handle(token)
+                    }
+                    None => event.into(),
+                }
+            }
+            Reaction::Continue => {
+                if let Some(token) = self.current {
+                    if let Reaction::Value(val) = self.handle(token) {
+                        return Reaction::Value(val);
+                    }
+                }
+                self.next_pending()
+            }
+        }
+    }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        tokens.extend(self.streams.iter().map(|(index, _)| Token(index)));
+    }
+}
+
+impl<S, H> TakeError for Connections<S, H>
+where
+    S: StreamRef,
+    H: Handler<S>,
+{
+    fn take_error(&mut self) -> Option<Error> {
+        self.error.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener as StdListener;
+
+    use super::*;
+    use crate::net::tcp::{ReactiveTcpStream, TcpStream};
+
+    #[test]
+    fn insert_without_capacity() {
+        let _system_sig = System::init().unwrap();
+        let listener = StdListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let stream = || ReactiveTcpStream::new(TcpStream::connect(&addr).unwrap()).unwrap();
+
+        let mut connections = Connections::new(|_, _: &mut ReactiveTcpStream| Handled::<()>::Continue);
+        connections.streams = PreVec::with_capacity(0);
+        connections.streams.prevent_growth();
+
+        assert!(connections.insert(stream()).is_err());
+
+        connections.react(Reaction::Value(stream()));
+        assert!(connections.is_empty());
+        assert!(connections.take_error().is_some());
+    }
+}
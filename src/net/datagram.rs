@@ -0,0 +1,203 @@
+//! Sending and receiving datagrams, shared by udp and unix datagram sockets.
+
+use std::collections::VecDeque;
+use std::io::{self, ErrorKind::WouldBlock};
+use std::net::SocketAddr;
+
+use mio::{Event, Evented, Ready, Token};
+
+use crate::errors::{Error, Result};
+use crate::reactor::{EventedReactor, Reaction};
+use crate::system::System;
+
+// The largest possible udp payload (plus some)
+const MAX_DATAGRAM_SIZE: usize = 65_536;
+
+// A socket that sends and receives datagrams
+pub(crate) trait DatagramSocket: Evented {
+    // The address of a received datagram
+    type Addr;
+    // The address a datagram is sent to
+    type Target;
+
+    fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, Self::Addr)>;
+    fn send_to(&self, buf: &[u8], target: &Self::Target) -> io::Result<usize>;
+    // Send to the connected address
+    fn send(&self, buf: &[u8]) -> io::Result<usize>;
+}
+
+impl DatagramSocket for mio::net::UdpSocket {
+    type Addr = SocketAddr;
+    type Target = SocketAddr;
+
+    fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+        mio::net::UdpSocket::recv_from(self, buf)
+    }
+
+    fn send_to(&self, buf: &[u8], target: &SocketAddr) -> io::Result<usize> {
+        mio::net::UdpSocket::send_to(self, buf, target)
+    }
+
+    fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        mio::net::UdpSocket::send(self, buf)
+    }
+}
+
+#[cfg(unix)]
+impl DatagramSocket for mio_uds::UnixDatagram {
+    type Addr = std::os::unix::net::SocketAddr;
+    type Target = std::path::PathBuf;
+
+    fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, Self::Addr)> {
+        mio_uds::UnixDatagram::recv_from(self, buf)
+    }
+
+    fn send_to(&self, buf: &[u8], target: &Self::Target) -> io::Result<usize> {
+        mio_uds::UnixDatagram::send_to(self, buf, target)
+    }
+
+    fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        mio_uds::UnixDatagram::send(self, buf)
+    }
+}
+
+// -----------------------------------------------------------------------------
+//              - Datagrams -
+// -----------------------------------------------------------------------------
+// Receive datagrams, and queue outgoing datagrams while the socket would block.
+pub(crate) struct Datagrams<S: DatagramSocket> {
+    inner: EventedReactor<S>,
+    buffer: Vec<u8>,
+    // A datagram without a target is sent to the connected address
+    send_queue: VecDeque<(Vec<u8>, Option<S::Target>)>,
+    send_queue_capacity: usize,
+    dropped: usize,
+    error: Option<Error>,
+}
+
+impl<S: DatagramSocket> Datagrams<S> {
+    pub(crate) fn new(socket: S) -> Result<Self> {
+        Ok(Self {
+            inner: EventedReactor::new(socket, Ready::readable() | Ready::writable())?,
+            buffer: vec![0; MAX_DATAGRAM_SIZE],
+            send_queue: VecDeque::new(),
+            send_queue_capacity: 1024,
+            dropped: 0,
+            error: None,
+        })
+    }
+
+    pub(crate) fn token(&self) -> Token {
+        self.inner.token()
+    }
+
+    pub(crate) fn inner(&self) -> &S {
+        self.inner.inner()
+    }
+
+    pub(crate) fn set_send_queue_capacity(&mut self, capacity: usize) {
+        self.send_queue_capacity = capacity;
+    }
+
+    pub(crate) fn queued(&self) -> usize {
+        self.send_queue.len()
+    }
+
+    pub(crate) fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    pub(crate) fn set_error(&mut self, error: Error) {
+        self.error = Some(error);
+    }
+
+    pub(crate) fn take_error(&mut self) -> Option<Error> {
+        self.error.take()
+    }
+
+    // Queue the datagram and send what can be sent.
+    // Returns `Error::NoCapacity` if the send queue is full.
+    // Send errors are stored rather than returned (see `flush`).
+    pub(crate) fn enqueue(&mut self, payload: Vec<u8>, target: Option<S::Target>) -> Result<()> {
+        if self.send_queue.len() >= self.send_queue_capacity {
+            self.dropped += 1;
+            return Err(Error::NoCapacity);
+        }
+
+        self.send_queue.push_back((payload, target));
+        self.flush()
+    }
+
+    // Send queued datagrams until the queue is empty or the socket would block.
+    // A datagram that fails to send is dropped and the error is stored, so one
+    // bad datagram doesn't hold up the rest of the queue.
+    pub(crate) fn flush(&mut self) -> Result<()> {
+        while let Some((payload, target)) = self.send_queue.front() {
+            let res = match target {
+                Some(target) => self.inner.inner().send_to(payload, target),
+                None => self.inner.inner().send(payload),
+            };
+
+            match res {
+                Ok(_) => { self.send_queue.pop_front(); }
+                Err(ref e) if e.kind() == WouldBlock => {
+                    self.inner.is_writable = false;
+                    System::reregister(&self.inner)?;
+                    break;
+                }
+                Err(e) => {
+                    // Drop the datagram rather than retrying it forever
+                    self.send_queue.pop_front();
+                    self.error = Some(e.into());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn recv(&mut self) -> Reaction<(Vec<u8>, S::Addr)> {
+        let mut failed = false;
+        while self.inner.is_readable {
+            match self.inner.inner().recv_from(&mut self.buffer) {
+                Ok((n, addr)) => return Reaction::Value((self.buffer[..n].to_vec(), addr)),
+                Err(ref e) if e.kind() == WouldBlock => {
+                    self.inner.is_readable = false;
+                    if let Err(e) = System::reregister(&self.inner) {
+                        self.error = Some(e);
+                    }
+                }
+                // A pending error (e.g. port unreachable on a connected socket) is only
+                // reported once, so the socket is still readable. Give up on a second
+                // error in a row, keeping the readiness for the next reaction.
+                Err(e) => {
+                    self.error = Some(e.into());
+                    if failed {
+                        break;
+                    }
+                    failed = true;
+                }
+            }
+        }
+        Reaction::Continue
+    }
+
+    // Send what was queued once the socket is writable,
+    // and receive the next datagram.
+    pub(crate) fn react_event(&mut self, event: Event) -> Reaction<(Vec<u8>, S::Addr)> {
+        if event.token() != self.inner.token() {
+            return event.into();
+        }
+
+        self.inner.is_readable |= event.readiness().is_readable();
+        self.inner.is_writable |= event.readiness().is_writable();
+
+        if self.inner.is_writable {
+            if let Err(e) = self.flush() {
+                self.error = Some(e);
+            }
+        }
+
+        self.recv()
+    }
+}
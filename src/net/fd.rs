@@ -0,0 +1,313 @@
+//! Pass file descriptors between processes over unix domain sockets.
+//!
+//! File descriptors are sent as `SCM_RIGHTS` ancillary data together with at least
+//! one byte of regular data.
+//!
+//! This makes it possible for a new process to adopt the listeners (and connections)
+//! of an old process, e.g. during a zero-downtime restart:
+//!
+//!```no_run
+//! # use std::os::unix::io::AsRawFd;
+//! # use sonr::prelude::*;
+//! # use sonr::errors::Result;
+//! use sonr::net::tcp::{ReactiveTcpListener, TcpListener};
+//! use sonr::net::uds::{ReactiveUdsListener, ReactiveUdsStream};
+//! use sonr::net::fd::{ReactiveFdReceiver, into_tcp_listener};
+//!
+//! // The old process hands over the listener
+//! fn hand_over(listener: &TcpListener) -> Result<()> {
+//!     let mut stream = ReactiveUdsStream::connect("/tmp/handover.sock")?;
+//!     stream.send_with_fds(b"listener", &[listener.as_raw_fd()])?;
+//!     Ok(())
+//! }
+//!
+//! // The new process adopts the listener
+//! fn main() -> Result<()> {
+//!     System::init()?;
+//!     let handover = ReactiveUdsListener::bind("/tmp/handover.sock")?
+//!         .map(|(stream, _)| ReactiveUdsStream::new(stream).unwrap())
+//!         .chain(ReactiveFdReceiver::new())
+//!         .map(|(_, fds)| {
+//!             for fd in fds {
+//!                 let listener = into_tcp_listener(fd).unwrap();
+//!                 // Start accepting connections
+//!             }
+//!         });
+//!
+//!     System::start(handover)?;
+//!     Ok(())
+//! }
+//! ```
+use std::collections::VecDeque;
+use std::io::{self, ErrorKind::WouldBlock};
+use std::mem;
+use std::os::unix::io::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::ptr;
+
+use mio::Token;
+
+use crate::errors::{Error, Result};
+use crate::net::tcp::{TcpListener, TcpStream};
+use crate::net::uds::ReactiveUdsStream;
+use crate::reactor::{Reaction, Reactor, TakeError};
+
+/// The maximum number of file descriptors received in one message
+pub const MAX_FDS: usize = 32;
+
+#[cfg(target_os = "linux")]
+const SEND_FLAGS: libc::c_int = libc::MSG_NOSIGNAL;
+#[cfg(not(target_os = "linux"))]
+const SEND_FLAGS: libc::c_int = 0;
+
+#[cfg(target_os = "linux")]
+const RECV_FLAGS: libc::c_int = libc::MSG_CMSG_CLOEXEC;
+#[cfg(not(target_os = "linux"))]
+const RECV_FLAGS: libc::c_int = 0;
+
+// The control buffer has to be aligned for `cmsghdr`
+fn control_buffer(fds: usize) -> Vec<u64> {
+    let space = unsafe { libc::CMSG_SPACE((fds * mem::size_of::<RawFd>()) as u32) } as usize;
+    vec![0u64; space.div_ceil(mem::size_of::<u64>())]
+}
+
+fn send_fds(socket: RawFd, data: &[u8], fds: &[RawFd]) -> io::Result<usize> {
+    if data.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "at least one byte has to be sent with the file descriptors",
+        ));
+    }
+
+    let mut iov = libc::iovec {
+        iov_base: data.as_ptr() as *mut libc::c_void,
+        iov_len: data.len(),
+    };
+
+    let mut control = control_buffer(fds.len());
+    let fds_len = mem::size_of_val(fds);
+
+    let mut msg: libc::msghdr = unsafe { mem::zeroed() };
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+
+    if !fds.is_empty() {
+        msg.msg_control = control.as_mut_ptr() as *mut libc::c_void;
+        msg.msg_controllen = (control.len() * mem::size_of::<u64>()) as _;
+
+        unsafe {
+            let cmsg = libc::CMSG_FIRSTHDR(&msg);
+            (*cmsg).cmsg_level = libc::SOL_SOCKET;
+            (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+            (*cmsg).cmsg_len = libc::CMSG_LEN(fds_len as u32) as _;
+            ptr::copy_nonoverlapping(fds.as_ptr() as *const u8, libc::CMSG_DATA(cmsg), fds_len);
+        }
+    }
+
+    let n = unsafe { libc::sendmsg(socket, &msg, SEND_FLAGS) };
+    if n < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(n as usize)
+}
+
+fn recv_fds(socket: RawFd, buf: &mut [u8], fds: &mut Vec<OwnedFd>) -> io::Result<usize> {
+    let mut iov = libc::iovec {
+        iov_base: buf.as_mut_ptr() as *mut libc::c_void,
+        iov_len: buf.len(),
+    };
+
+    let mut control = control_buffer(MAX_FDS);
+
+    let mut msg: libc::msghdr = unsafe { mem::zeroed() };
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+    msg.msg_control = control.as_mut_ptr() as *mut libc::c_void;
+    msg.msg_controllen = (control.len() * mem::size_of::<u64>()) as _;
+
+    let n = unsafe { libc::recvmsg(socket, &mut msg, RECV_FLAGS) };
+    if n < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    // Owned right away, so they are closed on error
+    let mut received = Vec::new();
+    unsafe {
+        let mut cmsg = libc::CMSG_FIRSTHDR(&msg);
+        while !cmsg.is_null() {
+            if (*cmsg).cmsg_level == libc::SOL_SOCKET && (*cmsg).cmsg_type == libc::SCM_RIGHTS {
+                let data = libc::CMSG_DATA(cmsg) as *const RawFd;
+                let len = (*cmsg).cmsg_len as usize - libc::CMSG_LEN(0) as usize;
+                for i in 0..len / mem::size_of::<RawFd>() {
+                    let fd = ptr::read_unaligned(data.add(i));
+                    received.push(OwnedFd::from_raw_fd(fd));
+                }
+            }
+            cmsg = libc::CMSG_NXTHDR(&msg, cmsg);
+        }
+    }
+
+    // The file descriptors that didn't fit were closed by the kernel
+    if msg.msg_flags & libc::MSG_CTRUNC != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "too many file descriptors in one message",
+        ));
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    for fd in &received {
+        set_cloexec(fd.as_raw_fd())?;
+    }
+
+    fds.extend(received);
+    Ok(n as usize)
+}
+
+// `MSG_CMSG_CLOEXEC` is Linux only
+#[cfg(not(target_os = "linux"))]
+fn set_cloexec(fd: RawFd) -> io::Result<()> {
+    let flags = unsafe { libc::fcntl(fd, libc::F_GETFD) };
+    if flags < 0 || unsafe { libc::fcntl(fd, libc::F_SETFD, flags | libc::FD_CLOEXEC) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+impl ReactiveUdsStream {
+    /// Send data together with file descriptors.
+    /// At least one byte of data has to be sent.
+    ///
+    /// The file descriptors are duplicated into the receiving process,
+    /// and can be closed once they are sent.
+    pub fn send_with_fds(&mut self, data: &[u8], fds: &[RawFd]) -> io::Result<usize> {
+        self.write_with(|socket| send_fds(socket.as_raw_fd(), data, fds))
+    }
+
+    /// Receive data and any file descriptors sent with it.
+    /// The received file descriptors are appended to `fds`,
+    /// and are closed when the process executes another program.
+    ///
+    /// At most [`MAX_FDS`] file descriptors are received per message.
+    /// A message with more file descriptors is an error (`InvalidData`),
+    /// and none of its file descriptors are received.
+    ///
+    /// [`MAX_FDS`]: constant.MAX_FDS.html
+    pub fn recv_with_fds(&mut self, buf: &mut [u8], fds: &mut Vec<OwnedFd>) -> io::Result<usize> {
+        self.read_with(|socket| recv_fds(socket.as_raw_fd(), buf, fds))
+    }
+}
+
+/// Convert a received file descriptor into a `TcpStream`
+pub fn into_tcp_stream(fd: OwnedFd) -> Result<TcpStream> {
+    Ok(TcpStream::from_stream(std::net::TcpStream::from(fd))?)
+}
+
+/// Convert a received file descriptor into a `TcpListener`
+pub fn into_tcp_listener(fd: OwnedFd) -> Result<TcpListener> {
+    Ok(TcpListener::from_std(std::net::TcpListener::from(fd))?)
+}
+
+// -----------------------------------------------------------------------------
+//              - Fd receiver -
+// -----------------------------------------------------------------------------
+/// Receive file descriptors from unix streams, and output the data and file descriptors
+/// of each message as `(Vec<u8>, Vec<OwnedFd>)`.
+///
+/// Each stream is dropped once the peer closes it, or once receiving fails.
+/// The error a stream failed with is available through [`TakeError::take_error`].
+///
+/// [`TakeError::take_error`]: ../../reactor/trait.TakeError.html#tymethod.take_error
+pub struct ReactiveFdReceiver {
+    streams: Vec<ReactiveUdsStream>,
+    received: VecDeque<(Vec<u8>, Vec<OwnedFd>)>,
+    error: Option<Error>,
+}
+
+impl ReactiveFdReceiver {
+    /// Create a new `ReactiveFdReceiver`
+    pub fn new() -> Self {
+        Self {
+            streams: Vec::new(),
+            received: VecDeque::new(),
+            error: None,
+        }
+    }
+
+    fn receive(&mut self, token: Token) {
+        let index = match self.streams.iter().position(|s| s.token() == token) {
+            Some(index) => index,
+            None => return,
+        };
+
+        let stream = &mut self.streams[index];
+        let mut buf = [0u8; 1024];
+        let mut closed = false;
+        while stream.readable() {
+            let mut fds = Vec::new();
+            match stream.recv_with_fds(&mut buf, &mut fds) {
+                Ok(0) => {
+                    closed = true;
+                    break;
+                }
+                Ok(n) => self.received.push_back((buf[..n].to_vec(), fds)),
+                Err(ref e) if e.kind() == WouldBlock => break,
+                Err(e) => {
+                    self.error = Some(e.into());
+                    closed = true;
+                    break;
+                }
+            }
+        }
+
+        if closed {
+            self.streams.remove(index);
+        }
+    }
+}
+
+impl Default for ReactiveFdReceiver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Reactor for ReactiveFdReceiver {
+    type Input = ReactiveUdsStream;
+    type Output = (Vec<u8>, Vec<OwnedFd>);
+
+    fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
+        match reaction {
+            Reaction::Value(stream) => {
+                self.streams.push(stream);
+                Reaction::Continue
+            }
+            Reaction::Event(event) => {
+                let token = event.token();
+                match self.streams.iter_mut().find(|s| s.token() == token) {
+                    Some(stream) => { stream.react(event.into()); }
+                    None => return event.into(),
+                }
+
+                self.receive(token);
+                match self.received.pop_front() {
+                    Some(received) => Reaction::Value(received),
+                    None => Reaction::Continue,
+                }
+            }
+            Reaction::Continue => match self.received.pop_front() {
+                Some(received) => Reaction::Value(received),
+                None => Reaction::Continue,
+            },
+        }
+    }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        tokens.extend(self.streams.iter().map(|s| s.token()));
+    }
+}
+
+impl TakeError for ReactiveFdReceiver {
+    fn take_error(&mut self) -> Option<Error> {
+        self.error.take()
+    }
+}
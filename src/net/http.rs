@@ -0,0 +1,1331 @@
+//! HTTP/1.1 server connections.
+//!
+//! Requires the `http` feature.
+//!
+//! * A [`RequestParser`] incrementally decodes [`Request`]s from a buffer of bytes.
+//! * An [`HttpConnection`] reads requests from a stream and writes a [`Response`] to each of them.
+//!
+//! Connections are kept alive and requests can be pipelined: responses are always
+//! written in the order the requests were received.
+//! Request bodies can be sent with a `Content-Length` or using the chunked transfer encoding,
+//! and responses can be streamed using the chunked transfer encoding.
+//!
+//! Slow clients are not timed out by the connection itself,
+//! use the deadlines of the underlying [`Stream`] for that.
+//!
+//! [`RequestParser`]: struct.RequestParser.html
+//! [`Request`]: struct.Request.html
+//! [`HttpConnection`]: struct.HttpConnection.html
+//! [`Response`]: struct.Response.html
+//! [`Stream`]: ../stream/struct.Stream.html
+use std::collections::VecDeque;
+use std::fmt::{self, Debug, Formatter};
+use std::io::{self, Read, Write};
+use std::io::ErrorKind::WouldBlock;
+
+use mio::Token;
+
+use crate::errors::{Error, Result};
+use crate::net::codec::Decoder;
+use crate::net::stream::{Stream, StreamRef};
+use crate::net::tcp::ReactiveTcpStream;
+use crate::reactor::{Reaction, Reactor, TakeError};
+
+const READ_CHUNK: usize = 4096;
+const MAX_HEADERS_SIZE: usize = 16 * 1024;
+const MAX_BODY_SIZE: usize = 1024 * 1024;
+const WRITE_BUFFER_LIMIT: usize = 64 * 1024;
+
+// Chunk size lines (including extensions) and trailer lines
+const MAX_LINE_SIZE: usize = 1024;
+
+fn invalid_input(msg: &str) -> Error {
+    Error::Io(io::Error::new(io::ErrorKind::InvalidInput, msg))
+}
+
+fn find(buf: &[u8], needle: &[u8]) -> Option<usize> {
+    buf.windows(needle.len()).position(|window| window == needle)
+}
+
+fn is_token(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+}
+
+// Header values can't contain control characters, other than tabs
+fn is_field_value(s: &str) -> bool {
+    !s.bytes().any(|b| b.is_ascii_control() && b != b'\t')
+}
+
+// Does the comma separated header value contain `token`?
+fn contains_token(value: &str, token: &str) -> bool {
+    value.split(',').any(|t| t.trim().eq_ignore_ascii_case(token))
+}
+
+/// The reason phrase of a status code, or an empty string
+/// for status codes without a known reason.
+pub fn reason(status: u16) -> &'static str {
+    match status {
+        100 => "Continue",
+        101 => "Switching Protocols",
+        200 => "OK",
+        201 => "Created",
+        202 => "Accepted",
+        204 => "No Content",
+        206 => "Partial Content",
+        301 => "Moved Permanently",
+        302 => "Found",
+        303 => "See Other",
+        304 => "Not Modified",
+        307 => "Temporary Redirect",
+        308 => "Permanent Redirect",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        409 => "Conflict",
+        411 => "Length Required",
+        413 => "Payload Too Large",
+        414 => "URI Too Long",
+        415 => "Unsupported Media Type",
+        429 => "Too Many Requests",
+        431 => "Request Header Fields Too Large",
+        500 => "Internal Server Error",
+        501 => "Not Implemented",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        504 => "Gateway Timeout",
+        505 => "HTTP Version Not Supported",
+        _ => "",
+    }
+}
+
+// Responses with these status codes never have a body
+fn has_body(status: u16) -> bool {
+    !(100..200).contains(&status) && status != 204 && status != 304
+}
+
+// -----------------------------------------------------------------------------
+// 		- Parse error -
+// -----------------------------------------------------------------------------
+/// A request that can't be parsed.
+///
+/// The connection answers the request with the [`status`] of the error,
+/// and closes once the response is written.
+///
+/// [`status`]: enum.ParseError.html#method.status
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+    /// A malformed request line, header or chunk
+    BadRequest,
+
+    /// The request line and headers exceed the maximum headers size
+    HeadersTooLarge,
+
+    /// The body exceeds the maximum body size
+    BodyTooLarge,
+
+    /// A transfer coding other than `chunked`
+    UnsupportedEncoding,
+
+    /// An HTTP version other than 1.0 and 1.1
+    UnsupportedVersion,
+}
+
+impl ParseError {
+    /// The status code of the response to the request
+    pub fn status(self) -> u16 {
+        match self {
+            ParseError::BadRequest => 400,
+            ParseError::HeadersTooLarge => 431,
+            ParseError::BodyTooLarge => 413,
+            ParseError::UnsupportedEncoding => 501,
+            ParseError::UnsupportedVersion => 505,
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// 		- Request -
+// -----------------------------------------------------------------------------
+/// The HTTP version of a request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Version {
+    /// HTTP/1.0
+    Http10,
+
+    /// HTTP/1.1
+    Http11,
+}
+
+/// A request, including the complete body.
+#[derive(Debug, Clone)]
+pub struct Request {
+    method: String,
+    target: String,
+    version: Version,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Request {
+    /// The request method, e.g. `GET`
+    pub fn method(&self) -> &str {
+        &self.method
+    }
+
+    /// The request target as sent by the client, e.g. `/search?q=sonr`
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// The path of the request target, e.g. `/search`
+    pub fn path(&self) -> &str {
+        match self.target.find('?') {
+            Some(pos) => &self.target[..pos],
+            None => &self.target,
+        }
+    }
+
+    /// The query of the request target, e.g. `q=sonr`
+    pub fn query(&self) -> Option<&str> {
+        self.target.find('?').map(|pos| &self.target[pos + 1..])
+    }
+
+    /// The HTTP version
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// All headers, in the order they were received
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    /// The value of the first header named `name` (header names are case insensitive)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn header_values<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> {
+        self.headers
+            .iter()
+            .filter(move |(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The request body
+    pub fn body(&self) -> &[u8] {
+        &self.body
+    }
+
+    /// Consume the request and return the body
+    pub fn into_body(self) -> Vec<u8> {
+        self.body
+    }
+
+    /// Returns `true` if the client wants to keep the connection open after the response.
+    /// HTTP/1.1 connections are kept alive unless the client sends `Connection: close`,
+    /// and HTTP/1.0 connections only if the client sends `Connection: keep-alive`.
+    pub fn keep_alive(&self) -> bool {
+        let mut connection = self.header_values("connection");
+        match self.version {
+            Version::Http11 => !connection.any(|value| contains_token(value, "close")),
+            Version::Http10 => connection.any(|value| contains_token(value, "keep-alive")),
+        }
+    }
+
+    fn expects_continue(&self) -> bool {
+        self.version == Version::Http11
+            && self.header_values("expect").any(|value| value.eq_ignore_ascii_case("100-continue"))
+    }
+}
+
+// -----------------------------------------------------------------------------
+// 		- Request parser -
+// -----------------------------------------------------------------------------
+enum Body {
+    Empty,
+    Length(usize),
+    Chunked,
+}
+
+fn parse_head(head: &[u8]) -> std::result::Result<Request, ParseError> {
+    let head = std::str::from_utf8(head).map_err(|_| ParseError::BadRequest)?;
+    let mut lines = head.split("\r\n");
+
+    let request_line = lines.next().ok_or(ParseError::BadRequest)?;
+    let mut parts = request_line.split(' ');
+    let (method, target, version) = match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(method), Some(target), Some(version), None) => (method, target, version),
+        _ => return Err(ParseError::BadRequest),
+    };
+
+    if !is_token(method) || target.is_empty() || target.bytes().any(|b| b.is_ascii_control()) {
+        return Err(ParseError::BadRequest);
+    }
+
+    let version = match version {
+        "HTTP/1.1" => Version::Http11,
+        "HTTP/1.0" => Version::Http10,
+        v if v.starts_with("HTTP/") => return Err(ParseError::UnsupportedVersion),
+        _ => return Err(ParseError::BadRequest),
+    };
+
+    let mut headers = Vec::new();
+    for line in lines {
+        // Folded header values are obsolete, and rejected
+        if line.starts_with(' ') || line.starts_with('\t') {
+            return Err(ParseError::BadRequest);
+        }
+
+        let pos = line.find(':').ok_or(ParseError::BadRequest)?;
+        let (name, value) = (&line[..pos], line[pos + 1..].trim());
+        if !is_token(name) || !is_field_value(value) {
+            return Err(ParseError::BadRequest);
+        }
+        headers.push((name.to_string(), value.to_string()));
+    }
+
+    Ok(Request {
+        method: method.to_string(),
+        target: target.to_string(),
+        version,
+        headers,
+        body: Vec::new(),
+    })
+}
+
+fn body_of(request: &Request) -> std::result::Result<Body, ParseError> {
+    let codings = request
+        .header_values("transfer-encoding")
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .collect::<Vec<_>>();
+    let lengths = request.header_values("content-length").collect::<Vec<_>>();
+
+    if !codings.is_empty() {
+        // A request with both is either broken or an attempt at request smuggling
+        if !lengths.is_empty() {
+            return Err(ParseError::BadRequest);
+        }
+        return match codings.as_slice() {
+            [coding] if coding.eq_ignore_ascii_case("chunked") => Ok(Body::Chunked),
+            _ => Err(ParseError::UnsupportedEncoding),
+        };
+    }
+
+    let mut length = None;
+    for value in lengths {
+        if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ParseError::BadRequest);
+        }
+        let value = value.parse::<usize>().map_err(|_| ParseError::BodyTooLarge)?;
+        if length.is_some() && length != Some(value) {
+            return Err(ParseError::BadRequest);
+        }
+        length = Some(value);
+    }
+
+    match length {
+        None | Some(0) => Ok(Body::Empty),
+        Some(length) => Ok(Body::Length(length)),
+    }
+}
+
+enum State {
+    Head,
+    Body { remaining: usize },
+    ChunkSize,
+    ChunkData { remaining: usize },
+    ChunkEnd,
+    Trailers,
+}
+
+/// Incrementally decode requests from a buffer of bytes.
+///
+/// The parser consumes the bytes of a request from the buffer as they arrive, so
+/// a request can be decoded from any number of partial reads, and the bytes of the
+/// next (pipelined) request are left in the buffer.
+///
+/// Requests with a request line and headers larger than the maximum headers size
+/// (16 KiB by default), or a body larger than the maximum body size (1 MiB by default)
+/// are rejected.
+/// Once the parser returns an error it should not be used again.
+///
+///```
+/// use sonr::net::codec::Decoder;
+/// use sonr::net::http::RequestParser;
+///
+/// let mut parser = RequestParser::new();
+/// let mut buf = b"POST /echo HTTP/1.1\r\nContent-Length: 5\r\n\r\nhel".to_vec();
+/// assert!(parser.decode(&mut buf).unwrap().is_none());
+///
+/// buf.extend_from_slice(b"lo");
+/// let request = parser.decode(&mut buf).unwrap().unwrap();
+/// assert_eq!(request.path(), "/echo");
+/// assert_eq!(request.body(), b"hello");
+/// ```
+pub struct RequestParser {
+    max_headers_size: usize,
+    max_body_size: usize,
+    state: State,
+    searched: usize,
+    request: Option<Request>,
+    expect_continue: bool,
+}
+
+impl Default for RequestParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RequestParser {
+    /// Create a new parser with the default limits
+    pub fn new() -> Self {
+        Self {
+            max_headers_size: MAX_HEADERS_SIZE,
+            max_body_size: MAX_BODY_SIZE,
+            state: State::Head,
+            searched: 0,
+            request: None,
+            expect_continue: false,
+        }
+    }
+
+    /// Set the maximum size of the request line and headers
+    pub fn max_headers_size(mut self, max: usize) -> Self {
+        self.max_headers_size = max;
+        self
+    }
+
+    /// Set the maximum size of a request body
+    pub fn max_body_size(mut self, max: usize) -> Self {
+        self.max_body_size = max;
+        self
+    }
+
+    /// Returns `true` once, after the headers of a request asking for
+    /// `100 Continue` are parsed, and before the body is complete.
+    pub fn take_expect_continue(&mut self) -> bool {
+        std::mem::replace(&mut self.expect_continue, false)
+    }
+
+    fn decode_head(&mut self, buf: &mut Vec<u8>) -> std::result::Result<Option<Request>, ParseError> {
+        // Empty lines before a request line are ignored
+        while buf.starts_with(b"\r\n") {
+            buf.drain(..2);
+        }
+
+        // Don't search the same bytes twice
+        let from = self.searched.saturating_sub(3).min(buf.len());
+        let end = match find(&buf[from..], b"\r\n\r\n") {
+            Some(pos) => from + pos,
+            None => {
+                self.searched = buf.len();
+                if buf.len() > self.max_headers_size {
+                    return Err(ParseError::HeadersTooLarge);
+                }
+                return Ok(None);
+            }
+        };
+
+        self.searched = 0;
+        if end + 4 > self.max_headers_size {
+            return Err(ParseError::HeadersTooLarge);
+        }
+
+        let head = buf.drain(..end + 4).collect::<Vec<_>>();
+        let request = parse_head(&head[..end])?;
+        match body_of(&request)? {
+            Body::Empty => return Ok(Some(request)),
+            Body::Length(length) if length > self.max_body_size => return Err(ParseError::BodyTooLarge),
+            Body::Length(length) => self.state = State::Body { remaining: length },
+            Body::Chunked => self.state = State::ChunkSize,
+        }
+
+        self.expect_continue = request.expects_continue();
+        self.request = Some(request);
+        Ok(None)
+    }
+
+    // Move up to `remaining` bytes of body from the buffer to the request
+    fn take_body(&mut self, buf: &mut Vec<u8>, remaining: usize) -> usize {
+        let n = remaining.min(buf.len());
+        if let Some(ref mut request) = self.request {
+            request.body.extend(buf.drain(..n));
+        }
+        remaining - n
+    }
+
+    fn body_len(&self) -> usize {
+        self.request.as_ref().map(|request| request.body.len()).unwrap_or(0)
+    }
+
+    // The next line of a chunked body, without the line ending
+    fn next_line(buf: &mut Vec<u8>) -> std::result::Result<Option<Vec<u8>>, ParseError> {
+        match find(buf, b"\r\n") {
+            Some(pos) => {
+                let mut line = buf.drain(..pos + 2).collect::<Vec<_>>();
+                line.truncate(pos);
+                Ok(Some(line))
+            }
+            None if buf.len() > MAX_LINE_SIZE => Err(ParseError::BadRequest),
+            None => Ok(None),
+        }
+    }
+
+    fn complete(&mut self) -> Option<Request> {
+        self.state = State::Head;
+        self.expect_continue = false;
+        self.request.take()
+    }
+
+    fn parse(&mut self, buf: &mut Vec<u8>) -> std::result::Result<Option<Request>, ParseError> {
+        loop {
+            match self.state {
+                State::Head => {
+                    if let Some(request) = self.decode_head(buf)? {
+                        return Ok(Some(request));
+                    }
+                    // Incomplete headers, otherwise go on with the body
+                    if let State::Head = self.state {
+                        return Ok(None);
+                    }
+                }
+                State::Body { remaining } => {
+                    let remaining = self.take_body(buf, remaining);
+                    if remaining > 0 {
+                        self.state = State::Body { remaining };
+                        return Ok(None);
+                    }
+                    return Ok(self.complete());
+                }
+                State::ChunkSize => {
+                    let line = match Self::next_line(buf)? {
+                        Some(line) => line,
+                        None => return Ok(None),
+                    };
+
+                    // Chunk extensions are ignored
+                    let size = line.split(|b| *b == b';').next().unwrap_or(&[]);
+                    let size = std::str::from_utf8(size).map_err(|_| ParseError::BadRequest)?.trim();
+                    if size.is_empty() || !size.bytes().all(|b| b.is_ascii_hexdigit()) {
+                        return Err(ParseError::BadRequest);
+                    }
+
+                    let size = usize::from_str_radix(size, 16).map_err(|_| ParseError::BodyTooLarge)?;
+                    self.state = match size {
+                        0 => State::Trailers,
+                        size if size > self.max_body_size - self.body_len() => {
+                            return Err(ParseError::BodyTooLarge)
+                        }
+                        size => State::ChunkData { remaining: size },
+                    };
+                }
+                State::ChunkData { remaining } => {
+                    let remaining = self.take_body(buf, remaining);
+                    if remaining > 0 {
+                        self.state = State::ChunkData { remaining };
+                        return Ok(None);
+                    }
+                    self.state = State::ChunkEnd;
+                }
+                State::ChunkEnd => {
+                    if buf.len() < 2 {
+                        return Ok(None);
+                    }
+                    if &buf[..2] != b"\r\n" {
+                        return Err(ParseError::BadRequest);
+                    }
+                    buf.drain(..2);
+                    self.state = State::ChunkSize;
+                }
+                State::Trailers => {
+                    // Trailers are ignored
+                    match Self::next_line(buf)? {
+                        Some(ref line) if line.is_empty() => return Ok(self.complete()),
+                        Some(_) => {}
+                        None => return Ok(None),
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Decoder for RequestParser {
+    type Item = Request;
+
+    fn decode(&mut self, buf: &mut Vec<u8>) -> Result<Option<Request>> {
+        Ok(self.parse(buf)?)
+    }
+}
+
+impl Debug for RequestParser {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("RequestParser")
+            .field("max_headers_size", &self.max_headers_size)
+            .field("max_body_size", &self.max_body_size)
+            .finish()
+    }
+}
+
+// -----------------------------------------------------------------------------
+// 		- Response -
+// -----------------------------------------------------------------------------
+/// A response to a request.
+///
+/// The `Content-Length`, `Transfer-Encoding` and `Connection` headers are set by
+/// the [`HttpConnection`], and are ignored if set on the response
+/// (except for `Connection: close`, which closes the connection after the response).
+///
+///```
+/// use sonr::net::http::Response;
+///
+/// let response = Response::new(200)
+///     .header("Content-Type", "text/plain")
+///     .body("Hello World");
+/// assert_eq!(response.status(), 200);
+/// ```
+///
+/// [`HttpConnection`]: struct.HttpConnection.html
+#[derive(Debug, Clone)]
+pub struct Response {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Response {
+    /// Create an empty response with a status code
+    pub fn new(status: u16) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: Vec::new(),
+        }
+    }
+
+    /// Add a header.
+    ///
+    /// The name has to be a token, and the value can't contain control
+    /// characters other than tabs (so no CR or LF), otherwise
+    /// `respond` and `respond_chunked` return an error.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Set the body
+    pub fn body<B: Into<Vec<u8>>>(mut self, body: B) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// The status code
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    /// The headers
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    fn valid_headers(&self) -> bool {
+        self.headers.iter().all(|(name, value)| is_token(name) && is_field_value(value))
+    }
+
+    fn closes(&self) -> bool {
+        self.headers
+            .iter()
+            .any(|(name, value)| name.eq_ignore_ascii_case("connection") && contains_token(value, "close"))
+    }
+}
+
+// How the body of a response is framed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Framing {
+    Length(usize),
+    Chunked,
+    UntilClose,
+}
+
+fn encode_head(response: &Response, version: Version, framing: Framing, close: bool, buf: &mut Vec<u8>) {
+    buf.extend_from_slice(format!("HTTP/1.1 {} {}\r\n", response.status, reason(response.status)).as_bytes());
+
+    let managed = ["content-length", "transfer-encoding", "connection"];
+    for (name, value) in &response.headers {
+        if managed.iter().any(|m| name.eq_ignore_ascii_case(m)) {
+            continue;
+        }
+        buf.extend_from_slice(format!("{}: {}\r\n", name, value).as_bytes());
+    }
+
+    if has_body(response.status) {
+        match framing {
+            Framing::Length(len) => buf.extend_from_slice(format!("Content-Length: {}\r\n", len).as_bytes()),
+            Framing::Chunked => buf.extend_from_slice(b"Transfer-Encoding: chunked\r\n"),
+            Framing::UntilClose => {}
+        }
+    }
+
+    if close {
+        buf.extend_from_slice(b"Connection: close\r\n");
+    } else if version == Version::Http10 {
+        buf.extend_from_slice(b"Connection: keep-alive\r\n");
+    }
+
+    buf.extend_from_slice(b"\r\n");
+}
+
+// -----------------------------------------------------------------------------
+// 		- Http connection -
+// -----------------------------------------------------------------------------
+// A request waiting for a response
+struct Pending {
+    version: Version,
+    keep_alive: bool,
+    head: bool,
+}
+
+// A response written with `respond_chunked`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Streaming {
+    Chunked,
+    UntilClose,
+    Discard,
+}
+
+/// Read requests from a stream, and write responses.
+///
+/// Each call to [`next_request`] writes what it can of the buffered responses,
+/// reads from the stream and returns the next complete request.
+/// Every request has to be answered, either with a [`Response`] using [`respond`],
+/// or by streaming the response body with [`respond_chunked`], [`write_chunk`] and [`finish`].
+/// Requests can be pipelined, and the responses are written in the order the
+/// requests were returned.
+///
+/// Responses are buffered and written as the stream is writable.
+/// Once more than the write buffer limit (64 KiB by default) is waiting to be written,
+/// [`write_buffer_full`] returns `true` and no more requests are read until the stream
+/// catches up. A large streamed response should stop writing chunks until then, and
+/// call [`flush`] once the stream is writable again before checking.
+///
+/// Requests that can't be parsed (see [`ParseError`]) are answered with an
+/// error response once the requests before them are answered, and the connection closes.
+/// The connection also closes after answering a request that doesn't keep the
+/// connection alive, and once the peer closes the stream and every request is answered.
+/// A closed connection ([`is_closed`]) should be dropped.
+///
+/// As a [`Reactor`] the connection outputs requests, and responds with the responses it receives.
+/// It can also be used with [`Connections`]:
+///
+///```no_run
+/// # use sonr::prelude::*;
+/// # use sonr::errors::Result;
+/// use sonr::net::tcp::{ReactiveTcpListener, ReactiveTcpStream};
+/// use sonr::net::connections::{Connections, Handled};
+/// use sonr::net::http::{HttpConnection, Response};
+///
+/// fn main() -> Result<()> {
+///     System::init()?;
+///
+///     let listener = ReactiveTcpListener::bind("127.0.0.1:8080")?;
+///     let connections = Connections::new(|_, conn: &mut HttpConnection| {
+///         while let Some(request) = conn.next_request() {
+///             let response = Response::new(200).body(format!("Hello {}\n", request.path()));
+///             if conn.respond(response).is_err() {
+///                 return Handled::Close;
+///             }
+///         }
+///
+///         match conn.is_closed() {
+///             true => Handled::Close,
+///             false => Handled::<()>::Continue,
+///         }
+///     });
+///
+///     let run = listener
+///         .map(|(stream, _)| HttpConnection::new(ReactiveTcpStream::new(stream).unwrap()))
+///         .chain(connections);
+///     System::start(run)?;
+///     Ok(())
+/// }
+/// ```
+///
+/// [`next_request`]: struct.HttpConnection.html#method.next_request
+/// [`Response`]: struct.Response.html
+/// [`respond`]: struct.HttpConnection.html#method.respond
+/// [`respond_chunked`]: struct.HttpConnection.html#method.respond_chunked
+/// [`write_chunk`]: struct.HttpConnection.html#method.write_chunk
+/// [`finish`]: struct.HttpConnection.html#method.finish
+/// [`write_buffer_full`]: struct.HttpConnection.html#method.write_buffer_full
+/// [`flush`]: struct.HttpConnection.html#method.flush
+/// [`ParseError`]: enum.ParseError.html
+/// [`is_closed`]: struct.HttpConnection.html#method.is_closed
+/// [`Reactor`]: ../../reactor/trait.Reactor.html
+/// [`Connections`]: ../connections/struct.Connections.html
+pub struct HttpConnection<S = ReactiveTcpStream> {
+    stream: S,
+    parser: RequestParser,
+    read_buf: Vec<u8>,
+    write_buf: Vec<u8>,
+    write_buffer_limit: usize,
+    pending: VecDeque<Pending>,
+    streaming: Option<Streaming>,
+    failed: Option<ParseError>,
+    closing: bool,
+    eof: bool,
+    closed: bool,
+    error: Option<Error>,
+}
+
+impl<S> HttpConnection<S>
+where
+    S: StreamRef + Read + Write,
+{
+    /// Create a new `HttpConnection` from a stream
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            parser: RequestParser::new(),
+            read_buf: Vec::new(),
+            write_buf: Vec::new(),
+            write_buffer_limit: WRITE_BUFFER_LIMIT,
+            pending: VecDeque::new(),
+            streaming: None,
+            failed: None,
+            closing: false,
+            eof: false,
+            closed: false,
+            error: None,
+        }
+    }
+
+    /// Set the maximum size of the request line and headers of a request
+    pub fn max_headers_size(mut self, max: usize) -> Self {
+        self.parser = self.parser.max_headers_size(max);
+        self
+    }
+
+    /// Set the maximum size of a request body
+    pub fn max_body_size(mut self, max: usize) -> Self {
+        self.parser = self.parser.max_body_size(max);
+        self
+    }
+
+    /// Stop reading requests while more than `limit` bytes are waiting to be written
+    pub fn write_buffer_limit(mut self, limit: usize) -> Self {
+        self.write_buffer_limit = limit;
+        self
+    }
+
+    /// Write what can be written, then read and return the next request (if any).
+    pub fn next_request(&mut self) -> Option<Request> {
+        self.flush();
+        loop {
+            if let Some(request) = self.decode() {
+                return Some(request);
+            }
+
+            if !self.can_read() || !self.fill() {
+                return None;
+            }
+        }
+    }
+
+    /// Answer the oldest request without a response.
+    ///
+    /// Returns an error if there is no request to answer, a streamed response
+    /// is not yet finished, or a header of the response is invalid.
+    /// The request is still waiting for a response after an error.
+    pub fn respond(&mut self, response: Response) -> Result<()> {
+        let (version, close, head) = self.start_response(&response)?;
+        let len = response.body.len();
+        encode_head(&response, version, Framing::Length(len), close, &mut self.write_buf);
+        if !head && has_body(response.status) {
+            self.write_buf.extend_from_slice(&response.body);
+        }
+
+        self.response_done();
+        Ok(())
+    }
+
+    /// Answer the oldest request without a response, and stream the body with
+    /// [`write_chunk`] until calling [`finish`].
+    /// The body of the response (if any) is written as the first chunk.
+    ///
+    /// HTTP/1.0 clients don't support chunked responses, and instead
+    /// the connection closes once the response is finished.
+    ///
+    /// [`write_chunk`]: struct.HttpConnection.html#method.write_chunk
+    /// [`finish`]: struct.HttpConnection.html#method.finish
+    pub fn respond_chunked(&mut self, response: Response) -> Result<()> {
+        let (version, mut close, head) = self.start_response(&response)?;
+        let streaming = match version {
+            Version::Http11 => Streaming::Chunked,
+            Version::Http10 => {
+                close = true;
+                Streaming::UntilClose
+            }
+        };
+
+        let framing = match streaming {
+            Streaming::Chunked => Framing::Chunked,
+            _ => Framing::UntilClose,
+        };
+        encode_head(&response, version, framing, close, &mut self.write_buf);
+        if close {
+            self.close_after_response();
+        }
+
+        self.streaming = match head || !has_body(response.status) {
+            true => Some(Streaming::Discard),
+            false => Some(streaming),
+        };
+        self.write_chunk(&response.body)
+    }
+
+    /// Write a chunk of the body of a response started with `respond_chunked`.
+    pub fn write_chunk(&mut self, data: &[u8]) -> Result<()> {
+        match self.streaming {
+            // An empty chunk would end the body
+            Some(Streaming::Chunked) if data.is_empty() => {}
+            Some(Streaming::Chunked) => {
+                self.write_buf.extend_from_slice(format!("{:x}\r\n", data.len()).as_bytes());
+                self.write_buf.extend_from_slice(data);
+                self.write_buf.extend_from_slice(b"\r\n");
+            }
+            Some(Streaming::UntilClose) => self.write_buf.extend_from_slice(data),
+            Some(Streaming::Discard) => {}
+            None => return Err(invalid_input("no streamed response")),
+        }
+
+        self.flush();
+        Ok(())
+    }
+
+    /// Finish a response started with `respond_chunked`.
+    pub fn finish(&mut self) -> Result<()> {
+        match self.streaming.take() {
+            Some(Streaming::Chunked) => self.write_buf.extend_from_slice(b"0\r\n\r\n"),
+            Some(_) => {}
+            None => return Err(invalid_input("no streamed response")),
+        }
+
+        self.response_done();
+        Ok(())
+    }
+
+    // The version of the request being answered, whether to close the
+    // connection after the response, and whether the request was a `HEAD` request.
+    fn start_response(&mut self, response: &Response) -> Result<(Version, bool, bool)> {
+        if self.streaming.is_some() {
+            return Err(invalid_input("streamed response in progress"));
+        }
+
+        if !response.valid_headers() {
+            return Err(invalid_input("invalid response header"));
+        }
+
+        let pending = match self.pending.pop_front() {
+            Some(pending) => pending,
+            None => return Err(invalid_input("no request to respond to")),
+        };
+
+        let close = !pending.keep_alive || response.closes() || (self.closing && self.pending.is_empty());
+        if close {
+            self.close_after_response();
+        }
+        Ok((pending.version, close, pending.head))
+    }
+
+    // Requests pipelined after a response that closes the connection are never answered
+    fn close_after_response(&mut self) {
+        self.closing = true;
+        self.pending.clear();
+    }
+
+    fn response_done(&mut self) {
+        // The request that failed to parse comes after every request
+        // that has been returned, and closes the connection.
+        if self.pending.is_empty() && self.streaming.is_none() {
+            if let Some(err) = self.failed.take() {
+                let response = Response::new(err.status());
+                encode_head(&response, Version::Http11, Framing::Length(0), true, &mut self.write_buf);
+            }
+        }
+        self.flush();
+    }
+
+    fn decode(&mut self) -> Option<Request> {
+        if self.closing || self.closed || self.streaming.is_some() || self.write_buffer_full() {
+            return None;
+        }
+
+        match self.parser.parse(&mut self.read_buf) {
+            Ok(Some(request)) => {
+                self.pending.push_back(Pending {
+                    version: request.version(),
+                    keep_alive: request.keep_alive(),
+                    head: request.method() == "HEAD",
+                });
+                if !request.keep_alive() {
+                    self.closing = true;
+                }
+                Some(request)
+            }
+            Ok(None) => {
+                // An interim response can't be written before the earlier responses,
+                // in which case the client sends the body after a while anyway.
+                if self.parser.take_expect_continue() && self.pending.is_empty() {
+                    self.write_buf.extend_from_slice(b"HTTP/1.1 100 Continue\r\n\r\n");
+                    self.flush();
+                }
+                None
+            }
+            Err(err) => {
+                self.failed = Some(err);
+                self.closing = true;
+                self.error = Some(err.into());
+                self.response_done();
+                None
+            }
+        }
+    }
+
+    fn can_read(&self) -> bool {
+        !self.closing
+            && !self.eof
+            && !self.closed
+            && self.streaming.is_none()
+            && !self.write_buffer_full()
+            && self.stream.stream_ref().readable()
+    }
+
+    // Read until the stream would block, or a chunk has been read.
+    // Returns `true` if anything was read.
+    fn fill(&mut self) -> bool {
+        let mut chunk = [0u8; READ_CHUNK];
+        match self.stream.read(&mut chunk) {
+            Ok(0) => {
+                self.eof = true;
+                false
+            }
+            Ok(n) => {
+                self.read_buf.extend_from_slice(&chunk[..n]);
+                true
+            }
+            Err(ref e) if e.kind() == WouldBlock => false,
+            Err(e) => {
+                self.close(e.into());
+                false
+            }
+        }
+    }
+
+    /// Write as much of the buffered responses as possible.
+    pub fn flush(&mut self) {
+        while self.stream.stream_ref().writable() && !self.write_buf.is_empty() {
+            match self.stream.write(&self.write_buf) {
+                Ok(0) => {
+                    self.closed = true;
+                    break;
+                }
+                Ok(n) => { self.write_buf.drain(..n); }
+                Err(ref e) if e.kind() == WouldBlock => break,
+                Err(e) => {
+                    self.close(e.into());
+                    break;
+                }
+            }
+        }
+
+        match self.stream.flush() {
+            Err(ref e) if e.kind() == WouldBlock => {}
+            Err(e) => self.close(e.into()),
+            Ok(()) => {}
+        }
+    }
+
+    fn close(&mut self, error: Error) {
+        self.closed = true;
+        self.error = Some(error);
+    }
+
+    /// `true` if more than the write buffer limit is waiting to be written
+    pub fn write_buffer_full(&self) -> bool {
+        self.write_buf.len() > self.write_buffer_limit
+    }
+
+    /// Bytes waiting to be written
+    pub fn pending_writes(&self) -> usize {
+        self.write_buf.len()
+    }
+
+    /// Number of requests without a response
+    pub fn pending_requests(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// `true` once the connection is done and should be dropped:
+    /// the stream failed, or the connection (or the peer) is closing and
+    /// every response is written.
+    pub fn is_closed(&self) -> bool {
+        self.closed
+            || ((self.closing || self.eof)
+                && self.pending.is_empty()
+                && self.streaming.is_none()
+                && self.failed.is_none()
+                && self.write_buf.is_empty())
+    }
+
+    /// The error that closed the connection, if any.
+    /// Requests that fail to parse are reported as `Error::Http`.
+    pub fn take_error(&mut self) -> Option<Error> {
+        self.error.take()
+    }
+
+    /// The `Token` of the underlying stream
+    pub fn token(&self) -> Token {
+        self.stream.token()
+    }
+
+    /// Consume the connection and return the underlying stream.
+    /// Any buffered data is lost.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<S> StreamRef for HttpConnection<S>
+where
+    S: StreamRef,
+{
+    type Evented = S::Evented;
+
+    fn stream_ref(&self) -> &Stream<Self::Evented> {
+        self.stream.stream_ref()
+    }
+
+    fn stream_mut(&mut self) -> &mut Stream<Self::Evented> {
+        self.stream.stream_mut()
+    }
+}
+
+impl<S> Reactor for HttpConnection<S>
+where
+    S: StreamRef + Read + Write,
+{
+    type Input = Response;
+    type Output = Request;
+
+    fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
+        match reaction {
+            Reaction::Event(event) => {
+                if event.token() != self.token() {
+                    return event.into();
+                }
+
+                self.stream.stream_mut().react(event.into());
+                match self.next_request() {
+                    Some(request) => Reaction::Value(request),
+                    None => Reaction::Continue,
+                }
+            }
+            Reaction::Value(response) => {
+                if let Err(e) = self.respond(response) {
+                    self.close(e);
+                }
+                Reaction::Continue
+            }
+            Reaction::Continue => match self.next_request() {
+                Some(request) => Reaction::Value(request),
+                None => Reaction::Continue,
+            },
+        }
+    }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        tokens.push(self.token());
+    }
+}
+
+impl<S> TakeError for HttpConnection<S>
+where
+    S: StreamRef + Read + Write,
+{
+    fn take_error(&mut self) -> Option<Error> {
+        self.error.take()
+    }
+}
+
+impl<S: Debug> Debug for HttpConnection<S> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("HttpConnection")
+            .field("stream", &self.stream)
+            .field("pending_requests", &self.pending.len())
+            .field("pending_writes", &self.write_buf.len())
+            .field("closing", &self.closing)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(bytes: &[u8]) -> std::result::Result<Option<Request>, ParseError> {
+        RequestParser::new().parse(&mut bytes.to_vec())
+    }
+
+    #[test]
+    fn parse_get() {
+        let request = parse(b"GET /search?q=sonr HTTP/1.1\r\nHost: localhost\r\nX-A: 1\r\nx-a: 2\r\n\r\n")
+            .unwrap()
+            .unwrap();
+        assert_eq!(request.method(), "GET");
+        assert_eq!(request.path(), "/search");
+        assert_eq!(request.query(), Some("q=sonr"));
+        assert_eq!(request.version(), Version::Http11);
+        assert_eq!(request.header("host"), Some("localhost"));
+        assert_eq!(request.header("x-a"), Some("1"));
+        assert_eq!(request.headers().len(), 3);
+        assert!(request.body().is_empty());
+    }
+
+    #[test]
+    fn parse_byte_by_byte() {
+        let bytes = b"POST / HTTP/1.1\r\nContent-Length: 3\r\n\r\nabcGET /next HTTP/1.1\r\n\r\n";
+        let mut parser = RequestParser::new();
+        let mut buf = Vec::new();
+        let mut requests = Vec::new();
+        for b in bytes.iter() {
+            buf.push(*b);
+            if let Some(request) = parser.parse(&mut buf).unwrap() {
+                requests.push(request);
+            }
+        }
+
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].body(), b"abc");
+        assert_eq!(requests[1].path(), "/next");
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn parse_pipelined() {
+        let mut parser = RequestParser::new();
+        let mut buf = b"GET /a HTTP/1.1\r\n\r\n\r\nGET /b HTTP/1.1\r\n\r\nGET /c".to_vec();
+        assert_eq!(parser.parse(&mut buf).unwrap().unwrap().path(), "/a");
+        assert_eq!(parser.parse(&mut buf).unwrap().unwrap().path(), "/b");
+        assert!(parser.parse(&mut buf).unwrap().is_none());
+        assert_eq!(buf, b"GET /c");
+    }
+
+    #[test]
+    fn parse_chunked() {
+        let mut parser = RequestParser::new();
+        let mut buf = b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n5;ext=1\r\nhello\r\n".to_vec();
+        assert!(parser.parse(&mut buf).unwrap().is_none());
+        buf.extend_from_slice(b"6\r\n world\r\n0\r\nTrailer: x\r\n\r\n");
+        let request = parser.parse(&mut buf).unwrap().unwrap();
+        assert_eq!(request.body(), b"hello world");
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn keep_alive() {
+        let request = |head: &[u8]| parse(head).unwrap().unwrap();
+        assert!(request(b"GET / HTTP/1.1\r\n\r\n").keep_alive());
+        assert!(!request(b"GET / HTTP/1.1\r\nConnection: Close\r\n\r\n").keep_alive());
+        assert!(!request(b"GET / HTTP/1.0\r\n\r\n").keep_alive());
+        assert!(request(b"GET / HTTP/1.0\r\nConnection: keep-alive\r\n\r\n").keep_alive());
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(parse(b"GET /\r\n\r\n").unwrap_err(), ParseError::BadRequest);
+        assert_eq!(parse(b"GET / HTTP/2.0\r\n\r\n").unwrap_err(), ParseError::UnsupportedVersion);
+        assert_eq!(parse(b"GET / HTTP/1.1\r\nBad Name: x\r\n\r\n").unwrap_err(), ParseError::BadRequest);
+        assert_eq!(parse(b"GET / HTTP/1.1\r\nA: b\r\n folded\r\n\r\n").unwrap_err(), ParseError::BadRequest);
+        assert_eq!(
+            parse(b"GET / HTTP/1.1\r\nA: b\nTransfer-Encoding: chunked\r\n\r\n").unwrap_err(),
+            ParseError::BadRequest
+        );
+        assert_eq!(parse(b"GET / HTTP/1.1\r\nA: b\rc\r\n\r\n").unwrap_err(), ParseError::BadRequest);
+        assert_eq!(parse(b"GET / HTTP/1.1\r\nA: b\0c\r\n\r\n").unwrap_err(), ParseError::BadRequest);
+        assert_eq!(parse(b"GET / HTTP/1.1\r\nA: b\tc\r\n\r\n").unwrap().unwrap().header("a"), Some("b\tc"));
+        assert_eq!(
+            parse(b"POST / HTTP/1.1\r\nContent-Length: 1\r\nContent-Length: 2\r\n\r\n").unwrap_err(),
+            ParseError::BadRequest
+        );
+        assert_eq!(
+            parse(b"POST / HTTP/1.1\r\nContent-Length: 1\r\nTransfer-Encoding: chunked\r\n\r\n").unwrap_err(),
+            ParseError::BadRequest
+        );
+        assert_eq!(
+            parse(b"POST / HTTP/1.1\r\nTransfer-Encoding: gzip, chunked\r\n\r\n").unwrap_err(),
+            ParseError::UnsupportedEncoding
+        );
+        assert_eq!(
+            parse(b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\nxyz\r\n").unwrap_err(),
+            ParseError::BadRequest
+        );
+    }
+
+    #[test]
+    fn limits() {
+        let mut parser = RequestParser::new().max_headers_size(32);
+        let mut buf = b"GET / HTTP/1.1\r\nX-Long: aaaaaaaaaaaaaaaaaaaa".to_vec();
+        assert_eq!(parser.parse(&mut buf).unwrap_err(), ParseError::HeadersTooLarge);
+
+        let mut parser = RequestParser::new().max_body_size(4);
+        let mut buf = b"POST / HTTP/1.1\r\nContent-Length: 5\r\n\r\n".to_vec();
+        assert_eq!(parser.parse(&mut buf).unwrap_err(), ParseError::BodyTooLarge);
+
+        let mut parser = RequestParser::new().max_body_size(4);
+        let mut buf = b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n3\r\nabc\r\n2\r\n".to_vec();
+        assert_eq!(parser.parse(&mut buf).unwrap_err(), ParseError::BodyTooLarge);
+    }
+
+    #[test]
+    fn expect_continue() {
+        let mut parser = RequestParser::new();
+        let mut buf = b"PUT / HTTP/1.1\r\nExpect: 100-continue\r\nContent-Length: 2\r\n\r\n".to_vec();
+        assert!(parser.parse(&mut buf).unwrap().is_none());
+        assert!(parser.take_expect_continue());
+        assert!(!parser.take_expect_continue());
+    }
+
+    #[test]
+    fn encode_response() {
+        let response = Response::new(404).header("Content-Length", "99").body("gone");
+        let mut buf = Vec::new();
+        encode_head(&response, Version::Http10, Framing::Length(4), false, &mut buf);
+        assert_eq!(
+            buf,
+            &b"HTTP/1.1 404 Not Found\r\nContent-Length: 4\r\nConnection: keep-alive\r\n\r\n"[..]
+        );
+
+        let mut buf = Vec::new();
+        encode_head(&Response::new(204), Version::Http11, Framing::Length(0), true, &mut buf);
+        assert_eq!(buf, &b"HTTP/1.1 204 No Content\r\nConnection: close\r\n\r\n"[..]);
+    }
+
+    #[test]
+    fn invalid_response_headers() {
+        assert!(Response::new(200).header("X-A", "b\tc").valid_headers());
+        assert!(!Response::new(200).header("X-A", "b\r\nSet-Cookie: c").valid_headers());
+        assert!(!Response::new(200).header("X-A", "b\nc").valid_headers());
+        assert!(!Response::new(200).header("X A", "b").valid_headers());
+        assert!(!Response::new(200).header("X-A\r\nB", "c").valid_headers());
+    }
+}
@@ -1,7 +1,25 @@
 //! Network Reactors
 
 pub mod tcp; 
+pub mod udp;
 pub mod stream;
+pub mod codec;
+pub mod connections;
+pub mod permit;
+pub mod connect;
+pub mod reconnect;
+pub mod resolve;
+
+mod datagram;
+
+#[cfg(feature = "tls")]
+pub mod tls;
+
+#[cfg(feature = "http")]
+pub mod http;
 
 #[cfg(unix)]
 pub mod uds;
+
+#[cfg(unix)]
+pub mod fd;
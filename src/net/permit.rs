@@ -0,0 +1,237 @@
+//! Limit the number of concurrent connections.
+//!
+//! A [`Permits`] hands out up to `max` [`Permit`]s at a time.
+//! A permit is returned when it's dropped, and every listener waiting for a permit
+//! is woken up (with a [`SignalSender`], so from any thread).
+//!
+//! Attach a permit to a stream with [`Stream::set_permit`] to return the permit
+//! once the stream is dropped.
+//!
+//! [`Permits`]: struct.Permits.html
+//! [`Permit`]: struct.Permit.html
+//! [`Stream::set_permit`]: ../stream/struct.Stream.html#method.set_permit
+//! [`SignalSender`]: ../../sync/signal/struct.SignalSender.html
+use std::fmt::{self, Debug, Formatter};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+use crate::sync::signal::SignalSender;
+
+struct Waiting {
+    id: usize,
+    waker: SignalSender<()>,
+    armed: bool,
+}
+
+struct State {
+    max: usize,
+    active: AtomicUsize,
+    next_id: AtomicUsize,
+    waiting: Mutex<Vec<Waiting>>,
+}
+
+/// A pool of permits, shared between clones.
+///
+/// Both the pool and the permits are `Send`, so a permit can follow a
+/// connection to another thread (e.g. a [`Runtime`] worker) and be returned there.
+///
+/// [`Runtime`]: ../../runtime/struct.Runtime.html
+#[derive(Clone)]
+pub struct Permits {
+    state: Arc<State>,
+}
+
+impl Permits {
+    /// Create a new pool of `max` permits
+    pub fn new(max: usize) -> Self {
+        Self {
+            state: Arc::new(State {
+                max,
+                active: AtomicUsize::new(0),
+                next_id: AtomicUsize::new(0),
+                waiting: Mutex::new(Vec::new()),
+            }),
+        }
+    }
+
+    /// Acquire a permit, if one is available
+    pub fn acquire(&self) -> Option<Permit> {
+        let mut active = self.state.active.load(Ordering::Acquire);
+        loop {
+            if active >= self.state.max {
+                return None;
+            }
+
+            match self.state.active.compare_exchange_weak(active, active + 1, Ordering::AcqRel, Ordering::Acquire) {
+                Ok(_) => break,
+                Err(current) => active = current,
+            }
+        }
+
+        Some(Permit {
+            state: self.state.clone(),
+        })
+    }
+
+    /// Register `waker` with the pool. Call [`Waiter::notify`] to have a signal
+    /// sent with `waker` once a permit is returned.
+    ///
+    /// Any number of wakers can be registered with the same pool,
+    /// and each waker stays registered until the [`Waiter`] is dropped.
+    ///
+    /// [`Waiter`]: struct.Waiter.html
+    /// [`Waiter::notify`]: struct.Waiter.html#method.notify
+    pub fn waiter(&self, waker: SignalSender<()>) -> Waiter {
+        let id = self.state.next_id.fetch_add(1, Ordering::Relaxed);
+        self.state.waiting.lock().push(Waiting { id, waker, armed: false });
+        Waiter {
+            id,
+            state: self.state.clone(),
+        }
+    }
+
+    /// Number of permits currently held
+    pub fn active(&self) -> usize {
+        self.state.active.load(Ordering::Acquire)
+    }
+
+    /// Number of permits available
+    pub fn available(&self) -> usize {
+        self.state.max.saturating_sub(self.active())
+    }
+
+    /// The maximum number of permits
+    pub fn max(&self) -> usize {
+        self.state.max
+    }
+}
+
+impl Debug for Permits {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("Permits")
+            .field("max", &self.state.max)
+            .field("active", &self.active())
+            .finish()
+    }
+}
+
+/// A permit from a [`Permits`] pool.
+/// The permit is returned to the pool when it's dropped, on any thread.
+///
+/// [`Permits`]: struct.Permits.html
+pub struct Permit {
+    state: Arc<State>,
+}
+
+impl Debug for Permit {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("Permit").finish()
+    }
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        self.state.active.fetch_sub(1, Ordering::AcqRel);
+
+        // Wake everyone waiting, as there is no telling which
+        // of them still has a connection to accept
+        for waiting in self.state.waiting.lock().iter_mut().filter(|w| w.armed) {
+            waiting.armed = false;
+            let _ = waiting.waker.send(());
+        }
+    }
+}
+
+/// A waker registered with a [`Permits`] pool.
+/// The waker is removed from the pool when the `Waiter` is dropped.
+///
+/// [`Permits`]: struct.Permits.html
+pub struct Waiter {
+    id: usize,
+    state: Arc<State>,
+}
+
+impl Waiter {
+    /// Send a signal with the waker once a permit is returned.
+    /// The waker is signalled once per call to `notify`.
+    pub fn notify(&self) {
+        if let Some(waiting) = self.state.waiting.lock().iter_mut().find(|w| w.id == self.id) {
+            waiting.armed = true;
+        }
+    }
+}
+
+impl Debug for Waiter {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("Waiter").field("id", &self.id).finish()
+    }
+}
+
+impl Drop for Waiter {
+    fn drop(&mut self) {
+        self.state.waiting.lock().retain(|w| w.id != self.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn acquire_and_release() {
+        let permits = Permits::new(2);
+        let first = permits.acquire().unwrap();
+        let _second = permits.acquire().unwrap();
+        assert!(permits.acquire().is_none());
+        assert_eq!(permits.available(), 0);
+
+        drop(first);
+        assert_eq!(permits.active(), 1);
+        assert!(permits.acquire().is_some());
+    }
+
+    #[test]
+    fn release_on_another_thread() {
+        use crate::sync::signal::SignalReceiver;
+
+        let permits = Permits::new(1);
+        let receiver = SignalReceiver::<()>::unbounded();
+        let waiter = permits.waiter(receiver.sender());
+        waiter.notify();
+
+        let permit = permits.acquire().unwrap();
+        std::thread::spawn(move || drop(permit)).join().unwrap();
+
+        assert_eq!(permits.available(), 1);
+        assert!(receiver.try_recv().is_ok());
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn wake_every_waiter() {
+        use crate::sync::signal::SignalReceiver;
+
+        let permits = Permits::new(1);
+        let first = SignalReceiver::<()>::unbounded();
+        let second = SignalReceiver::<()>::unbounded();
+        let first_waiter = permits.waiter(first.sender());
+        let second_waiter = permits.waiter(second.sender());
+        first_waiter.notify();
+        second_waiter.notify();
+
+        drop(permits.acquire().unwrap());
+        assert!(first.try_recv().is_ok());
+        assert!(second.try_recv().is_ok());
+
+        // Only waiters that asked to be notified are signalled,
+        // and a dropped waiter is removed from the pool
+        second_waiter.notify();
+        drop(first_waiter);
+        drop(permits.acquire().unwrap());
+        assert!(first.try_recv().is_err());
+        assert!(second.try_recv().is_ok());
+        assert_eq!(permits.state.waiting.lock().len(), 1);
+    }
+}
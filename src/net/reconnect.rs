@@ -0,0 +1,552 @@
+//! A client stream that reconnects when the connection is lost.
+//!
+//! See [`ReconnectingStream`].
+//!
+//! [`ReconnectingStream`]: struct.ReconnectingStream.html
+use std::collections::hash_map::RandomState;
+use std::collections::VecDeque;
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{BuildHasher, Hasher};
+use std::io::{self, ErrorKind::WouldBlock, Read, Write};
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+use mio::{Evented, Ready, Token};
+
+use crate::errors::Result;
+use crate::net::stream::{self, Stream};
+use crate::reactor::timer::TimerToken;
+use crate::reactor::{EventedReactor, Reaction, Reactor};
+use crate::system::System;
+
+// -----------------------------------------------------------------------------
+// 		- Dial -
+// -----------------------------------------------------------------------------
+/// Open a new connection, e.g. to a `SocketAddr` (tcp) or a `PathBuf` (unix domain socket).
+pub trait Dial {
+    /// The connected stream
+    type Stream: Read + Write + Evented;
+
+    /// Start connecting. The connection is not established until
+    /// `is_connected` returns `true`.
+    fn dial(&mut self) -> io::Result<Self::Stream>;
+
+    /// Check if the connection is established once the stream is writable.
+    /// Returns `Ok(false)` if the stream is still connecting,
+    /// and the error if the connection failed.
+    fn is_connected(stream: &Self::Stream) -> io::Result<bool>;
+}
+
+impl Dial for SocketAddr {
+    type Stream = mio::net::TcpStream;
+
+    fn dial(&mut self) -> io::Result<Self::Stream> {
+        mio::net::TcpStream::connect(self)
+    }
+
+    fn is_connected(stream: &Self::Stream) -> io::Result<bool> {
+        stream::is_connected(stream.take_error(), stream.peer_addr())
+    }
+}
+
+#[cfg(unix)]
+impl Dial for std::path::PathBuf {
+    type Stream = mio_uds::UnixStream;
+
+    fn dial(&mut self) -> io::Result<Self::Stream> {
+        mio_uds::UnixStream::connect(self)
+    }
+
+    fn is_connected(stream: &Self::Stream) -> io::Result<bool> {
+        stream::is_connected(stream.take_error(), stream.peer_addr())
+    }
+}
+
+// -----------------------------------------------------------------------------
+// 		- Backoff -
+// -----------------------------------------------------------------------------
+/// Exponential backoff between connection attempts.
+///
+/// The delay starts at `initial`, and is multiplied by `multiplier` after each
+/// failed attempt, up to `max`. Each delay is randomised by up to `jitter`
+/// (a fraction of the delay) in either direction, so clients that lost their
+/// connection at the same time don't reconnect at the same time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Backoff {
+    /// The delay before the first reconnect
+    pub initial: Duration,
+    /// The longest delay.
+    /// The stream stops reconnecting if the delay is too large to represent
+    /// (e.g. `Duration::MAX`).
+    pub max: Duration,
+    /// The factor the delay grows by after each failed attempt
+    pub multiplier: f64,
+    /// The random part of the delay, between `0.0` and `1.0`
+    pub jitter: f64,
+}
+
+impl Default for Backoff {
+    /// 100 ms doubling up to 30 s, with a jitter of 20%
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_millis(100),
+            max: Duration::from_secs(30),
+            multiplier: 2.0,
+            jitter: 0.2,
+        }
+    }
+}
+
+impl Backoff {
+    /// The delay before attempt number `attempt` (starting at zero)
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let max = self.max.as_secs_f64();
+        let delay = (self.initial.as_secs_f64() * self.multiplier.powi(attempt as i32)).min(max);
+
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        let random = RandomState::new().build_hasher().finish() as f64 / u64::MAX as f64;
+        let delay = delay * (1.0 - jitter + 2.0 * jitter * random);
+        Duration::try_from_secs_f64(delay.min(max)).unwrap_or(self.max)
+    }
+}
+
+/// What to do with writes while the stream is disconnected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WritePolicy {
+    /// Buffer up to `max` bytes, and write them once the stream reconnects.
+    /// Writes that don't fit in the buffer fail with `io::ErrorKind::WouldBlock`.
+    Buffer {
+        /// Maximum number of buffered bytes
+        max: usize,
+    },
+
+    /// Fail writes with `io::ErrorKind::NotConnected`
+    Reject,
+}
+
+/// The output of a [`ReconnectingStream`]
+///
+/// [`ReconnectingStream`]: struct.ReconnectingStream.html
+#[derive(Debug)]
+pub enum Link {
+    /// The stream connected (or reconnected)
+    Connected,
+
+    /// The connection was lost, and the stream is reconnecting
+    Disconnected(io::Error),
+
+    /// The connected stream is readable and/or writable
+    Ready,
+}
+
+enum State<S: Read + Write + Evented> {
+    Connecting(EventedReactor<S>),
+    Connected(Stream<S>),
+    Waiting,
+    Closed,
+}
+
+// -----------------------------------------------------------------------------
+// 		- Reconnecting stream -
+// -----------------------------------------------------------------------------
+/// A client stream that dials again whenever the connection is lost,
+/// waiting between attempts according to its [`Backoff`].
+///
+/// The stream reacts with [`Link::Connected`] and [`Link::Disconnected`] as the
+/// connection comes and goes, and with [`Link::Ready`] when the connected stream
+/// is readable or writable (see [`readable`] and [`writable`]).
+///
+/// The connection is considered lost once a read returns zero bytes,
+/// or a read or write fails. The [`Link::Disconnected`] reaction follows
+/// once the stream reacts to `Reaction::Continue`.
+///
+/// While disconnected, writes are buffered or rejected according to the [`WritePolicy`]
+/// (buffering up to 64 KiB by default).
+///
+/// Like a [`Stream`], a reconnecting stream is usually part of a larger reactor:
+///
+///```no_run
+/// # use std::io::{Read, Write};
+/// # use std::net::SocketAddr;
+/// # use sonr::prelude::*;
+/// # use sonr::errors::Result;
+/// use sonr::net::reconnect::{Link, ReconnectingStream};
+///
+/// struct Upstream {
+///     stream: ReconnectingStream<SocketAddr>,
+/// }
+///
+/// impl Reactor for Upstream {
+///     type Input = ();
+///     type Output = ();
+///
+///     fn react(&mut self, reaction: Reaction<()>) -> Reaction<()> {
+///         match self.stream.react(reaction) {
+///             Reaction::Value(Link::Connected) => {
+///                 let _ = self.stream.write(b"hello\n");
+///                 Reaction::Value(())
+///             }
+///             Reaction::Value(Link::Ready) => {
+///                 let mut buf = [0u8; 1024];
+///                 while self.stream.readable() {
+///                     match self.stream.read(&mut buf) {
+///                         Ok(0) | Err(_) => break,
+///                         Ok(n) => eprintln!("{:?}", &buf[..n]),
+///                     }
+///                 }
+///                 Reaction::Value(())
+///             }
+///             Reaction::Value(Link::Disconnected(e)) => {
+///                 eprintln!("lost the connection: {}", e);
+///                 Reaction::Value(())
+///             }
+///             Reaction::Event(event) => Reaction::Event(event),
+///             Reaction::Continue => Reaction::Continue,
+///         }
+///     }
+/// }
+///
+/// fn main() -> Result<()> {
+///     System::init()?;
+///     let addr: SocketAddr = "127.0.0.1:9000".parse()?;
+///     System::start(Upstream { stream: ReconnectingStream::new(addr)? })?;
+///     Ok(())
+/// }
+/// ```
+///
+/// [`Backoff`]: struct.Backoff.html
+/// [`WritePolicy`]: enum.WritePolicy.html
+/// [`Link::Connected`]: enum.Link.html#variant.Connected
+/// [`Link::Disconnected`]: enum.Link.html#variant.Disconnected
+/// [`Link::Ready`]: enum.Link.html#variant.Ready
+/// [`readable`]: struct.ReconnectingStream.html#method.readable
+/// [`writable`]: struct.ReconnectingStream.html#method.writable
+/// [`Stream`]: ../stream/struct.Stream.html
+pub struct ReconnectingStream<D: Dial> {
+    dialer: D,
+    state: State<D::Stream>,
+    timer: TimerToken,
+    backoff: Backoff,
+    attempt: u32,
+    connect_timeout: Duration,
+    policy: WritePolicy,
+    buffer: Vec<u8>,
+    links: VecDeque<Link>,
+}
+
+impl<D: Dial> ReconnectingStream<D> {
+    /// Create a stream and start connecting
+    pub fn new(dialer: D) -> Result<Self> {
+        let mut stream = Self {
+            dialer,
+            state: State::Waiting,
+            timer: TimerToken::new()?,
+            backoff: Backoff::default(),
+            attempt: 0,
+            connect_timeout: Duration::from_secs(10),
+            policy: WritePolicy::Buffer { max: 64 * 1024 },
+            buffer: Vec::new(),
+            links: VecDeque::new(),
+        };
+        stream.dial();
+        Ok(stream)
+    }
+
+    /// Set the backoff between connection attempts
+    pub fn set_backoff(&mut self, backoff: Backoff) {
+        self.backoff = backoff;
+    }
+
+    /// Give up on a connection attempt after `timeout` (and try again after the backoff).
+    /// Default is 10 seconds. A timeout too large to represent (e.g. `Duration::MAX`)
+    /// never expires.
+    pub fn set_connect_timeout(&mut self, timeout: Duration) {
+        self.connect_timeout = timeout;
+    }
+
+    /// Set what to do with writes while disconnected
+    pub fn set_write_policy(&mut self, policy: WritePolicy) {
+        self.policy = policy;
+    }
+
+    /// Returns `true` if the stream is connected
+    pub fn is_connected(&self) -> bool {
+        matches!(self.state, State::Connected(_))
+    }
+
+    /// Is the connected stream readable?
+    pub fn readable(&self) -> bool {
+        match self.state {
+            State::Connected(ref stream) => stream.readable(),
+            _ => false,
+        }
+    }
+
+    /// Is the connected stream writable?
+    pub fn writable(&self) -> bool {
+        match self.state {
+            State::Connected(ref stream) => stream.writable(),
+            _ => false,
+        }
+    }
+
+    /// Number of bytes waiting to be written once the stream (re)connects
+    pub fn buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Reference the connected stream
+    pub fn get_ref(&self) -> Option<&Stream<D::Stream>> {
+        match self.state {
+            State::Connected(ref stream) => Some(stream),
+            _ => None,
+        }
+    }
+
+    /// Stop reconnecting, and close the connection (if any)
+    pub fn close(&mut self) {
+        self.timer.cancel();
+        self.state = State::Closed;
+    }
+
+    fn dial(&mut self) {
+        let socket = self
+            .dialer
+            .dial()
+            .map_err(Into::into)
+            .and_then(|socket| EventedReactor::new(socket, Ready::readable() | Ready::writable()));
+
+        match socket {
+            Ok(socket) => {
+                self.state = State::Connecting(socket);
+                match Instant::now().checked_add(self.connect_timeout) {
+                    Some(deadline) => self.timer.schedule(deadline),
+                    None => self.timer.cancel(),
+                }
+            }
+            Err(_) => self.wait(),
+        }
+    }
+
+    // Wait for the backoff before dialing again
+    fn wait(&mut self) {
+        self.state = State::Waiting;
+        let delay = self.backoff.delay(self.attempt);
+        self.attempt = self.attempt.saturating_add(1);
+        match Instant::now().checked_add(delay) {
+            Some(deadline) => self.timer.schedule(deadline),
+            // Too long to wait for, so don't reconnect
+            None => self.timer.cancel(),
+        }
+    }
+
+    fn disconnected(&mut self, e: io::Error) {
+        if let State::Connected(_) = self.state {
+            self.links.push_back(Link::Disconnected(e));
+            self.wait();
+        }
+    }
+
+    fn connected(&mut self, mut socket: EventedReactor<D::Stream>, readiness: Ready) {
+        self.timer.cancel();
+        self.attempt = 0;
+
+        socket.is_writable = true;
+        socket.is_readable = readiness.is_readable();
+        self.state = State::Connected(Stream::from(socket));
+        self.links.push_back(Link::Connected);
+        self.flush_buffer();
+    }
+
+    // Write the buffered bytes, until the stream would block
+    fn flush_buffer(&mut self) {
+        while !self.buffer.is_empty() {
+            let res = match self.state {
+                State::Connected(ref mut stream) => stream.write(&self.buffer),
+                _ => return,
+            };
+            match res {
+                Ok(n) => {
+                    self.buffer.drain(..n);
+                }
+                Err(ref e) if e.kind() == WouldBlock => return,
+                Err(e) => return self.disconnected(e),
+            }
+        }
+    }
+
+    fn next_link(&mut self) -> Reaction<Link> {
+        match self.links.pop_front() {
+            Some(link) => Reaction::Value(link),
+            None => Reaction::Continue,
+        }
+    }
+}
+
+impl<D: Dial> Reactor for ReconnectingStream<D> {
+    type Input = ();
+    type Output = Link;
+
+    fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
+        let event = match reaction {
+            Reaction::Event(event) => event,
+            _ => return self.next_link(),
+        };
+
+        if System::is_shutdown(&event) {
+            // Stop reconnecting, but leave the connection open
+            // for the owner to close.
+            if !self.is_connected() {
+                self.close();
+            }
+            return Reaction::Event(event);
+        }
+
+        let token = event.token();
+        if token == self.timer.token() {
+            match self.state {
+                // The connect timed out
+                State::Connecting(_) => self.wait(),
+                State::Waiting => self.dial(),
+                _ => {}
+            }
+            return self.next_link();
+        }
+
+        match std::mem::replace(&mut self.state, State::Closed) {
+            State::Connecting(socket) if socket.token() == token => match D::is_connected(socket.inner()) {
+                Ok(true) => self.connected(socket, event.readiness()),
+                Ok(false) => self.state = State::Connecting(socket),
+                Err(_) => self.wait(),
+            },
+            State::Connected(mut stream) if stream.token() == token => {
+                let ready = stream.react(Reaction::Event(event));
+                self.state = State::Connected(stream);
+                if let Reaction::Value(()) = ready {
+                    self.flush_buffer();
+                    if self.is_connected() {
+                        self.links.push_back(Link::Ready);
+                    }
+                }
+            }
+            state => {
+                self.state = state;
+                return Reaction::Event(event);
+            }
+        }
+
+        self.next_link()
+    }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        tokens.push(self.timer.token());
+        match self.state {
+            State::Connecting(ref socket) => tokens.push(socket.token()),
+            State::Connected(ref stream) => tokens.push(stream.token()),
+            _ => {}
+        }
+    }
+}
+
+impl<D: Dial> Read for ReconnectingStream<D> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let res = match self.state {
+            State::Connected(ref mut stream) => stream.read(buf),
+            _ => return Err(io::ErrorKind::NotConnected.into()),
+        };
+
+        match res {
+            Ok(0) if !buf.is_empty() => {
+                self.disconnected(io::ErrorKind::UnexpectedEof.into());
+                Ok(0)
+            }
+            Err(e) if e.kind() != WouldBlock => {
+                self.disconnected(io::Error::new(e.kind(), e.to_string()));
+                Err(e)
+            }
+            res => res,
+        }
+    }
+}
+
+impl<D: Dial> Write for ReconnectingStream<D> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // Buffered bytes are written first, to keep the order of the writes
+        if self.is_connected() {
+            self.flush_buffer();
+        }
+
+        if let State::Connected(ref mut stream) = self.state {
+            if self.buffer.is_empty() {
+                match stream.write(buf) {
+                    Err(ref e) if e.kind() == WouldBlock => return Err(WouldBlock.into()),
+                    Err(e) => {
+                        self.disconnected(io::Error::new(e.kind(), e.to_string()));
+                        return Err(e);
+                    }
+                    res => return res,
+                }
+            }
+        }
+
+        match self.policy {
+            WritePolicy::Reject if !self.is_connected() => Err(io::ErrorKind::NotConnected.into()),
+            WritePolicy::Buffer { max } if self.buffer.len() + buf.len() <= max => {
+                self.buffer.extend_from_slice(buf);
+                Ok(buf.len())
+            }
+            _ => Err(WouldBlock.into()),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.flush_buffer();
+        match self.state {
+            State::Connected(ref mut stream) => stream.flush(),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<D: Dial> Debug for ReconnectingStream<D> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let state = match self.state {
+            State::Connecting(_) => "connecting",
+            State::Connected(_) => "connected",
+            State::Waiting => "waiting",
+            State::Closed => "closed",
+        };
+        f.debug_struct("ReconnectingStream")
+            .field("state", &state)
+            .field("attempt", &self.attempt)
+            .field("buffered", &self.buffer.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_delay() {
+        let backoff = Backoff {
+            initial: Duration::from_millis(100),
+            max: Duration::from_secs(1),
+            multiplier: 2.0,
+            jitter: 0.0,
+        };
+        assert_eq!(backoff.delay(0), Duration::from_millis(100));
+        assert_eq!(backoff.delay(2), Duration::from_millis(400));
+        assert_eq!(backoff.delay(10), Duration::from_secs(1));
+
+        let unbounded = Backoff { max: Duration::MAX, ..backoff };
+        assert_eq!(unbounded.delay(100), Duration::MAX);
+
+        let jittered = Backoff { jitter: 0.5, ..backoff };
+        for _ in 0..100 {
+            let delay = jittered.delay(0);
+            assert!(delay >= Duration::from_millis(50) && delay <= Duration::from_millis(150));
+        }
+    }
+}
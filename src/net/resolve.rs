@@ -0,0 +1,499 @@
+//! Resolve host names without blocking the System.
+//!
+//! See [`Resolver`].
+//!
+//! [`Resolver`]: struct.Resolver.html
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{self, Debug, Formatter};
+use std::fs;
+use std::io;
+use std::net::{IpAddr, SocketAddr, ToSocketAddrs};
+use std::path::Path;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam::channel::{unbounded, Sender};
+use mio::Token;
+
+use crate::errors::Result;
+use crate::reactor::{Reaction, Reactor};
+use crate::sync::signal::{ReactiveSignalReceiver, SignalReceiver, SignalSender};
+
+// -----------------------------------------------------------------------------
+// 		- Hosts -
+// -----------------------------------------------------------------------------
+/// Host names and addresses from a hosts file (e.g. `/etc/hosts`).
+#[derive(Debug, Clone, Default)]
+pub struct Hosts {
+    names: HashMap<String, Vec<IpAddr>>,
+}
+
+impl Hosts {
+    /// Parse the contents of a hosts file.
+    /// Lines that don't start with an ip address are ignored.
+    pub fn parse(contents: &str) -> Self {
+        let mut names: HashMap<String, Vec<IpAddr>> = HashMap::new();
+        for line in contents.lines() {
+            let line = line.split('#').next().unwrap_or("");
+            let mut fields = line.split_whitespace();
+            let ip = match fields.next().and_then(|ip| ip.parse::<IpAddr>().ok()) {
+                Some(ip) => ip,
+                None => continue,
+            };
+            for name in fields {
+                let ips = names.entry(name.to_ascii_lowercase()).or_default();
+                if !ips.contains(&ip) {
+                    ips.push(ip);
+                }
+            }
+        }
+        Self { names }
+    }
+
+    /// Load a hosts file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self::parse(&fs::read_to_string(path)?))
+    }
+
+    /// Load `/etc/hosts`.
+    /// The hosts are empty if the file can not be read.
+    pub fn system() -> Self {
+        match cfg!(unix) {
+            true => Self::load("/etc/hosts").unwrap_or_default(),
+            false => Self::default(),
+        }
+    }
+
+    /// The addresses of a host name (host names are case insensitive)
+    pub fn get(&self, name: &str) -> Option<&[IpAddr]> {
+        self.names.get(&name.to_ascii_lowercase()).map(Vec::as_slice)
+    }
+}
+
+// -----------------------------------------------------------------------------
+// 		- Resolver pool -
+// -----------------------------------------------------------------------------
+struct Job {
+    host: String,
+    reply: SignalSender<Reply>,
+}
+
+struct Reply {
+    host: String,
+    ips: io::Result<Vec<IpAddr>>,
+}
+
+/// Threads resolving host names for one or more [`Resolver`]s,
+/// using the resolver of the operating system (`getaddrinfo`).
+///
+/// The threads of a pool created with `ResolverPool::new` stop once every clone
+/// of the pool, and every [`Resolver`] using it, is dropped.
+/// The threads of the [`global`] pool run for as long as the process does.
+///
+/// [`Resolver`]: struct.Resolver.html
+/// [`global`]: struct.ResolverPool.html#method.global
+#[derive(Clone)]
+pub struct ResolverPool {
+    jobs: Sender<Job>,
+}
+
+impl ResolverPool {
+    /// Start a pool with a number of threads
+    pub fn new(threads: usize) -> Result<Self> {
+        let (jobs, rx) = unbounded::<Job>();
+        for index in 0..threads.max(1) {
+            let rx = rx.clone();
+            thread::Builder::new().name(format!("sonr-resolver-{}", index)).spawn(move || {
+                for job in rx {
+                    let ips = (job.host.as_str(), 0)
+                        .to_socket_addrs()
+                        .map(|addrs| addrs.map(|addr| addr.ip()).collect());
+                    let _ = job.reply.send(Reply { host: job.host, ips });
+                }
+            })?;
+        }
+        Ok(Self { jobs })
+    }
+
+    /// The pool shared by every [`Resolver`] created with `Resolver::new`,
+    /// started with four threads the first time it's used.
+    /// The pool is never dropped, so the threads are never stopped.
+    ///
+    /// [`Resolver`]: struct.Resolver.html
+    pub fn global() -> Result<Self> {
+        static GLOBAL: Mutex<Option<ResolverPool>> = Mutex::new(None);
+        let mut global = GLOBAL.lock().unwrap_or_else(|e| e.into_inner());
+        match *global {
+            Some(ref pool) => Ok(pool.clone()),
+            None => {
+                let pool = Self::new(4)?;
+                *global = Some(pool.clone());
+                Ok(pool)
+            }
+        }
+    }
+}
+
+impl Debug for ResolverPool {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("ResolverPool").field("queued", &self.jobs.len()).finish()
+    }
+}
+
+// -----------------------------------------------------------------------------
+// 		- Resolver -
+// -----------------------------------------------------------------------------
+/// The addresses of a name received by a [`Resolver`]
+///
+/// [`Resolver`]: struct.Resolver.html
+#[derive(Debug)]
+pub struct Resolution {
+    /// The name as received by the resolver, e.g. `db.internal:5432`
+    pub name: String,
+
+    /// The resolved addresses, or the reason the name could not be resolved
+    pub addrs: io::Result<Vec<SocketAddr>>,
+}
+
+// Split `host:port` into the host and the port
+fn split_name(name: &str) -> io::Result<(&str, u16)> {
+    let invalid = || io::Error::new(io::ErrorKind::InvalidInput, format!("invalid address: {}", name));
+    let (host, port) = name.rsplit_once(':').ok_or_else(invalid)?;
+    let port = port.parse().map_err(|_| invalid())?;
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    match host.is_empty() {
+        true => Err(invalid()),
+        false => Ok((host, port)),
+    }
+}
+
+fn with_port(ips: &[IpAddr], port: u16) -> Vec<SocketAddr> {
+    ips.iter().map(|ip| SocketAddr::new(*ip, port)).collect()
+}
+
+// Resolved host names, and when they expire (`None` never expires)
+struct Cache {
+    entries: HashMap<String, (Vec<IpAddr>, Option<Instant>)>,
+    capacity: usize,
+}
+
+impl Cache {
+    fn new(capacity: usize) -> Self {
+        Self { entries: HashMap::new(), capacity }
+    }
+
+    fn get(&mut self, host: &str, now: Instant) -> Option<&[IpAddr]> {
+        let (_, expires) = self.entries.get(host)?;
+        if matches!(expires, Some(expires) if *expires <= now) {
+            self.entries.remove(host);
+            return None;
+        }
+        self.entries.get(host).map(|(ips, _)| ips.as_slice())
+    }
+
+    // Expired entries are removed first, then the entry
+    // that expires first if the cache is still full.
+    fn insert(&mut self, host: String, ips: Vec<IpAddr>, expires: Option<Instant>, now: Instant) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if !self.entries.contains_key(&host) && self.entries.len() >= self.capacity {
+            self.entries.retain(|_, (_, expires)| !matches!(expires, Some(expires) if *expires <= now));
+        }
+
+        if !self.entries.contains_key(&host) && self.entries.len() >= self.capacity {
+            let first = self.entries
+                .iter()
+                .min_by_key(|(_, (_, expires))| (expires.is_none(), *expires))
+                .map(|(host, _)| host.clone());
+            if let Some(first) = first {
+                self.entries.remove(&first);
+            }
+        }
+
+        self.entries.insert(host, (ips, expires));
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+/// Resolve names (`host:port`) received as input into socket addresses,
+/// and output a [`Resolution`] for each name.
+///
+/// Literal addresses and names in the hosts file (`/etc/hosts`) are resolved
+/// right away. Other names are resolved on the threads of a [`ResolverPool`],
+/// and the addresses are cached for the TTL of the resolver (one minute by default).
+/// At most 1024 host names are cached (see [`max_cached`]).
+/// A name being resolved is only sent to the pool once, regardless of
+/// how many times it's received in the meantime.
+///
+/// Resolved addresses can be chained to a [`TcpConnector`]:
+///
+///```no_run
+/// # use sonr::prelude::*;
+/// # use sonr::errors::Result;
+/// use sonr::net::connect::{Connect, TcpConnector};
+/// use sonr::net::resolve::{Resolution, Resolver};
+/// use sonr::reactor::producers::Mono;
+///
+/// fn main() -> Result<()> {
+///     System::init()?;
+///
+///     let run = Mono::new("db.internal:5432".to_string())?
+///         .chain(Resolver::new()?)
+///         .map(|resolution: Resolution| resolution.addrs.unwrap_or_default())
+///         .chain(TcpConnector::new())
+///         .map(|connect: Connect| {
+///             eprintln!("connected: {}", connect.is_ok());
+///             System::send(SystemEvent::Stop);
+///         });
+///
+///     System::start(run)?;
+///     Ok(())
+/// }
+/// ```
+///
+/// [`Resolution`]: struct.Resolution.html
+/// [`ResolverPool`]: struct.ResolverPool.html
+/// [`TcpConnector`]: ../connect/struct.TcpConnector.html
+/// [`max_cached`]: struct.Resolver.html#method.max_cached
+pub struct Resolver {
+    pool: ResolverPool,
+    replies: ReactiveSignalReceiver<Reply>,
+    hosts: Hosts,
+    ttl: Duration,
+    cache: Cache,
+    pending: HashMap<String, Vec<(String, u16)>>,
+    done: VecDeque<Resolution>,
+}
+
+impl Resolver {
+    /// Create a resolver using the global [`ResolverPool`] and `/etc/hosts`.
+    ///
+    /// [`ResolverPool`]: struct.ResolverPool.html
+    pub fn new() -> Result<Self> {
+        Self::with_pool(ResolverPool::global()?)
+    }
+
+    /// Create a resolver using a [`ResolverPool`] and `/etc/hosts`
+    ///
+    /// [`ResolverPool`]: struct.ResolverPool.html
+    pub fn with_pool(pool: ResolverPool) -> Result<Self> {
+        Ok(Self {
+            pool,
+            replies: ReactiveSignalReceiver::new(SignalReceiver::unbounded())?,
+            hosts: Hosts::system(),
+            ttl: Duration::from_secs(60),
+            cache: Cache::new(1024),
+            pending: HashMap::new(),
+            done: VecDeque::new(),
+        })
+    }
+
+    /// Replace the hosts (loaded from `/etc/hosts` by default)
+    pub fn hosts(mut self, hosts: Hosts) -> Self {
+        self.hosts = hosts;
+        self
+    }
+
+    /// How long resolved addresses are cached.
+    /// A TTL of zero disables the cache, and a TTL too large to represent
+    /// (e.g. `Duration::MAX`) caches addresses until they are evicted.
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// The most host names cached at once.
+    /// Once the cache is full, expired host names are removed,
+    /// followed by the host name closest to expiring.
+    /// Default is 1024.
+    pub fn max_cached(mut self, max: usize) -> Self {
+        self.cache.capacity = max;
+        self
+    }
+
+    /// Number of host names waiting to be resolved
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    fn resolve(&mut self, name: String) {
+        if let Ok(addr) = name.parse::<SocketAddr>() {
+            return self.done.push_back(Resolution { name, addrs: Ok(vec![addr]) });
+        }
+
+        let (host, port) = match split_name(&name) {
+            Ok((host, port)) => (host.to_ascii_lowercase(), port),
+            Err(e) => return self.done.push_back(Resolution { name, addrs: Err(e) }),
+        };
+
+        if let Ok(ip) = host.parse::<IpAddr>() {
+            return self.done.push_back(Resolution { name, addrs: Ok(vec![SocketAddr::new(ip, port)]) });
+        }
+
+        if let Some(ips) = self.hosts.get(&host) {
+            let addrs = Ok(with_port(ips, port));
+            return self.done.push_back(Resolution { name, addrs });
+        }
+
+        if let Some(ips) = self.cache.get(&host, Instant::now()) {
+            let addrs = Ok(with_port(ips, port));
+            return self.done.push_back(Resolution { name, addrs });
+        }
+
+        if let Some(waiting) = self.pending.get_mut(&host) {
+            return waiting.push((name, port));
+        }
+
+        let job = Job { host: host.clone(), reply: self.replies.sender() };
+        match self.pool.jobs.send(job) {
+            Ok(()) => {
+                self.pending.insert(host, vec![(name, port)]);
+            }
+            Err(_) => {
+                let addrs = Err(io::Error::other("the resolver threads have stopped"));
+                self.done.push_back(Resolution { name, addrs });
+            }
+        }
+    }
+
+    fn resolved(&mut self, reply: Reply) {
+        let waiting = self.pending.remove(&reply.host).unwrap_or_default();
+        match reply.ips {
+            Ok(ips) => {
+                for (name, port) in waiting {
+                    self.done.push_back(Resolution { name, addrs: Ok(with_port(&ips, port)) });
+                }
+                if !self.ttl.is_zero() {
+                    let now = Instant::now();
+                    self.cache.insert(reply.host, ips, now.checked_add(self.ttl), now);
+                }
+            }
+            Err(e) => {
+                for (name, _) in waiting {
+                    let addrs = Err(io::Error::new(e.kind(), e.to_string()));
+                    self.done.push_back(Resolution { name, addrs });
+                }
+            }
+        }
+    }
+
+    fn next_done(&mut self) -> Reaction<Resolution> {
+        match self.done.pop_front() {
+            Some(resolution) => Reaction::Value(resolution),
+            None => Reaction::Continue,
+        }
+    }
+}
+
+impl Reactor for Resolver {
+    type Input = String;
+    type Output = Resolution;
+
+    fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
+        match reaction {
+            // Resolutions are output once the resolver reacts to `Reaction::Continue`
+            // (the output of reacting to a value is discarded when chained).
+            Reaction::Value(name) => {
+                self.resolve(name);
+                Reaction::Continue
+            }
+            Reaction::Event(event) => {
+                if event.token() != self.replies.token() {
+                    return Reaction::Event(event);
+                }
+                while let Ok(reply) = self.replies.try_recv() {
+                    self.resolved(reply);
+                }
+                self.next_done()
+            }
+            Reaction::Continue => self.next_done(),
+        }
+    }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        tokens.push(self.replies.token());
+    }
+}
+
+impl Debug for Resolver {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("Resolver")
+            .field("pending", &self.pending.len())
+            .field("cached", &self.cache.len())
+            .field("ttl", &self.ttl)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hosts() {
+        let hosts = Hosts::parse(
+            "# comment\n\
+             127.0.0.1\tlocalhost db.internal # trailing comment\n\
+             ::1 localhost\n\
+             not-an-ip ignored\n",
+        );
+        let localhost: Vec<IpAddr> = vec!["127.0.0.1".parse().unwrap(), "::1".parse().unwrap()];
+        assert_eq!(hosts.get("localhost"), Some(localhost.as_slice()));
+        assert_eq!(hosts.get("DB.Internal"), Some(&localhost[..1]));
+        assert_eq!(hosts.get("ignored"), None);
+    }
+
+    #[test]
+    fn test_cache_capacity() {
+        let now = Instant::now();
+        let ips = vec!["127.0.0.1".parse::<IpAddr>().unwrap()];
+        let mut cache = Cache::new(2);
+
+        cache.insert("expired".into(), ips.clone(), Some(now), now);
+        cache.insert("late".into(), ips.clone(), Some(now + Duration::from_secs(20)), now);
+
+        // The expired entry makes room
+        cache.insert("early".into(), ips.clone(), Some(now + Duration::from_secs(10)), now);
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get("expired", now).is_none());
+
+        // The entry closest to expiring makes room
+        cache.insert("new".into(), ips.clone(), Some(now + Duration::from_secs(30)), now);
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get("early", now).is_none());
+        assert!(cache.get("late", now).is_some());
+        assert!(cache.get("new", now).is_some());
+    }
+
+    #[test]
+    fn test_cache_forever() {
+        let now = Instant::now();
+        let ips = vec!["127.0.0.1".parse::<IpAddr>().unwrap()];
+        let mut cache = Cache::new(2);
+
+        assert!(now.checked_add(Duration::MAX).is_none());
+        cache.insert("forever".into(), ips.clone(), None, now);
+        cache.insert("late".into(), ips.clone(), Some(now + Duration::from_secs(20)), now);
+        assert!(cache.get("forever", now + Duration::from_secs(3600)).is_some());
+
+        // An entry that never expires is evicted last
+        cache.insert("new".into(), ips.clone(), Some(now + Duration::from_secs(30)), now);
+        assert!(cache.get("forever", now).is_some());
+        assert!(cache.get("late", now).is_none());
+    }
+
+    #[test]
+    fn test_split_name() {
+        assert_eq!(split_name("db.internal:5432").unwrap(), ("db.internal", 5432));
+        assert_eq!(split_name("[::1]:80").unwrap(), ("::1", 80));
+        assert!(split_name("db.internal").is_err());
+        assert!(split_name("db.internal:http").is_err());
+        assert!(split_name(":80").is_err());
+    }
+}
@@ -2,12 +2,15 @@
 
 use std::fmt::{self, Debug, Formatter};
 use std::io::{self, Read, Write};
+use std::time::{Duration, Instant};
 
 use mio::{Evented, Ready, Token};
 
 use crate::errors::Result;
+use crate::net::permit::Permit;
 use crate::reactor::Reactor;
 use crate::reactor::{EventedReactor, Reaction};
+use crate::system::System;
 
 /// Anything that has a stream
 pub trait StreamRef {
@@ -26,6 +29,69 @@ pub trait StreamRef {
     }
 }
 
+// -----------------------------------------------------------------------------
+// 		- Deadline -
+// -----------------------------------------------------------------------------
+/// The deadline that caused a [`Stream`] to time out.
+///
+/// [`Stream`]: struct.Stream.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Deadline {
+    /// Nothing was read within the read idle timeout
+    ReadIdle,
+
+    /// A blocked write made no progress within the write idle timeout
+    WriteIdle,
+
+    /// The stream outlived its lifetime
+    Lifetime,
+}
+
+struct Deadlines {
+    read_idle: Option<Duration>,
+    write_idle: Option<Duration>,
+    lifetime: Option<Instant>,
+    last_read: Instant,
+    last_write: Instant,
+    // The write idle deadline is only armed while a write is blocked
+    write_blocked: bool,
+    timed_out: Option<Deadline>,
+}
+
+impl Deadlines {
+    fn new() -> Self {
+        let now = Instant::now();
+        Self {
+            read_idle: None,
+            write_idle: None,
+            lifetime: None,
+            last_read: now,
+            last_write: now,
+            write_blocked: false,
+            timed_out: None,
+        }
+    }
+
+    fn is_set(&self) -> bool {
+        self.read_idle.is_some() || self.write_idle.is_some() || self.lifetime.is_some()
+    }
+
+    // The earliest deadline, and which deadline it is
+    fn next(&self) -> Option<(Instant, Deadline)> {
+        let read = self.read_idle.map(|d| (self.last_read + d, Deadline::ReadIdle));
+        let write = self
+            .write_idle
+            .filter(|_| self.write_blocked)
+            .map(|d| (self.last_write + d, Deadline::WriteIdle));
+        let lifetime = self.lifetime.map(|l| (l, Deadline::Lifetime));
+
+        [read, write, lifetime]
+            .iter()
+            .filter_map(|deadline| *deadline)
+            .min_by_key(|(instant, _)| *instant)
+    }
+}
+
 // -----------------------------------------------------------------------------
 // 		- Stream -
 // -----------------------------------------------------------------------------
@@ -100,11 +166,45 @@ pub trait StreamRef {
 /// # }
 ///```
 ///
+/// # Deadlines
+///
+/// A stream can be given a read idle timeout, a write idle timeout and a lifetime.
+/// The deadlines are driven by the [`System`]s timers, using the stream's own token.
+///
+/// The write idle timeout only applies while a write is blocked: it starts when a write
+/// returns `WouldBlock` and is reset by every successful write, so a stream with nothing
+/// to write never times out on it.
+///
+/// Once a deadline expires the stream reacts with `Reaction::Value(())`, is marked as both
+/// readable and writable, and every subsequent read or write returns an
+/// `io::ErrorKind::TimedOut` error, so the stream is dropped like any other failed stream.
+/// [`timed_out`] returns the deadline that expired.
+///
+///```
+/// # use std::time::Duration;
+/// # use sonr::errors::Result;
+/// use sonr::prelude::*;
+/// use sonr::net::tcp::{ReactiveTcpStream, TcpStream};
+///
+/// fn reap_idle(stream: TcpStream) -> Result<ReactiveTcpStream> {
+///     let mut stream = ReactiveTcpStream::new(stream)?;
+///     stream.set_read_idle_timeout(Some(Duration::from_secs(10)));
+///     stream.set_lifetime(Some(Duration::from_secs(60 * 5)));
+///     Ok(stream)
+/// }
+/// # fn main() {
+/// # }
+///```
+///
 /// [`Stream`]: struct.Stream.html
 /// [`Ready`]: ../../struct.Ready.html
 /// [`Event`]: ../../struct.Event.html
+/// [`System`]: ../../system/struct.System.html
+/// [`timed_out`]: struct.Stream.html#method.timed_out
 pub struct Stream<T: Read + Write + Evented> {
     inner: EventedReactor<T>,
+    deadlines: Deadlines,
+    permit: Option<Permit>,
 }
 
 impl<T: Evented + Write + Read> AsRef<Stream<T>> for Stream<T> {
@@ -136,7 +236,12 @@ where
 
 impl<T: Read + Write + Evented> From<EventedReactor<T>> for Stream<T> {
     fn from(reactor: EventedReactor<T>) -> Self {
-        Self { inner: reactor }
+        System::track_in_flight(reactor.token());
+        Self { 
+            inner: reactor,
+            deadlines: Deadlines::new(),
+            permit: None,
+        }
     }
 }
 
@@ -144,7 +249,7 @@ impl<T: Read + Write + Evented> Stream<T> {
     /// Create a new stream
     pub fn new(inner: T) -> Result<Self> {
         let inner = EventedReactor::new(inner, Ready::readable() | Ready::writable())?;
-        Ok(Self { inner })
+        Ok(Self::from(inner))
     }
 
     /// The token used to track readiness of the underlying stream
@@ -171,6 +276,73 @@ impl<T: Read + Write + Evented> Stream<T> {
     pub fn inner_mut(&mut self) -> &mut T {
         self.inner.inner_mut()
     }
+
+    /// Time out the stream if nothing is read within `timeout`.
+    /// The idle time is measured from the last successful read (or from now).
+    /// Passing `None` removes the timeout.
+    pub fn set_read_idle_timeout(&mut self, timeout: Option<Duration>) {
+        self.deadlines.read_idle = timeout;
+        self.deadlines.last_read = Instant::now();
+        self.schedule_deadline();
+    }
+
+    /// Time out the stream if a blocked write makes no progress within `timeout`.
+    /// The idle time is measured from when a write returned `WouldBlock`, and the
+    /// timeout does not apply while there is nothing waiting to be written.
+    /// Passing `None` removes the timeout.
+    pub fn set_write_idle_timeout(&mut self, timeout: Option<Duration>) {
+        self.deadlines.write_idle = timeout;
+        self.deadlines.last_write = Instant::now();
+        self.schedule_deadline();
+    }
+
+    /// Time out the stream once `lifetime` has passed, regardless of activity.
+    /// Passing `None` removes the lifetime.
+    pub fn set_lifetime(&mut self, lifetime: Option<Duration>) {
+        self.deadlines.lifetime = lifetime.map(|l| Instant::now() + l);
+        self.schedule_deadline();
+    }
+
+    /// Hold on to a [`Permit`] until the stream is dropped.
+    ///
+    /// [`Permit`]: ../permit/struct.Permit.html
+    pub fn set_permit(&mut self, permit: Permit) {
+        self.permit = Some(permit);
+    }
+
+    /// The deadline that expired, if the stream has timed out.
+    pub fn timed_out(&self) -> Option<Deadline> {
+        self.deadlines.timed_out
+    }
+
+    // The timer is not rescheduled on every read and write.
+    // Instead the deadlines are checked once the timer expires, and
+    // the timer is rescheduled if the stream was active in the meantime.
+    fn schedule_deadline(&mut self) {
+        if self.deadlines.timed_out.is_some() {
+            return;
+        }
+
+        match self.deadlines.next() {
+            Some((deadline, _)) => System::schedule_timer(self.token(), deadline),
+            None => System::cancel_timer(self.token()),
+        }
+    }
+
+    fn check_deadline(&mut self) -> Option<Deadline> {
+        match self.deadlines.next() {
+            Some((deadline, kind)) if deadline <= Instant::now() => {
+                self.deadlines.timed_out = Some(kind);
+                self.inner.is_readable = true;
+                self.inner.is_writable = true;
+                Some(kind)
+            }
+            _ => {
+                self.schedule_deadline();
+                None
+            }
+        }
+    }
 }
 
 impl<T: Read + Write + Evented> Reactor for Stream<T> {
@@ -183,6 +355,17 @@ impl<T: Read + Write + Evented> Reactor for Stream<T> {
                 return reaction;
             }
 
+            // Timers react with an empty readiness
+            if event.readiness().is_empty() {
+                if self.deadlines.timed_out.is_some() {
+                    return Reaction::Continue;
+                }
+                return match self.check_deadline() {
+                    Some(_) => Reaction::Value(()),
+                    None => Reaction::Continue,
+                };
+            }
+
             self.inner.is_readable |= event.readiness().is_readable();
             self.inner.is_writable |= event.readiness().is_writable();
 
@@ -191,17 +374,93 @@ impl<T: Read + Write + Evented> Reactor for Stream<T> {
             reaction
         }
     }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        tokens.push(self.inner.token());
+    }
+}
+
+// The outcome of a non-blocking connect once the socket is writable, given the
+// socket's `take_error` and `peer_addr`: `Ok(true)` once connected, `Ok(false)` while
+// still connecting, and the error if the connect failed (reported through `SO_ERROR`).
+pub(crate) fn is_connected<A>(error: io::Result<Option<io::Error>>, peer_addr: io::Result<A>) -> io::Result<bool> {
+    if let Some(e) = error? {
+        return Err(e);
+    }
+    match peer_addr {
+        Ok(_) => Ok(true),
+        Err(ref e) if e.kind() == io::ErrorKind::NotConnected => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+fn timed_out_error(deadline: Deadline) -> io::Error {
+    io::Error::new(io::ErrorKind::TimedOut, format!("stream timed out: {:?}", deadline))
+}
+
+impl<T: Read + Write + Evented> Stream<T> {
+    // Run a read operation on the underlying object, updating
+    // the readiness and deadlines the same way `Read::read` does.
+    pub(crate) fn read_with<F>(&mut self, f: F) -> io::Result<usize>
+    where
+        F: FnOnce(&mut T) -> io::Result<usize>,
+    {
+        if let Some(deadline) = self.deadlines.timed_out {
+            self.inner.is_readable = false;
+            return Err(timed_out_error(deadline));
+        }
+
+        let res = self.inner.read_with(f);
+        if let Ok(n) = res {
+            if n > 0 && self.deadlines.is_set() {
+                self.deadlines.last_read = Instant::now();
+            }
+        }
+        res
+    }
+
+    // Run a write operation on the underlying object, updating
+    // the readiness and deadlines the same way `Write::write` does.
+    pub(crate) fn write_with<F>(&mut self, f: F) -> io::Result<usize>
+    where
+        F: FnOnce(&mut T) -> io::Result<usize>,
+    {
+        if let Some(deadline) = self.deadlines.timed_out {
+            self.inner.is_writable = false;
+            return Err(timed_out_error(deadline));
+        }
+
+        let res = self.inner.write_with(f);
+        match res {
+            Ok(n) if n > 0 && self.deadlines.is_set() => {
+                self.deadlines.last_write = Instant::now();
+                self.deadlines.write_blocked = false;
+            }
+            // Arm the write idle deadline
+            Err(ref e)
+                if e.kind() == io::ErrorKind::WouldBlock
+                    && self.deadlines.write_idle.is_some()
+                    && !self.deadlines.write_blocked =>
+            {
+                self.deadlines.last_write = Instant::now();
+                self.deadlines.write_blocked = true;
+                self.schedule_deadline();
+            }
+            _ => {}
+        }
+        res
+    }
 }
 
 impl<T: Read + Write + Evented> Read for Stream<T> {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        self.inner.read(buf)
+        self.read_with(|inner| inner.read(buf))
     }
 }
 
 impl<T: Read + Write + Evented> Write for Stream<T> {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        self.inner.write(buf)
+        self.write_with(|inner| inner.write(buf))
     }
 
     fn flush(&mut self) -> io::Result<()> {
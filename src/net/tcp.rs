@@ -1,14 +1,19 @@
 //! Reactive Tcp networking
 
-use std::io::ErrorKind::WouldBlock;
+use std::io::{self, ErrorKind::WouldBlock};
 use std::net::SocketAddr;
+use std::time::{Duration, Instant};
 
-use mio::{Ready, Token};
+use mio::{Event, Ready, Token};
+use net2::TcpBuilder;
 
-use crate::errors::Result;
+use crate::errors::{Error, Result};
+use crate::metrics::ListenerMetrics;
+use crate::net::permit::{Permit, Permits, Waiter};
 use crate::net::stream::{Stream, StreamRef};
-use crate::reactor::Reactor;
+use crate::reactor::{Reactor, TakeError};
 use crate::reactor::{EventedReactor, Reaction};
+use crate::sync::signal::{ReactiveSignalReceiver, SignalReceiver};
 use crate::system::System;
 
 // Re-exports
@@ -46,32 +51,219 @@ pub use mio::net::{TcpStream, TcpListener};
 ///     Ok(())
 /// }
 /// ```
+///
+/// Once the [`System`] is shutting down the listener is deregistered and the
+/// listening socket is closed, so new connections are refused rather than left
+/// waiting in the backlog.
+///
+/// If the process runs out of file descriptors (`EMFILE`) the listener stops
+/// accepting connections for a while (see [`set_retry_delay`]) rather than
+/// retrying in a loop. The error is available through [`take_error`].
+///
+/// [`System`]: ../../system/struct.System.html
+/// [`set_retry_delay`]: struct.ReactiveTcpListener.html#method.set_retry_delay
+/// [`take_error`]: struct.ReactiveTcpListener.html#method.take_error
 pub struct ReactiveTcpListener {
-    inner: EventedReactor<mio::net::TcpListener>,
+    // `None` once the listener is closed
+    inner: Option<EventedReactor<mio::net::TcpListener>>,
+    token: Token,
+    stream_options: StreamOptions,
+    max_accepts: Option<usize>,
+    accepted: usize,
+    retry_delay: Duration,
+    paused: bool,
+    error: Option<Error>,
+    metrics: Option<ListenerMetrics>,
 }
 
 impl ReactiveTcpListener {
     /// Create a new listener from a mio::TcpListener
     pub fn new(listener: mio::net::TcpListener) -> Result<Self> {
+        let inner = EventedReactor::new(listener, Ready::readable())?;
         Ok(Self {
-            inner: EventedReactor::new(listener, Ready::readable())?,
+            token: inner.token(),
+            inner: Some(inner),
+            stream_options: StreamOptions::default(),
+            max_accepts: None,
+            accepted: 0,
+            retry_delay: Duration::from_millis(100),
+            paused: false,
+            error: None,
+            metrics: None,
         })
     }
 
     /// Create a new listener from an address
     pub fn bind(addr: &str) -> Result<Self> {
-        Ok(Self {
-            inner: EventedReactor::new(
-                mio::net::TcpListener::bind(&addr.parse()?)?,
-                Ready::readable(),
-            )?,
-        })
+        Self::new(mio::net::TcpListener::bind(&addr.parse()?)?)
+    }
+
+    /// Create a [`TcpListenerBuilder`] for an address
+    ///
+    /// [`TcpListenerBuilder`]: struct.TcpListenerBuilder.html
+    pub fn builder(addr: &str) -> Result<TcpListenerBuilder> {
+        TcpListenerBuilder::new(addr)
     }
 
     /// Get `Token` registered with the listener;
     pub fn token(&self) -> Token {
-        self.inner.token()
+        self.token
+    }
+
+    /// The local address of the listener.
+    /// Returns an error once the listener is closed.
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        match self.inner {
+            Some(ref inner) => Ok(inner.inner().local_addr()?),
+            None => Err(io::Error::new(io::ErrorKind::NotConnected, "listener is closed").into()),
+        }
+    }
+
+    /// Options applied to every accepted stream
+    pub fn stream_options(&self) -> &StreamOptions {
+        &self.stream_options
+    }
+
+    /// Set the options applied to every accepted stream
+    pub fn set_stream_options(&mut self, options: StreamOptions) {
+        self.stream_options = options;
+    }
+
+    /// Accept at most `max` connections per poll iteration.
+    /// Any remaining connections are accepted in the next iteration,
+    /// giving other reactors a chance to run in between.
+    /// Passing `None` removes the limit (the default).
+    pub fn set_max_accepts_per_iteration(&mut self, max: Option<usize>) {
+        self.max_accepts = max;
+    }
+
+    /// How long to stop accepting connections when the process
+    /// (or the system) runs out of file descriptors.
+    /// Default is 100 ms.
+    pub fn set_retry_delay(&mut self, delay: Duration) {
+        self.retry_delay = delay;
+    }
+
+    /// Record the number of accepted connections (and failed accepts)
+    /// in the global metrics [`Registry`], labelled with `listener="name"`.
+    ///
+    /// [`Registry`]: ../../metrics/struct.Registry.html
+    pub fn register_metrics(&mut self, name: &str) {
+        self.metrics = Some(ListenerMetrics::new(name));
     }
+
+    /// Limit the number of concurrent connections to the number of permits.
+    /// See [`LimitedTcpListener`].
+    ///
+    /// [`LimitedTcpListener`]: struct.LimitedTcpListener.html
+    pub fn limit(self, permits: Permits) -> Result<LimitedTcpListener> {
+        let waker = ReactiveSignalReceiver::new(SignalReceiver::unbounded())?;
+        let waiter = permits.waiter(waker.sender());
+        Ok(LimitedTcpListener {
+            listener: self,
+            permits,
+            waker,
+            waiter,
+        })
+    }
+
+    // Wake up the listener in a later poll iteration
+    fn retry_in(&mut self, delay: Duration) {
+        System::schedule_timer(self.token(), Instant::now() + delay);
+    }
+
+    // Accept connections as if the listener reacted to its own event
+    fn resume(&mut self) {
+        self.paused = false;
+        self.accepted = 0;
+    }
+
+    // The token is freed once the listener is closed,
+    // and might belong to another reactor.
+    fn owns(&self, event: &Event) -> bool {
+        self.inner.is_some() && event.token() == self.token
+    }
+
+    // Stop listening, freeing the token
+    fn close(&mut self) {
+        if let Some(inner) = self.inner.take() {
+            if let Err(e) = System::deregister(&inner) {
+                self.error = Some(e);
+            }
+        }
+    }
+
+    fn accept(&mut self) -> Reaction<(mio::net::TcpStream, SocketAddr)> {
+        let inner = match self.inner {
+            Some(ref inner) if !self.paused => inner,
+            _ => return Reaction::Continue,
+        };
+
+        if let Some(max) = self.max_accepts {
+            if self.accepted >= max {
+                self.paused = true;
+                self.retry_in(Duration::from_millis(0));
+                return Reaction::Continue;
+            }
+        }
+
+        loop {
+            match inner.inner().accept() {
+                Ok((stream, addr)) => {
+                    // A stream that can't be configured is dropped
+                    if self.stream_options.apply(&stream).is_ok() {
+                        self.accepted += 1;
+                        if let Some(metrics) = self.metrics.as_ref() {
+                            metrics.accepted.inc();
+                        }
+                        return Reaction::Value((stream, addr));
+                    }
+                }
+                Err(ref e) if e.kind() == WouldBlock => {
+                    if let Err(e) = System::reregister(inner) {
+                        self.error = Some(e);
+                    }
+                    return Reaction::Continue;
+                }
+                // The connection was closed before it was accepted
+                Err(ref e) if is_connection_error(e) => continue,
+                Err(e) => {
+                    if let Some(metrics) = self.metrics.as_ref() {
+                        metrics.errors.inc();
+                    }
+                    // Pending connections are not signaled again (edge triggered),
+                    // so retry later rather than waiting for the next connection.
+                    if is_resource_error(&e) {
+                        self.paused = true;
+                        let delay = self.retry_delay;
+                        self.retry_in(delay);
+                    }
+                    self.error = Some(e.into());
+                    return Reaction::Continue;
+                }
+            }
+        }
+    }
+}
+
+fn is_connection_error(e: &io::Error) -> bool {
+    matches!(
+        e.kind(),
+        io::ErrorKind::ConnectionAborted | io::ErrorKind::ConnectionReset | io::ErrorKind::Interrupted
+    )
+}
+
+#[cfg(unix)]
+fn is_resource_error(e: &io::Error) -> bool {
+    matches!(
+        e.raw_os_error(),
+        Some(libc::EMFILE) | Some(libc::ENFILE) | Some(libc::ENOBUFS) | Some(libc::ENOMEM)
+    )
+}
+
+#[cfg(not(unix))]
+fn is_resource_error(e: &io::Error) -> bool {
+    e.kind() == io::ErrorKind::OutOfMemory
 }
 
 impl Reactor for ReactiveTcpListener {
@@ -80,38 +272,392 @@ impl Reactor for ReactiveTcpListener {
 
     fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
         if let Reaction::Event(event) = reaction {
-            if self.inner.token() == event.token() {
-                let res = self.inner.inner().accept();
-                match res {
-                    Ok(val) => return Reaction::Value(val),
-                    Err(ref e) if e.kind() == WouldBlock => {
-                        let _ = System::reregister(&self.inner);
-                        return Reaction::Continue
-                    }
-                    Err(_) => return Reaction::Continue,
-                }
-            } else {
-                return Reaction::Event(event);
+            if System::is_shutdown(&event) {
+                self.close();
             }
         }
 
-        if let Reaction::Continue = reaction {
-            let res = self.inner.inner().accept();
-            match res {
-                Ok(val) => return Reaction::Value(val),
-                Err(ref e) if e.kind() == WouldBlock => {
-                    let _ = System::reregister(&self.inner);
-                    return Reaction::Continue
+        match reaction {
+            Reaction::Event(event) if self.owns(&event) => {
+                self.resume();
+                self.accept()
+            }
+            Reaction::Event(event) => Reaction::Event(event),
+            Reaction::Continue => self.accept(),
+            Reaction::Value(()) => Reaction::Continue,
+        }
+    } 
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        if self.inner.is_some() {
+            tokens.push(self.token);
+        }
+    }
+}
+
+impl TakeError for ReactiveTcpListener {
+    fn take_error(&mut self) -> Option<Error> {
+        self.error.take()
+    }
+}
+
+// -----------------------------------------------------------------------------
+//              - Limited Tcp Listener -
+// -----------------------------------------------------------------------------
+/// A [`ReactiveTcpListener`] that only accepts a connection when a [`Permit`] is available,
+/// and outputs `(TcpStream, SocketAddr, Permit)`.
+///
+/// Hold on to the permit for as long as the connection is open, e.g. with
+/// [`Stream::set_permit`]. Once all permits are taken the listener stops accepting
+/// connections, and it starts again as soon as a permit is dropped (on any thread).
+/// The permit is taken before a connection is accepted, so several listeners can
+/// share the same [`Permits`] without accepting a connection there is no permit for.
+///
+///```no_run
+/// # use sonr::prelude::*;
+/// # use sonr::errors::Result;
+/// use sonr::net::tcp::{ReactiveTcpListener, ReactiveTcpStream, TcpStream};
+/// use sonr::net::permit::{Permit, Permits};
+///
+/// fn main() -> Result<()> {
+///     System::init()?;
+///
+///     let listener = ReactiveTcpListener::bind("127.0.0.1:5559")?
+///         .limit(Permits::new(1000))?
+///         .map(|(stream, _, permit): (TcpStream, _, Permit)| {
+///             let mut stream = ReactiveTcpStream::new(stream).unwrap();
+///             stream.set_permit(permit);
+///             stream
+///         });
+///
+///     System::start(listener)?;
+///     Ok(())
+/// }
+/// ```
+///
+/// [`ReactiveTcpListener`]: struct.ReactiveTcpListener.html
+/// [`Permit`]: ../permit/struct.Permit.html
+/// [`Permits`]: ../permit/struct.Permits.html
+/// [`Stream::set_permit`]: ../stream/struct.Stream.html#method.set_permit
+pub struct LimitedTcpListener {
+    listener: ReactiveTcpListener,
+    permits: Permits,
+    waker: ReactiveSignalReceiver<()>,
+    waiter: Waiter,
+}
+
+impl LimitedTcpListener {
+    /// The permits of the listener
+    pub fn permits(&self) -> &Permits {
+        &self.permits
+    }
+
+    /// Reference the listener
+    pub fn listener(&self) -> &ReactiveTcpListener {
+        &self.listener
+    }
+
+    /// Mutable reference to the listener
+    pub fn listener_mut(&mut self) -> &mut ReactiveTcpListener {
+        &mut self.listener
+    }
+
+    // Take a permit, or ask to be woken up once one is returned
+    // (unless one was returned in the meantime).
+    fn acquire(&self) -> Option<Permit> {
+        if let Some(permit) = self.permits.acquire() {
+            return Some(permit);
+        }
+
+        self.waiter.notify();
+        self.permits.acquire()
+    }
+}
+
+impl Reactor for LimitedTcpListener {
+    type Output = (mio::net::TcpStream, SocketAddr, Permit);
+    type Input = ();
+
+    fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
+        let reaction = match reaction {
+            // A permit was returned
+            Reaction::Event(event) if event.token() == self.waker.token() => {
+                while self.waker.try_recv().is_ok() {}
+                self.listener.resume();
+                Reaction::Continue
+            }
+            reaction => reaction,
+        };
+
+        let own_event = match reaction {
+            Reaction::Event(ref event) => self.listener.owns(event),
+            _ => true,
+        };
+
+        if !own_event {
+            return match self.listener.react(reaction) {
+                Reaction::Event(event) => Reaction::Event(event),
+                _ => Reaction::Continue,
+            };
+        }
+
+        // Wait for a permit to be returned.
+        // The permit is returned again if nothing is accepted.
+        let permit = match self.acquire() {
+            Some(permit) => permit,
+            None => return Reaction::Continue,
+        };
+
+        match self.listener.react(reaction) {
+            Reaction::Value((stream, addr)) => {
+                // The listener might not be called again before a permit
+                // is returned, so ask to be woken up right away.
+                if self.permits.available() == 0 {
+                    self.waiter.notify();
                 }
-                Err(_) => return Reaction::Continue,
+                Reaction::Value((stream, addr, permit))
             }
+            Reaction::Event(event) => Reaction::Event(event),
+            Reaction::Continue => Reaction::Continue,
         }
+    }
 
-        match reaction {
-            Reaction::Event(e) => Reaction::Event(e),
-            _ => Reaction::Continue,
-        } 
-    } 
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        self.listener.tokens(tokens);
+        tokens.push(self.waker.token());
+    }
+}
+
+impl TakeError for LimitedTcpListener {
+    fn take_error(&mut self) -> Option<Error> {
+        self.listener.take_error()
+    }
+}
+
+// -----------------------------------------------------------------------------
+//              - Stream options -
+// -----------------------------------------------------------------------------
+/// Socket options applied to a `TcpStream`.
+/// Options that are `None` are left as they are.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StreamOptions {
+    /// `TCP_NODELAY`
+    pub nodelay: Option<bool>,
+
+    /// `SO_KEEPALIVE`, and the time before the first keepalive probe
+    pub keepalive: Option<Option<Duration>>,
+
+    /// `SO_LINGER`
+    pub linger: Option<Option<Duration>>,
+
+    /// `SO_RCVBUF`
+    pub recv_buffer_size: Option<usize>,
+
+    /// `SO_SNDBUF`
+    pub send_buffer_size: Option<usize>,
+}
+
+impl StreamOptions {
+    /// Apply the options to a stream
+    pub fn apply(&self, stream: &mio::net::TcpStream) -> Result<()> {
+        if let Some(nodelay) = self.nodelay {
+            stream.set_nodelay(nodelay)?;
+        }
+
+        if let Some(keepalive) = self.keepalive {
+            stream.set_keepalive(keepalive)?;
+        }
+
+        if let Some(linger) = self.linger {
+            stream.set_linger(linger)?;
+        }
+
+        if let Some(size) = self.recv_buffer_size {
+            stream.set_recv_buffer_size(size)?;
+        }
+
+        if let Some(size) = self.send_buffer_size {
+            stream.set_send_buffer_size(size)?;
+        }
+
+        Ok(())
+    }
+}
+
+// -----------------------------------------------------------------------------
+//              - Tcp listener builder -
+// -----------------------------------------------------------------------------
+enum Socket {
+    Addr(SocketAddr),
+    Std(std::net::TcpListener),
+}
+
+/// Configure and create a [`ReactiveTcpListener`].
+///
+/// Every accepted stream has the stream options (`nodelay`, `keepalive`, `linger` and
+/// buffer sizes) applied before it's output.
+///
+/// With `reuse_port` several listeners, each running in their own [`System`] thread,
+/// can bind the same address and the kernel distributes the connections between them.
+///
+///```
+/// # use std::time::Duration;
+/// # use sonr::prelude::*;
+/// # use sonr::errors::Result;
+/// use sonr::net::tcp::ReactiveTcpListener;
+///
+/// fn main() -> Result<()> {
+///     System::init()?;
+///
+///     let listener = ReactiveTcpListener::builder("127.0.0.1:5558")?
+///         .backlog(128)
+///         .reuse_port(true)
+///         .nodelay(true)
+///         .keepalive(Some(Duration::from_secs(60)))
+///         .build()?;
+///
+///     assert_eq!(listener.local_addr()?, "127.0.0.1:5558".parse()?);
+///     Ok(())
+/// }
+/// ```
+///
+/// [`ReactiveTcpListener`]: struct.ReactiveTcpListener.html
+/// [`System`]: ../../system/struct.System.html
+pub struct TcpListenerBuilder {
+    socket: Socket,
+    backlog: i32,
+    reuse_address: bool,
+    reuse_port: bool,
+    only_v6: Option<bool>,
+    stream_options: StreamOptions,
+}
+
+impl TcpListenerBuilder {
+    /// Create a new builder for a listener bound to an address.
+    ///
+    /// The default backlog is 1024, and on unix `SO_REUSEADDR` is set by default.
+    pub fn new(addr: &str) -> Result<Self> {
+        Ok(Self::with_socket(Socket::Addr(addr.parse()?)))
+    }
+
+    /// Create a new builder from a listener that is already bound and listening,
+    /// for instance one inherited from a parent process.
+    ///
+    /// Only the stream options apply to such a listener: the backlog and the
+    /// socket options of the listener itself are ignored.
+    pub fn from_std(listener: std::net::TcpListener) -> Self {
+        Self::with_socket(Socket::Std(listener))
+    }
+
+    fn with_socket(socket: Socket) -> Self {
+        Self {
+            socket,
+            backlog: 1024,
+            reuse_address: cfg!(unix),
+            reuse_port: false,
+            only_v6: None,
+            stream_options: StreamOptions::default(),
+        }
+    }
+
+    /// The maximum number of pending connections
+    pub fn backlog(mut self, backlog: i32) -> Self {
+        self.backlog = backlog;
+        self
+    }
+
+    /// Set `SO_REUSEADDR`
+    pub fn reuse_address(mut self, reuse: bool) -> Self {
+        self.reuse_address = reuse;
+        self
+    }
+
+    /// Set `SO_REUSEPORT`, allowing several sockets to bind the same address.
+    #[cfg(unix)]
+    pub fn reuse_port(mut self, reuse: bool) -> Self {
+        self.reuse_port = reuse;
+        self
+    }
+
+    /// Set `IPV6_V6ONLY`.
+    /// Only applies to ipv6 addresses.
+    pub fn only_v6(mut self, only_v6: bool) -> Self {
+        self.only_v6 = Some(only_v6);
+        self
+    }
+
+    /// Set `TCP_NODELAY` on every accepted stream
+    pub fn nodelay(mut self, nodelay: bool) -> Self {
+        self.stream_options.nodelay = Some(nodelay);
+        self
+    }
+
+    /// Set `SO_KEEPALIVE` on every accepted stream
+    pub fn keepalive(mut self, keepalive: Option<Duration>) -> Self {
+        self.stream_options.keepalive = Some(keepalive);
+        self
+    }
+
+    /// Set `SO_LINGER` on every accepted stream
+    pub fn linger(mut self, linger: Option<Duration>) -> Self {
+        self.stream_options.linger = Some(linger);
+        self
+    }
+
+    /// Set `SO_RCVBUF` on every accepted stream
+    pub fn recv_buffer_size(mut self, size: usize) -> Self {
+        self.stream_options.recv_buffer_size = Some(size);
+        self
+    }
+
+    /// Set `SO_SNDBUF` on every accepted stream
+    pub fn send_buffer_size(mut self, size: usize) -> Self {
+        self.stream_options.send_buffer_size = Some(size);
+        self
+    }
+
+    /// Set all the options applied to every accepted stream
+    pub fn stream_options(mut self, options: StreamOptions) -> Self {
+        self.stream_options = options;
+        self
+    }
+
+    /// Create the listener.
+    /// Requires `System::init` to have been called.
+    pub fn build(self) -> Result<ReactiveTcpListener> {
+        let listener = match self.socket {
+            Socket::Addr(addr) => {
+                let builder = match addr {
+                    SocketAddr::V4(..) => TcpBuilder::new_v4()?,
+                    SocketAddr::V6(..) => {
+                        let builder = TcpBuilder::new_v6()?;
+                        if let Some(only_v6) = self.only_v6 {
+                            builder.only_v6(only_v6)?;
+                        }
+                        builder
+                    }
+                };
+
+                builder.reuse_address(self.reuse_address)?;
+
+                #[cfg(unix)]
+                {
+                    use net2::unix::UnixTcpBuilderExt;
+                    if self.reuse_port {
+                        builder.reuse_port(true)?;
+                    }
+                }
+
+                builder.bind(addr)?;
+                builder.listen(self.backlog)?
+            }
+            Socket::Std(listener) => listener,
+        };
+
+        let mut listener = ReactiveTcpListener::new(mio::net::TcpListener::from_std(listener)?)?;
+        listener.stream_options = self.stream_options;
+        Ok(listener)
+    }
 }
 
 /// A reactive tcp stream.
@@ -121,7 +667,12 @@ impl Reactor for ReactiveTcpListener {
 pub type ReactiveTcpStream = Stream<mio::net::TcpStream>;
 
 impl ReactiveTcpStream {
-    /// Create a new reactive tcp stream from a &SocketAddr
+    /// Create a new reactive tcp stream from a &SocketAddr.
+    ///
+    /// This returns right away, before the connection is established.
+    /// Use a [`TcpConnector`] to find out if (and when) the connection succeeded.
+    ///
+    /// [`TcpConnector`]: ../connect/struct.TcpConnector.html
     pub fn connect(addr: &SocketAddr) -> Result<Self> {
         let stream = mio::net::TcpStream::connect(addr)?;
         Ok(Self::new(stream)?)
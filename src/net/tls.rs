@@ -0,0 +1,577 @@
+//! TLS streams, using [rustls].
+//!
+//! Requires the `tls` feature.
+//!
+//! * A [`TlsStream`] encrypts a [`ReactiveTcpStream`], on either the client or the server side.
+//! * A [`TlsAcceptor`] performs the server handshake for the streams accepted by a listener.
+//! * [`SniCertificates`] selects a certificate by the server name the client asks for.
+//!
+//! Configurations are plain rustls configurations (`rustls` is re-exported),
+//! and [`server_config`] and [`client_config`] cover the common cases.
+//! Certificates and keys in PEM files can be loaded with
+//! `rustls::pki_types::pem::PemObject`.
+//!
+//! [rustls]: https://docs.rs/rustls
+//! [`TlsStream`]: struct.TlsStream.html
+//! [`ReactiveTcpStream`]: ../tcp/type.ReactiveTcpStream.html
+//! [`TlsAcceptor`]: struct.TlsAcceptor.html
+//! [`SniCertificates`]: struct.SniCertificates.html
+//! [`server_config`]: fn.server_config.html
+//! [`client_config`]: fn.client_config.html
+use std::collections::{HashMap, VecDeque};
+use std::convert::TryFrom;
+use std::fmt::{self, Debug, Formatter};
+use std::io::{self, ErrorKind::WouldBlock, Read, Write};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use mio::Token;
+use rustls::crypto::{ring, CryptoProvider};
+use rustls::pki_types::ServerName;
+use rustls::server::{ClientHello, ResolvesServerCert};
+use rustls::sign::CertifiedKey;
+use rustls::{ClientConfig, ClientConnection, Connection, RootCertStore, ServerConfig, ServerConnection};
+
+use crate::errors::{Error, Result};
+use crate::net::stream::{Stream, StreamRef};
+use crate::net::tcp::ReactiveTcpStream;
+use crate::reactor::{Reaction, Reactor};
+use crate::system::System;
+
+// Re-exports
+pub use rustls;
+pub use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+
+fn provider() -> Arc<CryptoProvider> {
+    Arc::new(ring::default_provider())
+}
+
+fn alpn_protocols(alpn: &[&[u8]]) -> Vec<Vec<u8>> {
+    alpn.iter().map(|protocol| protocol.to_vec()).collect()
+}
+
+fn certified_key(certs: Vec<CertificateDer<'static>>, key: &PrivateKeyDer<'static>) -> Result<Arc<CertifiedKey>> {
+    let key = ring::sign::any_supported_type(key)?;
+    Ok(Arc::new(CertifiedKey::new(certs, key)))
+}
+
+/// A server configuration with a single certificate chain,
+/// offering the ALPN protocols in `alpn` (e.g. `&[b"h2", b"http/1.1"]`).
+pub fn server_config(certs: Vec<CertificateDer<'static>>, key: PrivateKeyDer<'static>, alpn: &[&[u8]]) -> Result<Arc<ServerConfig>> {
+    let mut config = ServerConfig::builder_with_provider(provider())
+        .with_safe_default_protocol_versions()?
+        .with_no_client_auth()
+        .with_single_cert(certs, key)?;
+    config.alpn_protocols = alpn_protocols(alpn);
+    Ok(Arc::new(config))
+}
+
+/// A client configuration verifying servers against the root certificates in `roots`,
+/// offering the ALPN protocols in `alpn`.
+pub fn client_config(roots: &[CertificateDer<'static>], alpn: &[&[u8]]) -> Result<Arc<ClientConfig>> {
+    let mut store = RootCertStore::empty();
+    for root in roots {
+        store.add(root.clone())?;
+    }
+
+    let mut config = ClientConfig::builder_with_provider(provider())
+        .with_safe_default_protocol_versions()?
+        .with_root_certificates(store)
+        .with_no_client_auth();
+    config.alpn_protocols = alpn_protocols(alpn);
+    Ok(Arc::new(config))
+}
+
+// -----------------------------------------------------------------------------
+// 		- SNI certificates -
+// -----------------------------------------------------------------------------
+/// Select the certificate by the server name (SNI) sent by the client.
+///
+/// Clients that don't send a server name, or send a name without a certificate,
+/// get the default certificate (if any), otherwise the handshake fails.
+///
+///```no_run
+/// # use sonr::errors::Result;
+/// use sonr::net::tls::{CertificateDer, PrivateKeyDer, SniCertificates};
+///
+/// fn config(
+///     api: (Vec<CertificateDer<'static>>, PrivateKeyDer<'static>),
+///     www: (Vec<CertificateDer<'static>>, PrivateKeyDer<'static>),
+/// ) -> Result<()> {
+///     let mut certificates = SniCertificates::new();
+///     certificates.add("api.example.com", api.0, &api.1)?;
+///     certificates.add("www.example.com", www.0.clone(), &www.1)?;
+///     certificates.set_default(www.0, &www.1)?;
+///     let _config = certificates.server_config(&[b"http/1.1"])?;
+///     Ok(())
+/// }
+/// # fn main() {}
+/// ```
+#[derive(Debug, Default)]
+pub struct SniCertificates {
+    names: HashMap<String, Arc<CertifiedKey>>,
+    default: Option<Arc<CertifiedKey>>,
+}
+
+impl SniCertificates {
+    /// Create an empty set of certificates
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use a certificate chain for a server name (server names are case insensitive)
+    pub fn add(&mut self, name: &str, certs: Vec<CertificateDer<'static>>, key: &PrivateKeyDer<'static>) -> Result<()> {
+        let key = certified_key(certs, key)?;
+        self.names.insert(name.to_ascii_lowercase(), key);
+        Ok(())
+    }
+
+    /// Use a certificate chain for clients asking for an unknown server name, or none at all
+    pub fn set_default(&mut self, certs: Vec<CertificateDer<'static>>, key: &PrivateKeyDer<'static>) -> Result<()> {
+        self.default = Some(certified_key(certs, key)?);
+        Ok(())
+    }
+
+    /// A server configuration using the certificates,
+    /// offering the ALPN protocols in `alpn`.
+    pub fn server_config(self, alpn: &[&[u8]]) -> Result<Arc<ServerConfig>> {
+        let mut config = ServerConfig::builder_with_provider(provider())
+            .with_safe_default_protocol_versions()?
+            .with_no_client_auth()
+            .with_cert_resolver(Arc::new(self));
+        config.alpn_protocols = alpn_protocols(alpn);
+        Ok(Arc::new(config))
+    }
+}
+
+impl ResolvesServerCert for SniCertificates {
+    fn resolve(&self, client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        client_hello
+            .server_name()
+            .and_then(|name| self.names.get(&name.to_ascii_lowercase()))
+            .or(self.default.as_ref())
+            .cloned()
+    }
+}
+
+// -----------------------------------------------------------------------------
+// 		- Tls stream -
+// -----------------------------------------------------------------------------
+/// An encrypted [`ReactiveTcpStream`].
+///
+/// Reading and writing a `TlsStream` reads and writes plain text, and works like
+/// reading and writing the underlying stream: `readable` and `writable` are the
+/// readiness of the underlying stream, and both reads and writes return
+/// `io::ErrorKind::WouldBlock` once the stream would block.
+///
+/// The handshake is performed as the stream reacts to events (and as it's read from).
+/// While handshaking the stream reacts with `Reaction::Continue`, and written
+/// data is buffered until the handshake is complete.
+/// If the handshake (or any later TLS record) fails, every read and write
+/// returns the error, and the stream should be dropped.
+///
+/// On the server side, a [`TlsAcceptor`] only outputs streams that completed the handshake.
+///
+/// A `TlsStream` can be used anywhere a [`StreamRef`] is, e.g. with
+/// [`Connections`] or [`Framed`]. These react the underlying stream rather
+/// than the `TlsStream`, so a client handshake completes as the stream is read.
+/// Note that reading and writing the stream returned by `stream_mut` bypasses the encryption.
+///
+/// [`ReactiveTcpStream`]: ../tcp/type.ReactiveTcpStream.html
+/// [`TlsAcceptor`]: struct.TlsAcceptor.html
+/// [`StreamRef`]: ../stream/trait.StreamRef.html
+/// [`Connections`]: ../connections/struct.Connections.html
+/// [`Framed`]: ../codec/struct.Framed.html
+pub struct TlsStream {
+    stream: ReactiveTcpStream,
+    tls: Connection,
+    error: Option<io::Error>,
+}
+
+impl TlsStream {
+    /// The server side of a connection
+    pub fn server(stream: ReactiveTcpStream, config: Arc<ServerConfig>) -> Result<Self> {
+        let tls = ServerConnection::new(config)?;
+        Ok(Self::new(stream, tls.into()))
+    }
+
+    /// The client side of a connection to `server_name`.
+    /// The server's certificate is verified against the roots of the configuration,
+    /// and has to be valid for `server_name`.
+    pub fn client(stream: ReactiveTcpStream, config: Arc<ClientConfig>, server_name: &str) -> Result<Self> {
+        let server_name = ServerName::try_from(server_name.to_string())
+            .map_err(|e| Error::Io(io::Error::new(io::ErrorKind::InvalidInput, e)))?;
+        let tls = ClientConnection::new(config, server_name)?;
+        let mut stream = Self::new(stream, tls.into());
+
+        // Send the client hello, unless the socket is still connecting
+        let _ = stream.handshake();
+        Ok(stream)
+    }
+
+    fn new(stream: ReactiveTcpStream, tls: Connection) -> Self {
+        Self { stream, tls, error: None }
+    }
+
+    /// Returns `true` until the handshake is complete
+    pub fn is_handshaking(&self) -> bool {
+        self.tls.is_handshaking()
+    }
+
+    /// Is the underlying stream readable?
+    pub fn readable(&self) -> bool {
+        self.stream.readable()
+    }
+
+    /// Is the underlying stream writable?
+    pub fn writable(&self) -> bool {
+        self.stream.writable()
+    }
+
+    /// The ALPN protocol agreed on during the handshake
+    pub fn alpn_protocol(&self) -> Option<&[u8]> {
+        self.tls.alpn_protocol()
+    }
+
+    /// The server name (SNI) sent by the client.
+    /// Only available on the server side.
+    pub fn server_name(&self) -> Option<&str> {
+        match self.tls {
+            Connection::Server(ref server) => server.server_name(),
+            Connection::Client(_) => None,
+        }
+    }
+
+    /// The certificate chain of the peer, once the handshake is complete
+    pub fn peer_certificates(&self) -> Option<&[CertificateDer<'static>]> {
+        self.tls.peer_certificates()
+    }
+
+    /// The rustls connection
+    pub fn connection(&self) -> &Connection {
+        &self.tls
+    }
+
+    /// Mutable reference to the rustls connection
+    pub fn connection_mut(&mut self) -> &mut Connection {
+        &mut self.tls
+    }
+
+    /// Tell the peer the connection is closing (`close_notify`).
+    /// Nothing can be written after closing.
+    pub fn close(&mut self) -> io::Result<()> {
+        self.tls.send_close_notify();
+        self.write_tls()
+    }
+
+    fn check(&self) -> io::Result<()> {
+        match self.error {
+            Some(ref e) => Err(io::Error::new(e.kind(), e.to_string())),
+            None => Ok(()),
+        }
+    }
+
+    // Remember the error, so every later read and write fails with it
+    fn fail(&mut self, e: io::Error) -> io::Error {
+        let err = io::Error::new(e.kind(), e.to_string());
+        self.error = Some(e);
+        err
+    }
+
+    // Write TLS records until the socket would block
+    fn write_tls(&mut self) -> io::Result<()> {
+        while self.tls.wants_write() {
+            match self.tls.write_tls(&mut self.stream) {
+                Ok(_) => {}
+                Err(ref e) if e.kind() == WouldBlock => break,
+                Err(e) => return Err(self.fail(e)),
+            }
+        }
+        Ok(())
+    }
+
+    // Read TLS records from the socket.
+    // Returns `Ok(0)` if the peer closed the socket.
+    fn read_tls(&mut self) -> io::Result<usize> {
+        let n = match self.tls.read_tls(&mut self.stream) {
+            Err(ref e) if e.kind() == WouldBlock => return Err(WouldBlock.into()),
+            Err(e) => return Err(self.fail(e)),
+            Ok(n) => n,
+        };
+
+        if let Err(e) = self.tls.process_new_packets() {
+            // Send the alert to the peer
+            let _ = self.write_tls();
+            return Err(self.fail(io::Error::new(io::ErrorKind::InvalidData, e)));
+        }
+
+        // Handshake messages and key updates
+        self.write_tls()?;
+        Ok(n)
+    }
+
+    // Drive the handshake until it's complete or the socket would block
+    fn handshake(&mut self) -> io::Result<()> {
+        self.check()?;
+        self.write_tls()?;
+        while self.tls.is_handshaking() && !self.tls.wants_write() && self.stream.readable() {
+            match self.read_tls() {
+                Ok(0) => return Err(self.fail(io::ErrorKind::UnexpectedEof.into())),
+                Ok(_) => {}
+                Err(ref e) if e.kind() == WouldBlock => break,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Reactor for TlsStream {
+    type Output = ();
+    type Input = ();
+
+    fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
+        let event = match reaction {
+            Reaction::Event(event) if event.token() == self.stream.token() => event,
+            reaction => return reaction,
+        };
+
+        let reaction = self.stream.react(Reaction::Event(event));
+        if self.tls.is_handshaking() {
+            // A failed handshake reacts, so the error is noticed
+            return match self.handshake() {
+                Ok(()) if self.tls.is_handshaking() => Reaction::Continue,
+                _ => Reaction::Value(()),
+            };
+        }
+
+        // Anything written while the socket was blocking
+        let _ = self.write_tls();
+        reaction
+    }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        tokens.push(self.stream.token());
+    }
+}
+
+impl Read for TlsStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.check()?;
+        loop {
+            match self.tls.reader().read(buf) {
+                Err(ref e) if e.kind() == WouldBlock => {}
+                res => return res,
+            }
+
+            // No plain text left, read more records
+            if self.read_tls()? == 0 {
+                return Ok(0);
+            }
+        }
+    }
+}
+
+impl Write for TlsStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.check()?;
+
+        // Don't accept more data while earlier records are waiting on the socket
+        self.write_tls()?;
+        if self.tls.wants_write() {
+            return Err(WouldBlock.into());
+        }
+
+        match self.tls.writer().write(buf)? {
+            0 if !buf.is_empty() => Err(WouldBlock.into()),
+            n => {
+                self.write_tls()?;
+                Ok(n)
+            }
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.check()?;
+        self.tls.writer().flush()?;
+        self.write_tls()?;
+        match self.tls.wants_write() {
+            true => Err(WouldBlock.into()),
+            false => Ok(()),
+        }
+    }
+}
+
+impl StreamRef for TlsStream {
+    type Evented = mio::net::TcpStream;
+
+    fn stream_ref(&self) -> &Stream<Self::Evented> {
+        &self.stream
+    }
+
+    fn stream_mut(&mut self) -> &mut Stream<Self::Evented> {
+        &mut self.stream
+    }
+}
+
+impl Debug for TlsStream {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("TlsStream")
+            .field("stream", &self.stream)
+            .field("handshaking", &self.tls.is_handshaking())
+            .field("error", &self.error)
+            .finish()
+    }
+}
+
+// -----------------------------------------------------------------------------
+// 		- Tls acceptor -
+// -----------------------------------------------------------------------------
+/// Perform the server handshake for accepted connections, and output
+/// each [`TlsStream`] once the handshake is complete.
+///
+/// Connections that fail the handshake, or don't complete it within the
+/// handshake timeout (ten seconds by default), are dropped.
+///
+///```no_run
+/// # use std::io::Read;
+/// # use sonr::prelude::*;
+/// # use sonr::errors::Result;
+/// use sonr::net::tcp::ReactiveTcpListener;
+/// use sonr::net::connections::{Connections, Handled};
+/// use sonr::net::tls::{self, CertificateDer, PrivateKeyDer, TlsAcceptor, TlsStream};
+///
+/// fn serve(certs: Vec<CertificateDer<'static>>, key: PrivateKeyDer<'static>) -> Result<()> {
+///     System::init()?;
+///
+///     let config = tls::server_config(certs, key, &[b"http/1.1"])?;
+///     let listener = ReactiveTcpListener::bind("127.0.0.1:8443")?;
+///     let acceptor = TlsAcceptor::new(config);
+///
+///     let connections = Connections::new(|_, stream: &mut TlsStream| {
+///         let mut buf = [0u8; 1024];
+///         while stream.readable() {
+///             match stream.read(&mut buf) {
+///                 Ok(0) => return Handled::Close,
+///                 Ok(n) => eprintln!("{:?}", &buf[..n]),
+///                 Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => break,
+///                 Err(_) => return Handled::Close,
+///             }
+///         }
+///         Handled::<()>::Continue
+///     });
+///
+///     let run = listener.chain(acceptor).map(|(stream, _addr)| stream).chain(connections);
+///     System::start(run)?;
+///     Ok(())
+/// }
+/// # fn main() {}
+/// ```
+///
+/// [`TlsStream`]: struct.TlsStream.html
+pub struct TlsAcceptor {
+    config: Arc<ServerConfig>,
+    handshake_timeout: Duration,
+    handshakes: HashMap<Token, (TlsStream, SocketAddr)>,
+    done: VecDeque<(TlsStream, SocketAddr)>,
+}
+
+impl TlsAcceptor {
+    /// Create an acceptor with a server configuration
+    pub fn new(config: Arc<ServerConfig>) -> Self {
+        Self {
+            config,
+            handshake_timeout: Duration::from_secs(10),
+            handshakes: HashMap::new(),
+            done: VecDeque::new(),
+        }
+    }
+
+    /// Drop connections that haven't completed the handshake within `timeout`
+    pub fn handshake_timeout(mut self, timeout: Duration) -> Self {
+        self.handshake_timeout = timeout;
+        self
+    }
+
+    /// Number of connections handshaking
+    pub fn len(&self) -> usize {
+        self.handshakes.len()
+    }
+
+    /// Returns `true` if no connections are handshaking
+    pub fn is_empty(&self) -> bool {
+        self.handshakes.is_empty()
+    }
+
+    fn accept(&mut self, stream: mio::net::TcpStream, addr: SocketAddr) -> Result<()> {
+        let mut stream = ReactiveTcpStream::new(stream)?;
+        stream.set_lifetime(Some(self.handshake_timeout));
+        let stream = TlsStream::server(stream, self.config.clone())?;
+        self.handshakes.insert(stream.stream.token(), (stream, addr));
+        Ok(())
+    }
+
+    fn next_done(&mut self) -> Reaction<(TlsStream, SocketAddr)> {
+        match self.done.pop_front() {
+            Some(accepted) => Reaction::Value(accepted),
+            None => Reaction::Continue,
+        }
+    }
+}
+
+impl Reactor for TlsAcceptor {
+    type Input = (mio::net::TcpStream, SocketAddr);
+    type Output = (TlsStream, SocketAddr);
+
+    fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
+        match reaction {
+            // Streams are output once the acceptor reacts to `Reaction::Continue`
+            // (the output of reacting to a value is discarded when chained).
+            Reaction::Value((stream, addr)) => {
+                // Nothing to do about a stream that can't be registered
+                let _ = self.accept(stream, addr);
+                Reaction::Continue
+            }
+            Reaction::Event(event) => {
+                let token = event.token();
+                let (stream, _) = match self.handshakes.get_mut(&token) {
+                    Some(handshake) => handshake,
+                    None => return Reaction::Event(event),
+                };
+
+                if let Reaction::Continue = stream.react(Reaction::Event(event)) {
+                    return self.next_done();
+                }
+
+                // Complete or failed
+                let (mut stream, addr) = match self.handshakes.remove(&token) {
+                    Some(handshake) => handshake,
+                    None => return self.next_done(),
+                };
+                if stream.error.is_none() && !stream.is_handshaking() {
+                    stream.stream.set_lifetime(None);
+                    // The socket might have been drained while handshaking,
+                    // so wake up the stream for whoever receives it.
+                    System::schedule_timer(token, Instant::now());
+                    self.done.push_back((stream, addr));
+                }
+                self.next_done()
+            }
+            Reaction::Continue => self.next_done(),
+        }
+    }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        tokens.extend(self.handshakes.keys());
+    }
+}
+
+impl Debug for TlsAcceptor {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("TlsAcceptor")
+            .field("handshaking", &self.handshakes.len())
+            .field("handshake_timeout", &self.handshake_timeout)
+            .finish()
+    }
+}
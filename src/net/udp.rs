@@ -0,0 +1,207 @@
+//! Reactive Udp networking
+
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use mio::Token;
+
+use crate::errors::{Error, Result};
+use crate::net::datagram::Datagrams;
+use crate::reactor::{Reactor, TakeError};
+use crate::reactor::Reaction;
+
+// Re-exports
+pub use mio::net::UdpSocket;
+
+// -----------------------------------------------------------------------------
+//              - Udp Socket -
+// -----------------------------------------------------------------------------
+/// Receive datagrams and output `(Vec<u8>, SocketAddr)`, and send every
+/// `(Vec<u8>, SocketAddr)` received as input.
+///
+/// If the socket would block, outgoing datagrams are queued and sent once the socket
+/// is writable again. The queue is bounded (see [`set_send_queue_capacity`]), and
+/// datagrams that don't fit are dropped (see [`dropped`]).
+///
+/// Once the socket is connected (see [`connect`]) datagrams are only sent to,
+/// and received from, the connected address, and the address of any input is ignored.
+///
+///```
+/// # use sonr::prelude::*;
+/// # use sonr::errors::Result;
+/// use sonr::net::udp::ReactiveUdpSocket;
+///
+/// fn main() -> Result<()> {
+///     let system_signals = System::init()?;
+///
+///     let receiver = ReactiveUdpSocket::bind("127.0.0.1:5580")?;
+///     let mut sender = ReactiveUdpSocket::bind("127.0.0.1:0")?;
+///     sender.send_to(b"hello".to_vec(), "127.0.0.1:5580".parse()?)?;
+///
+///     let run = receiver.map(|(payload, addr)| {
+///         eprintln!("{:?} from {:?}", payload, addr);
+///         system_signals.send(SystemEvent::Stop);
+///     }).and(sender);
+///
+///     System::start(run)?;
+///     Ok(())
+/// }
+/// ```
+///
+/// [`set_send_queue_capacity`]: struct.ReactiveUdpSocket.html#method.set_send_queue_capacity
+/// [`dropped`]: struct.ReactiveUdpSocket.html#method.dropped
+/// [`connect`]: struct.ReactiveUdpSocket.html#method.connect
+pub struct ReactiveUdpSocket {
+    socket: Datagrams<UdpSocket>,
+    peer: Option<SocketAddr>,
+}
+
+impl ReactiveUdpSocket {
+    /// Create a new reactive udp socket from a mio::UdpSocket
+    pub fn new(socket: UdpSocket) -> Result<Self> {
+        Ok(Self {
+            socket: Datagrams::new(socket)?,
+            peer: None,
+        })
+    }
+
+    /// Create a new reactive udp socket bound to an address
+    pub fn bind(addr: &str) -> Result<Self> {
+        Self::new(UdpSocket::bind(&addr.parse()?)?)
+    }
+
+    /// Connect the socket to an address.
+    /// Only datagrams from this address are received, and every
+    /// datagram is sent to this address.
+    pub fn connect(&mut self, addr: SocketAddr) -> Result<()> {
+        self.socket.inner().connect(addr)?;
+        self.peer = Some(addr);
+        Ok(())
+    }
+
+    /// Get `Token` registered with the socket
+    pub fn token(&self) -> Token {
+        self.socket.token()
+    }
+
+    /// Reference the underlying socket
+    pub fn inner(&self) -> &UdpSocket {
+        self.socket.inner()
+    }
+
+    /// The local address of the socket
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.socket.inner().local_addr()?)
+    }
+
+    /// Set the maximum number of datagrams waiting to be sent.
+    /// Default is 1024.
+    pub fn set_send_queue_capacity(&mut self, capacity: usize) {
+        self.socket.set_send_queue_capacity(capacity);
+    }
+
+    /// Number of datagrams waiting to be sent
+    pub fn queued(&self) -> usize {
+        self.socket.queued()
+    }
+
+    /// Number of datagrams dropped because the send queue was full
+    pub fn dropped(&self) -> usize {
+        self.socket.dropped()
+    }
+
+    /// Allow sending to broadcast addresses
+    pub fn set_broadcast(&self, on: bool) -> Result<()> {
+        Ok(self.socket.inner().set_broadcast(on)?)
+    }
+
+    /// Join an ipv4 multicast group
+    pub fn join_multicast_v4(&self, multiaddr: &Ipv4Addr, interface: &Ipv4Addr) -> Result<()> {
+        Ok(self.socket.inner().join_multicast_v4(multiaddr, interface)?)
+    }
+
+    /// Leave an ipv4 multicast group
+    pub fn leave_multicast_v4(&self, multiaddr: &Ipv4Addr, interface: &Ipv4Addr) -> Result<()> {
+        Ok(self.socket.inner().leave_multicast_v4(multiaddr, interface)?)
+    }
+
+    /// Join an ipv6 multicast group
+    pub fn join_multicast_v6(&self, multiaddr: &Ipv6Addr, interface: u32) -> Result<()> {
+        Ok(self.socket.inner().join_multicast_v6(multiaddr, interface)?)
+    }
+
+    /// Leave an ipv6 multicast group
+    pub fn leave_multicast_v6(&self, multiaddr: &Ipv6Addr, interface: u32) -> Result<()> {
+        Ok(self.socket.inner().leave_multicast_v6(multiaddr, interface)?)
+    }
+
+    /// Receive multicast datagrams sent from this socket
+    pub fn set_multicast_loop_v4(&self, on: bool) -> Result<()> {
+        Ok(self.socket.inner().set_multicast_loop_v4(on)?)
+    }
+
+    /// Set the time-to-live of outgoing ipv4 multicast datagrams
+    pub fn set_multicast_ttl_v4(&self, ttl: u32) -> Result<()> {
+        Ok(self.socket.inner().set_multicast_ttl_v4(ttl)?)
+    }
+
+    /// Send a datagram to an address.
+    /// If the socket would block the datagram is queued.
+    /// If sending fails the datagram is dropped, and the error is
+    /// available through `take_error`.
+    ///
+    /// Returns `Error::NoCapacity` if the send queue is full.
+    pub fn send_to(&mut self, payload: Vec<u8>, addr: SocketAddr) -> Result<()> {
+        // A connected socket sends to the connected address
+        let target = match self.peer {
+            Some(_) => None,
+            None => Some(addr),
+        };
+        self.socket.enqueue(payload, target)
+    }
+
+    /// Send a datagram to the connected address.
+    /// See [`send_to`].
+    ///
+    /// Returns `Error::NoConnection` if the socket is not connected.
+    ///
+    /// [`send_to`]: struct.ReactiveUdpSocket.html#method.send_to
+    pub fn send(&mut self, payload: Vec<u8>) -> Result<()> {
+        match self.peer {
+            Some(addr) => self.send_to(payload, addr),
+            None => Err(Error::NoConnection(self.token())),
+        }
+    }
+
+    /// Send queued datagrams until the queue is empty or the socket would block.
+    pub fn flush(&mut self) -> Result<()> {
+        self.socket.flush()
+    }
+}
+
+impl Reactor for ReactiveUdpSocket {
+    type Output = (Vec<u8>, SocketAddr);
+    type Input = (Vec<u8>, SocketAddr);
+
+    fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
+        match reaction {
+            Reaction::Event(event) => self.socket.react_event(event),
+            Reaction::Value((payload, addr)) => {
+                if let Err(e) = self.send_to(payload, addr) {
+                    self.socket.set_error(e);
+                }
+                Reaction::Continue
+            }
+            Reaction::Continue => self.socket.recv(),
+        }
+    }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        tokens.push(self.socket.token());
+    }
+}
+
+impl TakeError for ReactiveUdpSocket {
+    fn take_error(&mut self) -> Option<Error> {
+        self.socket.take_error()
+    }
+}
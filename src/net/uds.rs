@@ -2,17 +2,19 @@
 
 use std::io::ErrorKind::WouldBlock;
 use std::os::unix::net::SocketAddr;
+use std::path::{Path, PathBuf};
 
 use mio::{Ready, Token};
 
-use crate::reactor::Reactor;
+use crate::reactor::{Reactor, TakeError};
 use crate::reactor::{Reaction, EventedReactor};
 use crate::system::System;
-use crate::errors::Result;
+use crate::errors::{Error, Result};
+use crate::net::datagram::Datagrams;
 use crate::net::stream::{Stream, StreamRef};
 
 // Re-exports
-pub use mio_uds::{UnixListener, UnixStream};
+pub use mio_uds::{UnixListener, UnixStream, UnixDatagram};
 
 // -----------------------------------------------------------------------------
 //              - Uds Listener -
@@ -47,24 +49,73 @@ pub use mio_uds::{UnixListener, UnixStream};
 ///     Ok(())
 /// }
 /// ```
+///
+/// Once the [`System`] is shutting down the listener is deregistered and the
+/// listening socket is closed, so new connections are refused.
+/// The socket file is left in place.
+///
+/// [`System`]: ../../system/struct.System.html
 pub struct ReactiveUdsListener {
-    inner: EventedReactor<UnixListener>,
+    // `None` once the listener is closed
+    inner: Option<EventedReactor<UnixListener>>,
+    token: Token,
+    error: Option<Error>,
 }
 
 impl ReactiveUdsListener {
     /// Create a new Reactive UnixListener
     pub fn bind(path: impl AsRef<str>) -> Result<Self> {
+        let inner = EventedReactor::new(
+            UnixListener::bind(path.as_ref())?,
+            Ready::readable(),
+        )?;
         Ok(Self {
-            inner: EventedReactor::new(
-                UnixListener::bind(path.as_ref())?,
-                Ready::readable(),
-            )?,
+            token: inner.token(),
+            inner: Some(inner),
+            error: None,
         })
     }
 
     /// Get `Token` registered with the listener;
     pub fn token(&self) -> Token {
-        self.inner.token()
+        self.token
+    }
+
+    // Stop listening, freeing the token
+    fn close(&mut self) {
+        if let Some(inner) = self.inner.take() {
+            if let Err(e) = System::deregister(&inner) {
+                self.error = Some(e);
+            }
+        }
+    }
+
+    fn accept(&mut self) -> Reaction<(UnixStream, SocketAddr)> {
+        let inner = match self.inner {
+            Some(ref inner) => inner,
+            None => return Reaction::Continue,
+        };
+
+        match inner.inner().accept() {
+            Ok(Some(val)) => Reaction::Value(val),
+            Ok(None) => Reaction::Continue,
+            Err(ref e) if e.kind() == WouldBlock => {
+                if let Err(e) = System::reregister(inner) {
+                    self.error = Some(e);
+                }
+                Reaction::Continue
+            }
+            Err(e) => {
+                self.error = Some(e.into());
+                Reaction::Continue
+            }
+        }
+    }
+}
+
+impl TakeError for ReactiveUdsListener {
+    fn take_error(&mut self) -> Option<Error> {
+        self.error.take()
     }
 }
 
@@ -74,39 +125,25 @@ impl Reactor for ReactiveUdsListener {
 
     fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
         if let Reaction::Event(event) = reaction {
-            if self.inner.token() == event.token() {
-                let res = self.inner.inner().accept();
-                match res {
-                    Ok(Some(val)) => return Reaction::Value(val),
-                    Ok(None) => return Reaction::Continue,
-                    Err(ref e) if e.kind() == WouldBlock => {
-                        System::reregister(&self.inner).unwrap();
-                        return Reaction::Continue
-                    }
-                    Err(_) => return Reaction::Continue,
-                }
+            if System::is_shutdown(&event) {
+                self.close();
             }
         }
 
-        if let Reaction::Continue = reaction {
-                let res = self.inner.inner().accept();
-                match res {
-                    Ok(Some(val)) => return Reaction::Value(val),
-                    Ok(None) => return Reaction::Continue,
-                    Err(ref e) if e.kind() == WouldBlock => {
-                        System::reregister(&self.inner).unwrap();
-                        return Reaction::Continue
-                    }
-                    Err(_) => return Reaction::Continue,
-                }
-        }
-
         match reaction {
-            Reaction::Event(e) => Reaction::Event(e),
-            Reaction::Continue => Reaction::Continue,
+            // The token is freed once the listener is closed
+            Reaction::Event(event) if self.inner.is_some() && self.token == event.token() => self.accept(),
+            Reaction::Event(event) => Reaction::Event(event),
+            Reaction::Continue => self.accept(),
             Reaction::Value(_) => Reaction::Continue,
         } 
     } 
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        if self.inner.is_some() {
+            tokens.push(self.token);
+        }
+    }
 }
 
 
@@ -132,3 +169,173 @@ impl StreamRef for ReactiveUdsStream {
         self
     }
 }
+
+// -----------------------------------------------------------------------------
+//              - Uds Datagram -
+// -----------------------------------------------------------------------------
+/// Receive datagrams and output `(Vec<u8>, SocketAddr)`, and send every
+/// `(Vec<u8>, PathBuf)` received as input.
+///
+/// If the socket would block, outgoing datagrams are queued and sent once the socket
+/// is writable again. The queue is bounded (see [`set_send_queue_capacity`]), and
+/// datagrams that don't fit are dropped (see [`dropped`]).
+///
+/// On Linux the socket can be bound and connected to an abstract socket address
+/// (see [`bind_abstract`] and [`connect_abstract`]).
+///
+///```
+/// # use std::fs::remove_file;
+/// # use sonr::prelude::*;
+/// # use sonr::errors::Result;
+/// use sonr::net::uds::ReactiveUdsDatagram;
+///
+/// fn main() -> Result<()> {
+///     let system_signals = System::init()?;
+///
+///     # remove_file("/tmp/sonr-uds-dgram-test.sock");
+///     let receiver = ReactiveUdsDatagram::bind("/tmp/sonr-uds-dgram-test.sock")?;
+///     let mut sender = ReactiveUdsDatagram::unbound()?;
+///     sender.send_to(b"hello".to_vec(), "/tmp/sonr-uds-dgram-test.sock")?;
+///
+///     let run = receiver.map(|(payload, addr)| {
+///         eprintln!("{:?} from {:?}", payload, addr);
+///         system_signals.send(SystemEvent::Stop);
+///     }).and(sender);
+///
+///     System::start(run)?;
+///     Ok(())
+/// }
+/// ```
+///
+/// [`set_send_queue_capacity`]: struct.ReactiveUdsDatagram.html#method.set_send_queue_capacity
+/// [`dropped`]: struct.ReactiveUdsDatagram.html#method.dropped
+/// [`bind_abstract`]: struct.ReactiveUdsDatagram.html#method.bind_abstract
+/// [`connect_abstract`]: struct.ReactiveUdsDatagram.html#method.connect_abstract
+pub struct ReactiveUdsDatagram {
+    socket: Datagrams<UnixDatagram>,
+}
+
+impl ReactiveUdsDatagram {
+    /// Create a new reactive unix datagram socket from a `UnixDatagram`
+    pub fn new(socket: UnixDatagram) -> Result<Self> {
+        Ok(Self {
+            socket: Datagrams::new(socket)?,
+        })
+    }
+
+    /// Create a socket bound to a path
+    pub fn bind(path: impl AsRef<Path>) -> Result<Self> {
+        Self::new(UnixDatagram::bind(path)?)
+    }
+
+    /// Create a socket that is not bound to a path.
+    /// Datagrams can be sent from an unbound socket, but the receiver can't reply.
+    pub fn unbound() -> Result<Self> {
+        Self::new(UnixDatagram::unbound()?)
+    }
+
+    /// Create an unbound socket connected to a path
+    pub fn connect(path: impl AsRef<Path>) -> Result<Self> {
+        let socket = UnixDatagram::unbound()?;
+        socket.connect(path)?;
+        Self::new(socket)
+    }
+
+    /// Create a socket bound to an abstract socket address
+    #[cfg(target_os = "linux")]
+    pub fn bind_abstract(name: &[u8]) -> Result<Self> {
+        use std::os::linux::net::SocketAddrExt;
+
+        let addr = SocketAddr::from_abstract_name(name)?;
+        let socket = std::os::unix::net::UnixDatagram::bind_addr(&addr)?;
+        Self::new(UnixDatagram::from_datagram(socket)?)
+    }
+
+    /// Create an unbound socket connected to an abstract socket address
+    #[cfg(target_os = "linux")]
+    pub fn connect_abstract(name: &[u8]) -> Result<Self> {
+        use std::os::linux::net::SocketAddrExt;
+
+        let addr = SocketAddr::from_abstract_name(name)?;
+        let socket = std::os::unix::net::UnixDatagram::unbound()?;
+        socket.connect_addr(&addr)?;
+        Self::new(UnixDatagram::from_datagram(socket)?)
+    }
+
+    /// Get `Token` registered with the socket
+    pub fn token(&self) -> Token {
+        self.socket.token()
+    }
+
+    /// Reference the underlying socket
+    pub fn inner(&self) -> &UnixDatagram {
+        self.socket.inner()
+    }
+
+    /// Set the maximum number of datagrams waiting to be sent.
+    /// Default is 1024.
+    pub fn set_send_queue_capacity(&mut self, capacity: usize) {
+        self.socket.set_send_queue_capacity(capacity);
+    }
+
+    /// Number of datagrams waiting to be sent
+    pub fn queued(&self) -> usize {
+        self.socket.queued()
+    }
+
+    /// Number of datagrams dropped because the send queue was full
+    pub fn dropped(&self) -> usize {
+        self.socket.dropped()
+    }
+
+    /// Send a datagram to a path.
+    /// If the socket would block the datagram is queued.
+    /// If sending fails the datagram is dropped, and the error is
+    /// available through `take_error`.
+    ///
+    /// Returns `Error::NoCapacity` if the send queue is full.
+    pub fn send_to(&mut self, payload: Vec<u8>, path: impl AsRef<Path>) -> Result<()> {
+        self.socket.enqueue(payload, Some(path.as_ref().to_path_buf()))
+    }
+
+    /// Send a datagram to the connected address.
+    /// See [`send_to`].
+    ///
+    /// [`send_to`]: struct.ReactiveUdsDatagram.html#method.send_to
+    pub fn send(&mut self, payload: Vec<u8>) -> Result<()> {
+        self.socket.enqueue(payload, None)
+    }
+
+    /// Send queued datagrams until the queue is empty or the socket would block.
+    pub fn flush(&mut self) -> Result<()> {
+        self.socket.flush()
+    }
+}
+
+impl Reactor for ReactiveUdsDatagram {
+    type Output = (Vec<u8>, SocketAddr);
+    type Input = (Vec<u8>, PathBuf);
+
+    fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
+        match reaction {
+            Reaction::Event(event) => self.socket.react_event(event),
+            Reaction::Value((payload, path)) => {
+                if let Err(e) = self.send_to(payload, path) {
+                    self.socket.set_error(e);
+                }
+                Reaction::Continue
+            }
+            Reaction::Continue => self.socket.recv(),
+        }
+    }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        tokens.push(self.socket.token());
+    }
+}
+
+impl TakeError for ReactiveUdsDatagram {
+    fn take_error(&mut self) -> Option<Error> {
+        self.socket.take_error()
+    }
+}
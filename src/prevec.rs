@@ -8,6 +8,19 @@ enum Entry<T> {
     Occupied(T)
 }
 
+/// How a [`PreVec`] grows when inserting above its capacity.
+///
+/// [`PreVec`]: struct.PreVec.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Growth {
+    /// Never grow; inserting above the capacity returns `Error::NoCapacity`
+    Fixed,
+    /// Double the capacity (the default)
+    Double,
+    /// Add a fixed number of slots
+    Linear(usize),
+}
+
 // -----------------------------------------------------------------------------
 // 		- PreVec -
 // -----------------------------------------------------------------------------
@@ -52,7 +65,7 @@ pub struct PreVec<T> {
     next: usize,
     offset: usize,
     length: usize,
-    can_grow: bool,
+    growth: Growth,
 }
 
 
@@ -80,7 +93,7 @@ impl<T> PreVec<T> {
             next: 0,
             offset: 0,
             length: 0,
-            can_grow: true,
+            growth: Growth::Double,
         }
     }
 
@@ -114,12 +127,32 @@ impl<T> PreVec<T> {
 
     /// Prevent inserting above the capacity.
     pub fn prevent_growth(&mut self) {
-        self.can_grow = false;
+        self.growth = Growth::Fixed;
     }
 
     /// Enable the collection to grow and allocate more space.
     pub fn enable_growth(&mut self) {
-        self.can_grow = true;
+        self.growth = Growth::Double;
+    }
+
+    /// Set how the collection grows when inserting above the capacity
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sonr::{Growth, PreVec};
+    /// let mut v = PreVec::with_capacity(2);
+    /// v.set_growth(Growth::Linear(3));
+    /// v.insert_at(2, "foo").unwrap();
+    /// assert_eq!(v.capacity(), 5);
+    /// ```
+    pub fn set_growth(&mut self, growth: Growth) {
+        self.growth = growth;
+    }
+
+    /// How the collection grows
+    pub fn growth(&self) -> Growth {
+        self.growth
     }
 
     /// Check if the index is within the range of the collection 
@@ -173,7 +206,7 @@ impl<T> PreVec<T> {
         if index >= self.inner.len() {
             // If the PreVec is not allowed to grow then 
             // return a NoCapacity error
-            if index >= self.capacity && !self.can_grow {
+            if index >= self.capacity && self.growth == Growth::Fixed {
                 return Err(Error::NoCapacity);
             }
 
@@ -186,7 +219,12 @@ impl<T> PreVec<T> {
                     .collect();
                 self.inner.append(&mut inner);
             } else {
-                self.capacity *= 2;
+                while index >= self.capacity {
+                    self.capacity = match self.growth {
+                        Growth::Linear(step) => self.capacity + step.max(1),
+                        _ => (self.capacity * 2).max(1),
+                    };
+                }
                 let mut inner: Vec<Entry<T>> = (len..self.capacity)
                     .map(|i| Entry::Empty(i+1))
                     .collect();
@@ -224,6 +262,41 @@ impl<T> PreVec<T> {
         Ok(index + self.offset)
     }
 
+    /// Insert a value at a specific index, returning the previous value (if any).
+    /// Inserting above capacity will cause reallocation if and only if the `PreVec` can grow.
+    ///
+    /// This is useful when the index is decided elsewhere, e.g. storing connections by
+    /// the `Token` reserved from the `System`.
+    ///
+    /// Note: a `PreVec` should use either `insert` or `insert_at`, not both, as
+    /// `insert_at` does not update the next available slot.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sonr::PreVec;
+    /// let mut v = PreVec::with_capacity(2);
+    /// assert_eq!(v.insert_at(5, "foo").unwrap(), None);
+    /// assert_eq!(v.insert_at(5, "bar").unwrap(), Some("foo"));
+    /// assert_eq!(v.get(5), Some(&"bar"));
+    /// ```
+    pub fn insert_at(&mut self, index: usize, v: T) -> Result<Option<T>> {
+        if index < self.offset {
+            return Err(Error::NoCapacity);
+        }
+        let local = index - self.offset;
+
+        self.grow_if_required(local)?;
+
+        match replace(&mut self.inner[local], Entry::Occupied(v)) {
+            Entry::Empty(_) => {
+                self.length += 1;
+                Ok(None)
+            }
+            Entry::Occupied(prev) => Ok(Some(prev)),
+        }
+    }
+
     /// Remove at index (inserting an empty entry)
     pub fn remove(&mut self, index: usize) -> Option<T> {
         if let Entry::Empty(_) = self.inner[index - self.offset] {
@@ -255,6 +328,26 @@ impl<T> PreVec<T> {
         self.len() == 0
     }
 
+    /// Iterate over the occupied slots, returning the index and the value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use sonr::PreVec;
+    /// let mut v = PreVec::with_capacity_and_offset(4, 10);
+    /// v.insert("foo");
+    /// v.insert("bar");
+    /// v.remove(10);
+    /// assert_eq!(v.iter().collect::<Vec<_>>(), vec![(11, &"bar")]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &T)> {
+        let offset = self.offset;
+        self.inner.iter().enumerate().filter_map(move |(index, entry)| match entry {
+            Entry::Occupied(v) => Some((index + offset, v)),
+            Entry::Empty(_) => None,
+        })
+    }
+
     /// Remove all entries
     pub fn clear(&mut self) {
         self.inner = (0..self.capacity).map(|i| Entry::Empty(i+1)).collect();
@@ -339,6 +432,15 @@ mod tests {
         assert_eq!(v.insert("foo").unwrap(), 0);
     }
 
+    #[test]
+    fn fixed_growth() {
+        let mut v = PreVec::with_capacity(1);
+        v.set_growth(Growth::Fixed);
+        assert_eq!(v.insert("foo").unwrap(), 0);
+        assert!(v.insert("bar").is_err());
+        assert_eq!(v.capacity(), 1);
+    }
+
     #[test]
     fn insert_many() {
         let cap = 10;
@@ -455,6 +557,15 @@ mod tests {
         assert_eq!(store.capacity, 2);
     }
 
+    #[test]
+    fn insert_at_beyond_capacity() {
+        let mut store: PreVec<u32> = PreVec::with_capacity(1);
+        assert_eq!(store.insert_at(10, 1).unwrap(), None);
+        assert_eq!(store.get(10), Some(&1));
+        assert_eq!(store.remove(10), Some(1));
+        assert_eq!(store.len(), 0);
+    }
+
     #[test]
     fn disable_growth() {
         let mut store: PreVec<u32> = PreVec::with_capacity(1);
@@ -1,13 +1,24 @@
 //! Combine [`Reactor`]s creating new [`Reactor`]s.
 //!
 //! See each individual combinator for more information.
+use std::collections::VecDeque;
 use std::marker::PhantomData;
 
+use mio::Token;
+
 use super::{Reaction, Reactor};
+use crate::route::{claims, Router};
 
 /// Chain two [`Reactor`]s together, making the output of the first
 /// reactor the input of the second.
 ///
+/// Once the first reactor returns `Reaction::Continue` the chain passes
+/// `Reaction::Continue` to the second reactor and returns whatever it returns.
+/// A value returned by the second reactor while it's being drained is passed on
+/// rather than dropped, and the chain is drained again on the next `Reaction::Continue`.
+/// This way a reactor that produces values when it reacts to `Reaction::Continue`
+/// can be chained without losing its output.
+///
 /// [`Reactor`]: ../trait.Reactor.html
 pub struct Chain<F, T>
 where
@@ -48,14 +59,15 @@ where
                     let _ = self.to.react(Reaction::Value(val));
                     r1 = self.from.react(Reaction::Continue);
                 }
-                Reaction::Continue => {
-                    if let Reaction::Continue = self.to.react(Reaction::Continue) {
-                        break Reaction::Continue;
-                    }
-                }
+                Reaction::Continue => break self.to.react(Reaction::Continue),
             }
         }
     }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        self.from.tokens(tokens);
+        self.to.tokens(tokens);
+    }
 }
 
 // -----------------------------------------------------------------------------
@@ -85,8 +97,15 @@ where
 /// }
 /// ```
 ///
-/// This means a `Reaction::Event(event)` from the `System` will be passed on to both
-/// listeners.
+/// A `Reaction::Event(event)` from the `System` is passed straight to the reactor
+/// that owns the event's token (see [`Reactor::tokens`]), and events for any other token
+/// are passed on to both reactors.
+///
+/// After an event each reactor that saw it is drained by reacting with `Reaction::Continue`
+/// until it stops returning values, as the `System` only drains the reactor it's given.
+/// The output of `And` is `()`, so values returned while draining are discarded.
+///
+/// [`Reactor::tokens`]: trait.Reactor.html#method.tokens
 pub struct And<T, U>
 where
     T: Reactor,
@@ -94,6 +113,7 @@ where
 {
     first: T,
     second: U,
+    router: Router,
 }
 
 impl<T, U> And<T, U>
@@ -103,7 +123,10 @@ where
 {
     /// Create a new `And` from two reactors.
     pub fn new(first: T, second: U) -> Self {
-        Self { first, second }
+        let router = Router::new();
+        router.add(0, &first);
+        router.add(1, &second);
+        Self { first, second, router }
     }
 }
 
@@ -118,13 +141,34 @@ where
     fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
         match reaction {
             Reaction::Event(event) => {
-                self.first.react(Reaction::Event(event));
-                self.second.react(Reaction::Event(event));
+                let (first, second) = (&self.first, &self.second);
+                let route = self.router.branch(&event, |branch| match branch {
+                    0 => claims(first, event.token()),
+                    _ => claims(second, event.token()),
+                });
+
+                // Drain both reactors, as the System only drains
+                // the reactor it's given.
+                if matches!(route, None | Some(0)) {
+                    let first = &mut self.first;
+                    self.router.react(0, first, Reaction::Event(event));
+                    while let Reaction::Value(_) = self.router.react(0, first, Reaction::Continue) { }
+                }
+                if matches!(route, None | Some(1)) {
+                    let second = &mut self.second;
+                    self.router.react(1, second, Reaction::Event(event));
+                    while let Reaction::Value(_) = self.router.react(1, second, Reaction::Continue) { }
+                }
                 Reaction::Event(event)
             }
             _ => Reaction::Continue,
         }
     }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        self.first.tokens(tokens);
+        self.second.tokens(tokens);
+    }
 }
 
 // -----------------------------------------------------------------------------
@@ -195,6 +239,350 @@ where
             Reaction::Continue => Reaction::Continue,
         }
     }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        self.source.tokens(tokens);
+    }
+}
+
+// -----------------------------------------------------------------------------
+// 		- Filter -
+// -----------------------------------------------------------------------------
+/// Only output the values for which the predicate returns `true`.
+///
+/// Values that are filtered out don't stop the drain loop: the source reacts to
+/// `Reaction::Continue` until it outputs a value that passes the predicate, or
+/// has nothing more to output.
+///
+///```
+/// # use sonr::prelude::*;
+/// # use sonr::errors::Result;
+/// use sonr::reactor::producers::ReactiveGenerator;
+///
+/// fn main() -> Result<()> {
+///     let system_sig = System::init()?;
+///     let run = ReactiveGenerator::new(vec![1u32, 2, 3, 4])?
+///         .filter(|n| n % 2 == 0)
+///         .map(|n| {
+///             assert_eq!(n % 2, 0);
+///             if n == 4 {
+///                 system_sig.send(SystemEvent::Stop);
+///             }
+///         });
+///
+///     System::start(run)?;
+///     Ok(())
+/// }
+/// ```
+pub struct Filter<S, F> {
+    source: S,
+    predicate: F,
+}
+
+impl<S, F> Filter<S, F> {
+    /// Create a new filter from a reactor and a predicate.
+    pub fn new(source: S, predicate: F) -> Self {
+        Self { source, predicate }
+    }
+}
+
+impl<S, F> Reactor for Filter<S, F>
+where
+    S: Reactor,
+    F: FnMut(&S::Output) -> bool,
+{
+    type Output = S::Output;
+    type Input = S::Input;
+
+    fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
+        let mut reaction = self.source.react(reaction);
+        loop {
+            match reaction {
+                Reaction::Value(val) => {
+                    if (self.predicate)(&val) {
+                        break Reaction::Value(val);
+                    }
+                    reaction = self.source.react(Reaction::Continue);
+                }
+                Reaction::Event(event) => break Reaction::Event(event),
+                Reaction::Continue => break Reaction::Continue,
+            }
+        }
+    }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        self.source.tokens(tokens);
+    }
+}
+
+// -----------------------------------------------------------------------------
+// 		- Filter map -
+// -----------------------------------------------------------------------------
+/// Map and filter in one go: only the values for which the closure returns `Some`
+/// are output.
+///
+/// See [`Filter`] for how filtered out values are drained.
+///
+/// [`Filter`]: struct.Filter.html
+pub struct FilterMap<S, F, T> {
+    source: S,
+    callback: F,
+    _p: PhantomData<T>,
+}
+
+impl<S, F, T> FilterMap<S, F, T> {
+    /// Create a new filter map from a reactor and a closure.
+    pub fn new(source: S, callback: F) -> Self {
+        Self {
+            source,
+            callback,
+            _p: PhantomData,
+        }
+    }
+}
+
+impl<S, F, T> Reactor for FilterMap<S, F, T>
+where
+    S: Reactor,
+    F: FnMut(S::Output) -> Option<T>,
+{
+    type Output = T;
+    type Input = S::Input;
+
+    fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
+        let mut reaction = self.source.react(reaction);
+        loop {
+            match reaction {
+                Reaction::Value(val) => {
+                    if let Some(val) = (self.callback)(val) {
+                        break Reaction::Value(val);
+                    }
+                    reaction = self.source.react(Reaction::Continue);
+                }
+                Reaction::Event(event) => break Reaction::Event(event),
+                Reaction::Continue => break Reaction::Continue,
+            }
+        }
+    }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        self.source.tokens(tokens);
+    }
+}
+
+// -----------------------------------------------------------------------------
+// 		- Flat map -
+// -----------------------------------------------------------------------------
+/// Turn each value into any number of values.
+///
+/// The first value is output straight away, and the rest are output one at a time
+/// as the reactor reacts to `Reaction::Continue`.
+///
+///```
+/// # use sonr::prelude::*;
+/// # use sonr::errors::Result;
+/// use sonr::reactor::producers::Mono;
+///
+/// fn main() -> Result<()> {
+///     let system_sig = System::init()?;
+///     let run = Mono::new("a b c")?
+///         .flat_map(|s: &str| s.split(' ').map(String::from).collect::<Vec<_>>())
+///         .map(|word| {
+///             if word == "c" {
+///                 system_sig.send(SystemEvent::Stop);
+///             }
+///         });
+///
+///     System::start(run)?;
+///     Ok(())
+/// }
+/// ```
+pub struct FlatMap<S, F, I: IntoIterator> {
+    source: S,
+    callback: F,
+    pending: VecDeque<I::IntoIter>,
+}
+
+impl<S, F, I: IntoIterator> FlatMap<S, F, I> {
+    /// Create a new flat map from a reactor and a closure.
+    pub fn new(source: S, callback: F) -> Self {
+        Self {
+            source,
+            callback,
+            pending: VecDeque::new(),
+        }
+    }
+
+    fn next_pending(&mut self) -> Option<I::Item> {
+        while let Some(iter) = self.pending.front_mut() {
+            match iter.next() {
+                Some(val) => return Some(val),
+                None => { self.pending.pop_front(); }
+            }
+        }
+        None
+    }
+}
+
+impl<S, F, I> Reactor for FlatMap<S, F, I>
+where
+    S: Reactor,
+    F: FnMut(S::Output) -> I,
+    I: IntoIterator,
+{
+    type Output = I::Item;
+    type Input = S::Input;
+
+    fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
+        if let Reaction::Continue = reaction {
+            if let Some(val) = self.next_pending() {
+                return Reaction::Value(val);
+            }
+        }
+
+        let mut reaction = self.source.react(reaction);
+        loop {
+            match reaction {
+                Reaction::Value(val) => {
+                    let iter = (self.callback)(val).into_iter();
+                    self.pending.push_back(iter);
+                    if let Some(val) = self.next_pending() {
+                        break Reaction::Value(val);
+                    }
+                    reaction = self.source.react(Reaction::Continue);
+                }
+                Reaction::Event(event) => break Reaction::Event(event),
+                Reaction::Continue => break Reaction::Continue,
+            }
+        }
+    }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        self.source.tokens(tokens);
+    }
+}
+
+// -----------------------------------------------------------------------------
+// 		- Inspect -
+// -----------------------------------------------------------------------------
+/// Call a closure with a reference to each value before passing it on.
+pub struct Inspect<S, F> {
+    source: S,
+    callback: F,
+}
+
+impl<S, F> Inspect<S, F> {
+    /// Create a new inspect from a reactor and a closure.
+    pub fn new(source: S, callback: F) -> Self {
+        Self { source, callback }
+    }
+}
+
+impl<S, F> Reactor for Inspect<S, F>
+where
+    S: Reactor,
+    F: FnMut(&S::Output),
+{
+    type Output = S::Output;
+    type Input = S::Input;
+
+    fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
+        let reaction = self.source.react(reaction);
+        if let Reaction::Value(ref val) = reaction {
+            (self.callback)(val);
+        }
+        reaction
+    }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        self.source.tokens(tokens);
+    }
+}
+
+// -----------------------------------------------------------------------------
+// 		- Take -
+// -----------------------------------------------------------------------------
+/// Output the first `n` values, and drop the rest.
+///
+/// Events are still passed on once all `n` values are output.
+pub struct Take<S> {
+    source: S,
+    remaining: usize,
+}
+
+impl<S> Take<S> {
+    /// Create a new take from a reactor.
+    pub fn new(source: S, n: usize) -> Self {
+        Self { source, remaining: n }
+    }
+}
+
+impl<S: Reactor> Reactor for Take<S> {
+    type Output = S::Output;
+    type Input = S::Input;
+
+    fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
+        let mut reaction = self.source.react(reaction);
+        loop {
+            match reaction {
+                Reaction::Value(val) => {
+                    if self.remaining > 0 {
+                        self.remaining -= 1;
+                        break Reaction::Value(val);
+                    }
+                    reaction = self.source.react(Reaction::Continue);
+                }
+                Reaction::Event(event) => break Reaction::Event(event),
+                Reaction::Continue => break Reaction::Continue,
+            }
+        }
+    }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        self.source.tokens(tokens);
+    }
+}
+
+// -----------------------------------------------------------------------------
+// 		- Skip -
+// -----------------------------------------------------------------------------
+/// Drop the first `n` values, and output the rest.
+pub struct Skip<S> {
+    source: S,
+    remaining: usize,
+}
+
+impl<S> Skip<S> {
+    /// Create a new skip from a reactor.
+    pub fn new(source: S, n: usize) -> Self {
+        Self { source, remaining: n }
+    }
+}
+
+impl<S: Reactor> Reactor for Skip<S> {
+    type Output = S::Output;
+    type Input = S::Input;
+
+    fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
+        let mut reaction = self.source.react(reaction);
+        loop {
+            match reaction {
+                Reaction::Value(val) => {
+                    if self.remaining == 0 {
+                        break Reaction::Value(val);
+                    }
+                    self.remaining -= 1;
+                    reaction = self.source.react(Reaction::Continue);
+                }
+                Reaction::Event(event) => break Reaction::Event(event),
+                Reaction::Continue => break Reaction::Continue,
+            }
+        }
+    }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        self.source.tokens(tokens);
+    }
 }
 
 // -----------------------------------------------------------------------------
@@ -234,11 +622,15 @@ where
 pub struct Or<T, U> {
     first: T,
     second: U,
+    router: Router,
 }
 
-impl<T, U> Or<T, U> {
+impl<T: Reactor, U: Reactor> Or<T, U> {
     pub(crate) fn new(first: T, second: U) -> Self {
-        Self { first, second }
+        let router = Router::new();
+        router.add(0, &first);
+        router.add(1, &second);
+        Self { first, second, router }
     }
 }
 
@@ -250,17 +642,31 @@ impl<T: Reactor<Output = O>, U: Reactor<Output = O>, O> Reactor for Or<T, U> {
         use Reaction::*;
         match reaction {
             Value(val) => match val {
-                Either::A(val) => self.first.react(Value(val)),
-                Either::B(val) => self.second.react(Value(val)),
+                Either::A(val) => self.router.react(0, &mut self.first, Value(val)),
+                Either::B(val) => self.router.react(1, &mut self.second, Value(val)),
             },
             Event(event) => {
-                self.first.react(Event(event));
-                self.second.react(Event(event));
+                let (first, second) = (&self.first, &self.second);
+                let route = self.router.branch(&event, |branch| match branch {
+                    0 => claims(first, event.token()),
+                    _ => claims(second, event.token()),
+                });
+                if matches!(route, None | Some(0)) {
+                    self.router.react(0, &mut self.first, Event(event));
+                }
+                if matches!(route, None | Some(1)) {
+                    self.router.react(1, &mut self.second, Event(event));
+                }
                 event.into()
             }
             Continue => Continue,
         }
     }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        self.first.tokens(tokens);
+        self.second.tokens(tokens);
+    }
 }
 
 /// Either A or B
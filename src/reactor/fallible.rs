@@ -0,0 +1,308 @@
+use std::result::Result as StdResult;
+
+use mio::Token;
+
+use crate::errors::Error;
+
+use super::{Reaction, Reactor};
+
+/// A reactor that outputs `Result<T, E>`.
+///
+/// `TryReactor` is implemented for every reactor with a `Result` as output,
+/// and adds combinators to deal with the errors, so a failure anywhere in
+/// a chain can reach a handler at the end of the chain.
+///
+///```
+/// # use sonr::prelude::*;
+/// # use sonr::errors::{Error, Result};
+/// use sonr::reactor::producers::Mono;
+///
+/// fn main() -> Result<()> {
+///     let system_sig = System::init()?;
+///
+///     let run = Mono::new("not a number")?
+///         .map(|s: &str| s.parse::<u32>())
+///         .map_err(|e| e.to_string())
+///         .recover(|e| {
+///             eprintln!("failed to parse: {}", e);
+///             system_sig.send(SystemEvent::Stop);
+///             None
+///         });
+///
+///     System::start(run)?;
+///     Ok(())
+/// }
+/// ```
+pub trait TryReactor<T, E>: Reactor<Output = StdResult<T, E>> {
+    /// Map the error of a failed output
+    fn map_err<F, E2>(self, callback: F) -> MapErr<Self, F>
+    where
+        F: FnMut(E) -> E2,
+    {
+        MapErr { source: self, callback }
+    }
+
+    /// Call the closure with the error of a failed output.
+    /// The closure either turns the error into a value or returns another error.
+    fn or_else<F, E2>(self, callback: F) -> OrElse<Self, F>
+    where
+        F: FnMut(E) -> StdResult<T, E2>,
+    {
+        OrElse { source: self, callback }
+    }
+
+    /// Handle errors at the end of a chain.
+    /// Successful values are output as they are, and errors are passed to the closure
+    /// which can return a value in place of the error.
+    fn recover<F>(self, callback: F) -> Recover<Self, F>
+    where
+        F: FnMut(E) -> Option<T>,
+    {
+        Recover { source: self, callback }
+    }
+
+    /// Chain a reactor that can fail.
+    /// Successful values are passed on to the next reactor, and errors skip the
+    /// next reactor and are output as they are.
+    fn try_chain<R, U>(self, to: R) -> TryChain<Self, R>
+    where
+        R: Reactor<Input = T, Output = StdResult<U, E>>,
+    {
+        TryChain { from: self, to }
+    }
+}
+
+impl<R, T, E> TryReactor<T, E> for R where R: Reactor<Output = StdResult<T, E>> {}
+
+// -----------------------------------------------------------------------------
+// 		- Map error -
+// -----------------------------------------------------------------------------
+/// Map the error of a [`TryReactor`].
+/// See [`TryReactor::map_err`].
+///
+/// [`TryReactor`]: trait.TryReactor.html
+/// [`TryReactor::map_err`]: trait.TryReactor.html#method.map_err
+pub struct MapErr<S, F> {
+    source: S,
+    callback: F,
+}
+
+impl<S, F, T, E, E2> Reactor for MapErr<S, F>
+where
+    S: Reactor<Output = StdResult<T, E>>,
+    F: FnMut(E) -> E2,
+{
+    type Input = S::Input;
+    type Output = StdResult<T, E2>;
+
+    fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
+        match self.source.react(reaction) {
+            Reaction::Value(val) => Reaction::Value(val.map_err(&mut self.callback)),
+            Reaction::Event(event) => Reaction::Event(event),
+            Reaction::Continue => Reaction::Continue,
+        }
+    }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        self.source.tokens(tokens);
+    }
+}
+
+// -----------------------------------------------------------------------------
+// 		- Or else -
+// -----------------------------------------------------------------------------
+/// Turn the error of a [`TryReactor`] into a value or another error.
+/// See [`TryReactor::or_else`].
+///
+/// [`TryReactor`]: trait.TryReactor.html
+/// [`TryReactor::or_else`]: trait.TryReactor.html#method.or_else
+pub struct OrElse<S, F> {
+    source: S,
+    callback: F,
+}
+
+impl<S, F, T, E, E2> Reactor for OrElse<S, F>
+where
+    S: Reactor<Output = StdResult<T, E>>,
+    F: FnMut(E) -> StdResult<T, E2>,
+{
+    type Input = S::Input;
+    type Output = StdResult<T, E2>;
+
+    fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
+        match self.source.react(reaction) {
+            Reaction::Value(val) => Reaction::Value(val.or_else(&mut self.callback)),
+            Reaction::Event(event) => Reaction::Event(event),
+            Reaction::Continue => Reaction::Continue,
+        }
+    }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        self.source.tokens(tokens);
+    }
+}
+
+// -----------------------------------------------------------------------------
+// 		- Recover -
+// -----------------------------------------------------------------------------
+/// Handle the errors of a [`TryReactor`].
+/// See [`TryReactor::recover`].
+///
+/// [`TryReactor`]: trait.TryReactor.html
+/// [`TryReactor::recover`]: trait.TryReactor.html#method.recover
+pub struct Recover<S, F> {
+    source: S,
+    callback: F,
+}
+
+impl<S, F, T, E> Reactor for Recover<S, F>
+where
+    S: Reactor<Output = StdResult<T, E>>,
+    F: FnMut(E) -> Option<T>,
+{
+    type Input = S::Input;
+    type Output = T;
+
+    fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
+        let mut reaction = self.source.react(reaction);
+        loop {
+            match reaction {
+                Reaction::Value(Ok(val)) => break Reaction::Value(val),
+                Reaction::Value(Err(e)) => match (self.callback)(e) {
+                    Some(val) => break Reaction::Value(val),
+                    // Keep draining the source, there could be more values
+                    None => reaction = self.source.react(Reaction::Continue),
+                },
+                Reaction::Event(event) => break Reaction::Event(event),
+                Reaction::Continue => break Reaction::Continue,
+            }
+        }
+    }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        self.source.tokens(tokens);
+    }
+}
+
+// -----------------------------------------------------------------------------
+// 		- Try chain -
+// -----------------------------------------------------------------------------
+/// Chain two reactors that can fail.
+/// See [`TryReactor::try_chain`].
+///
+/// [`TryReactor::try_chain`]: trait.TryReactor.html#method.try_chain
+pub struct TryChain<F, T> {
+    from: F,
+    to: T,
+}
+
+impl<F, T, A, B, E> Reactor for TryChain<F, T>
+where
+    F: Reactor<Output = StdResult<A, E>>,
+    T: Reactor<Input = A, Output = StdResult<B, E>>,
+{
+    type Input = F::Input;
+    type Output = StdResult<B, E>;
+
+    fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
+        let mut r1 = self.from.react(reaction);
+        loop {
+            match r1 {
+                Reaction::Event(event) => break self.to.react(Reaction::Event(event)),
+                Reaction::Value(Ok(val)) => {
+                    if let Reaction::Value(out) = self.to.react(Reaction::Value(val)) {
+                        break Reaction::Value(out);
+                    }
+                    r1 = self.from.react(Reaction::Continue);
+                }
+                Reaction::Value(Err(e)) => break Reaction::Value(Err(e)),
+                Reaction::Continue => break self.to.react(Reaction::Continue),
+            }
+        }
+    }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        self.from.tokens(tokens);
+        self.to.tokens(tokens);
+    }
+}
+
+// -----------------------------------------------------------------------------
+// 		- Fallible -
+// -----------------------------------------------------------------------------
+/// A reactor that keeps track of the last error it ran into,
+/// rather than failing the whole chain.
+pub trait TakeError: Reactor {
+    /// The last error, if any.
+    fn take_error(&mut self) -> Option<Error>;
+
+    /// Output `Result<Output, Error>`, with every error as an `Err`
+    /// so it can be handled further down the chain.
+    /// See [`TryReactor`].
+    ///
+    /// [`TryReactor`]: trait.TryReactor.html
+    fn fallible(self) -> Fallible<Self> {
+        Fallible {
+            inner: self,
+            pending: None,
+        }
+    }
+}
+
+/// Output the errors of a reactor as values.
+/// See [`TakeError::fallible`].
+///
+/// [`TakeError::fallible`]: trait.TakeError.html#method.fallible
+pub struct Fallible<R: Reactor> {
+    inner: R,
+    pending: Option<Reaction<R::Output>>,
+}
+
+impl<R: Reactor> Fallible<R> {
+    /// Reference the inner reactor
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+
+    /// Mutable reference to the inner reactor
+    pub fn inner_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+}
+
+impl<R: TakeError> Reactor for Fallible<R> {
+    type Input = R::Input;
+    type Output = StdResult<R::Output, Error>;
+
+    fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
+        // Output the reaction that was held back by the last error
+        if let Reaction::Continue = reaction {
+            if let Some(pending) = self.pending.take() {
+                return wrap(pending);
+            }
+        }
+
+        let reaction = self.inner.react(reaction);
+        match self.inner.take_error() {
+            Some(e) => {
+                if let Reaction::Value(_) | Reaction::Event(_) = reaction {
+                    self.pending = Some(reaction);
+                }
+                Reaction::Value(Err(e))
+            }
+            None => wrap(reaction),
+        }
+    }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        self.inner.tokens(tokens);
+    }
+}
+
+fn wrap<T>(reaction: Reaction<T>) -> Reaction<StdResult<T, Error>> {
+    match reaction {
+        Reaction::Value(val) => Reaction::Value(Ok(val)),
+        Reaction::Event(event) => Reaction::Event(event),
+        Reaction::Continue => Reaction::Continue,
+    }
+}
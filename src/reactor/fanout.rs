@@ -0,0 +1,412 @@
+use mio::{Event, Token};
+
+use super::{Either, Reaction, Reactor};
+use crate::route::{claims, Router};
+
+// -----------------------------------------------------------------------------
+// 		- Either3..Either12 -
+// -----------------------------------------------------------------------------
+macro_rules! either {
+    ($(#[$doc:meta])* $name:ident; $($V:ident),+) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum $name<$($V),+> {
+            $(
+                #[allow(missing_docs)]
+                $V($V),
+            )+
+        }
+    };
+}
+
+either!(/// One of three values. Used to route values with [`any`](fn.any.html).
+    Either3; A, B, C);
+either!(/// One of four values. Used to route values with [`any`](fn.any.html).
+    Either4; A, B, C, D);
+either!(/// One of five values. Used to route values with [`any`](fn.any.html).
+    Either5; A, B, C, D, E);
+either!(/// One of six values. Used to route values with [`any`](fn.any.html).
+    Either6; A, B, C, D, E, F);
+either!(/// One of seven values. Used to route values with [`any`](fn.any.html).
+    Either7; A, B, C, D, E, F, G);
+either!(/// One of eight values. Used to route values with [`any`](fn.any.html).
+    Either8; A, B, C, D, E, F, G, H);
+either!(/// One of nine values. Used to route values with [`any`](fn.any.html).
+    Either9; A, B, C, D, E, F, G, H, I);
+either!(/// One of ten values. Used to route values with [`any`](fn.any.html).
+    Either10; A, B, C, D, E, F, G, H, I, J);
+either!(/// One of eleven values. Used to route values with [`any`](fn.any.html).
+    Either11; A, B, C, D, E, F, G, H, I, J, K);
+either!(/// One of twelve values. Used to route values with [`any`](fn.any.html).
+    Either12; A, B, C, D, E, F, G, H, I, J, K, L);
+
+// -----------------------------------------------------------------------------
+// 		- Fan out -
+// 		Pass an event to each reactor in turn, draining
+// 		every reactor that outputs a value before moving on
+// 		to the next one.
+// -----------------------------------------------------------------------------
+struct FanOut {
+    router: Router,
+    routed: bool,
+    event: Option<Event>,
+    next: usize,
+    end: usize,
+    current: Option<usize>,
+}
+
+impl FanOut {
+    fn new() -> Self {
+        Self {
+            router: Router::new(),
+            routed: false,
+            event: None,
+            next: 0,
+            end: 0,
+            current: None,
+        }
+    }
+
+    // The reactors are routed on the first reaction, rather than when the
+    // combinator is created, as the combinator functions are not bound by `Reactor`.
+    fn route(&mut self, reactors: &impl Routes) {
+        if !self.routed {
+            reactors.add_routes(&self.router);
+            self.routed = true;
+        }
+    }
+
+    // `react(router, reactors, index, Some(event))` passes the event to the reactor at `index`,
+    // and `react(router, reactors, index, None)` passes `Reaction::Continue`.
+    fn react<T, O, Rs, F>(&mut self, reaction: Reaction<T>, reactors: &mut Rs, len: usize, mut react: F) -> Reaction<O>
+    where
+        Rs: Routes,
+        F: FnMut(&Router, &mut Rs, usize, Option<Event>) -> Reaction<O>,
+    {
+        match reaction {
+            Reaction::Event(event) => {
+                // Pass the event straight to the reactor that owns the token
+                let route = self.router.branch(&event, |index| reactors.claims(index, event.token()));
+                let (next, end) = match route {
+                    Some(index) => (index, index + 1),
+                    None => (0, len),
+                };
+                self.event = Some(event);
+                self.next = next;
+                self.end = end;
+                self.current = None;
+            }
+            Reaction::Continue => {}
+            Reaction::Value(_) => return Reaction::Continue,
+        }
+
+        loop {
+            if let Some(index) = self.current {
+                if let Reaction::Value(val) = react(&self.router, reactors, index, None) {
+                    return Reaction::Value(val);
+                }
+                self.current = None;
+            }
+
+            match self.event {
+                Some(event) if self.next < self.end => {
+                    let index = self.next;
+                    self.next += 1;
+                    if let Reaction::Value(val) = react(&self.router, reactors, index, Some(event)) {
+                        self.current = Some(index);
+                        return Reaction::Value(val);
+                    }
+                }
+                // Every reactor has seen the event, pass it on
+                Some(event) => {
+                    self.event = None;
+                    return Reaction::Event(event);
+                }
+                None => return Reaction::Continue,
+            }
+        }
+    }
+}
+
+// Implemented for tuples of reactors
+trait Routes {
+    fn add_routes(&self, router: &Router);
+    fn claims(&self, index: usize, token: Token) -> bool;
+    fn tokens(&self, tokens: &mut Vec<Token>);
+}
+
+fn input<T>(event: Option<Event>) -> Reaction<T> {
+    match event {
+        Some(event) => Reaction::Event(event),
+        None => Reaction::Continue,
+    }
+}
+
+fn wrap<T, U>(reaction: Reaction<T>, f: impl FnOnce(T) -> U) -> Reaction<U> {
+    match reaction {
+        Reaction::Value(val) => Reaction::Value(f(val)),
+        Reaction::Event(event) => Reaction::Event(event),
+        Reaction::Continue => Reaction::Continue,
+    }
+}
+
+// -----------------------------------------------------------------------------
+// 		- All -
+// -----------------------------------------------------------------------------
+/// Run a tuple of (up to twelve) reactors side by side.
+///
+/// Unlike [`And`] the output of each reactor is preserved: the output of the
+/// first reactor is `Either::A`, the second `Either::B` and so on (`Either`
+/// for two reactors, [`Either3`] for three etc.).
+///
+///```
+/// # use sonr::prelude::*;
+/// # use sonr::errors::Result;
+/// use sonr::reactor::producers::Mono;
+/// use sonr::reactor::{all, Either3};
+///
+/// fn main() -> Result<()> {
+///     let system_sig = System::init()?;
+///
+///     let mut received = 0;
+///     let run = all((Mono::new(1u8)?, Mono::new("two")?, Mono::new(3.0f32)?))
+///         .map(|val| {
+///             match val {
+///                 Either3::A(n) => assert_eq!(n, 1),
+///                 Either3::B(s) => assert_eq!(s, "two"),
+///                 Either3::C(f) => assert_eq!(f, 3.0),
+///             }
+///             received += 1;
+///             if received == 3 {
+///                 system_sig.send(SystemEvent::Stop);
+///             }
+///         });
+///
+///     System::start(run)?;
+///     Ok(())
+/// }
+/// ```
+///
+/// [`And`]: struct.And.html
+/// [`Either3`]: enum.Either3.html
+pub struct All<T> {
+    reactors: T,
+    fan_out: FanOut,
+}
+
+/// Run a tuple of reactors side by side, preserving their output.
+/// See [`All`].
+///
+/// [`All`]: struct.All.html
+pub fn all<T>(reactors: T) -> All<T> {
+    All {
+        reactors,
+        fan_out: FanOut::new(),
+    }
+}
+
+// -----------------------------------------------------------------------------
+// 		- Select -
+// -----------------------------------------------------------------------------
+/// Run a tuple of (up to twelve) reactors with the same output side by side,
+/// and merge their output.
+///
+///```
+/// # use sonr::prelude::*;
+/// # use sonr::errors::Result;
+/// use sonr::reactor::producers::{Mono, ReactiveGenerator};
+/// use sonr::reactor::select;
+///
+/// fn main() -> Result<()> {
+///     let system_sig = System::init()?;
+///
+///     let mut total = 0;
+///     let run = select((Mono::new(1u32)?, ReactiveGenerator::new(vec![2u32, 3])?))
+///         .map(|n| {
+///             total += n;
+///             if total == 6 {
+///                 system_sig.send(SystemEvent::Stop);
+///             }
+///         });
+///
+///     System::start(run)?;
+///     Ok(())
+/// }
+/// ```
+pub struct Select<T> {
+    reactors: T,
+    fan_out: FanOut,
+}
+
+/// Merge the output of a tuple of reactors.
+/// See [`Select`].
+///
+/// [`Select`]: struct.Select.html
+pub fn select<T>(reactors: T) -> Select<T> {
+    Select {
+        reactors,
+        fan_out: FanOut::new(),
+    }
+}
+
+// -----------------------------------------------------------------------------
+// 		- Any -
+// -----------------------------------------------------------------------------
+/// Route each value to one of a tuple of (up to twelve) reactors with the same output.
+///
+/// This is [`Or`] for more than two reactors: a value of `Either3::A` is passed to the
+/// first reactor, `Either3::B` to the second and so on.
+/// Events are passed to the reactor that owns the token (or every reactor, see
+/// [`Reactor::tokens`]), and the output of every reactor is preserved.
+///
+///```
+/// # use sonr::prelude::*;
+/// # use sonr::errors::Result;
+/// use sonr::reactor::consumers::Consume;
+/// use sonr::reactor::producers::ReactiveGenerator;
+/// use sonr::reactor::{any, Either3};
+///
+/// fn main() -> Result<()> {
+///     let system_sig = System::init()?;
+///
+///     let producer = ReactiveGenerator::new(vec![1u32, 2, 3])?
+///         .map(|n| match n % 3 {
+///             0 => Either3::A(n),
+///             1 => Either3::B(n),
+///             _ => Either3::C(n),
+///         });
+///
+///     let consumers = any((
+///         Consume::new().map(|n: u32| n * 10),
+///         Consume::new().map(|n: u32| n * 100),
+///         Consume::new(),
+///     ))
+///     .map(|n| {
+///         if n == 30 {
+///             system_sig.send(SystemEvent::Stop);
+///         }
+///     });
+///
+///     System::start(producer.chain(consumers))?;
+///     Ok(())
+/// }
+/// ```
+///
+/// [`Or`]: struct.Or.html
+/// [`Reactor::tokens`]: trait.Reactor.html#method.tokens
+pub struct Any<T> {
+    reactors: T,
+    fan_out: FanOut,
+}
+
+/// Route values to one of a tuple of reactors.
+/// See [`Any`].
+///
+/// [`Any`]: struct.Any.html
+pub fn any<T>(reactors: T) -> Any<T> {
+    Any {
+        reactors,
+        fan_out: FanOut::new(),
+    }
+}
+
+macro_rules! fan_out {
+    ($either:ident, $len:expr; $($idx:tt $R:ident $V:ident),+) => {
+        impl<$($R: Reactor),+> Routes for ($($R,)+) {
+            fn add_routes(&self, router: &Router) {
+                $(router.add($idx, &self.$idx);)+
+            }
+
+            fn claims(&self, index: usize, token: Token) -> bool {
+                match index {
+                    $($idx => claims(&self.$idx, token),)+
+                    _ => false,
+                }
+            }
+
+            fn tokens(&self, tokens: &mut Vec<Token>) {
+                $(self.$idx.tokens(tokens);)+
+            }
+        }
+
+        impl<$($R: Reactor),+> Reactor for All<($($R,)+)> {
+            type Input = ();
+            type Output = $either<$($R::Output),+>;
+
+            fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
+                let reactors = &mut self.reactors;
+                self.fan_out.route(reactors);
+                self.fan_out.react(reaction, reactors, $len, |router, reactors, index, event| match index {
+                    $($idx => wrap(router.react($idx, &mut reactors.$idx, input(event)), $either::$V),)+
+                    _ => unreachable!(),
+                })
+            }
+
+            fn tokens(&self, tokens: &mut Vec<Token>) {
+                self.reactors.tokens(tokens);
+            }
+        }
+
+        impl<O, $($R: Reactor<Output = O>),+> Reactor for Select<($($R,)+)> {
+            type Input = ();
+            type Output = O;
+
+            fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
+                let reactors = &mut self.reactors;
+                self.fan_out.route(reactors);
+                self.fan_out.react(reaction, reactors, $len, |router, reactors, index, event| match index {
+                    $($idx => router.react($idx, &mut reactors.$idx, input(event)),)+
+                    _ => unreachable!(),
+                })
+            }
+
+            fn tokens(&self, tokens: &mut Vec<Token>) {
+                self.reactors.tokens(tokens);
+            }
+        }
+
+        impl<O, $($R: Reactor<Output = O>),+> Reactor for Any<($($R,)+)> {
+            type Input = $either<$($R::Input),+>;
+            type Output = O;
+
+            fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
+                let reactors = &mut self.reactors;
+                self.fan_out.route(reactors);
+                match reaction {
+                    Reaction::Value(val) => {
+                        let router = &self.fan_out.router;
+                        let (index, reaction) = match val {
+                            $($either::$V(val) => ($idx, router.react($idx, &mut reactors.$idx, Reaction::Value(val))),)+
+                        };
+
+                        // Drain the reactor on `Reaction::Continue`
+                        if let Reaction::Value(_) = reaction {
+                            self.fan_out.current = Some(index);
+                        }
+                        reaction
+                    }
+                    reaction => self.fan_out.react(reaction, reactors, $len, |router, reactors, index, event| match index {
+                        $($idx => router.react($idx, &mut reactors.$idx, input(event)),)+
+                        _ => unreachable!(),
+                    }),
+                }
+            }
+
+            fn tokens(&self, tokens: &mut Vec<Token>) {
+                self.reactors.tokens(tokens);
+            }
+        }
+    };
+}
+
+fan_out!(Either, 2; 0 R0 A, 1 R1 B);
+fan_out!(Either3, 3; 0 R0 A, 1 R1 B, 2 R2 C);
+fan_out!(Either4, 4; 0 R0 A, 1 R1 B, 2 R2 C, 3 R3 D);
+fan_out!(Either5, 5; 0 R0 A, 1 R1 B, 2 R2 C, 3 R3 D, 4 R4 E);
+fan_out!(Either6, 6; 0 R0 A, 1 R1 B, 2 R2 C, 3 R3 D, 4 R4 E, 5 R5 F);
+fan_out!(Either7, 7; 0 R0 A, 1 R1 B, 2 R2 C, 3 R3 D, 4 R4 E, 5 R5 F, 6 R6 G);
+fan_out!(Either8, 8; 0 R0 A, 1 R1 B, 2 R2 C, 3 R3 D, 4 R4 E, 5 R5 F, 6 R6 G, 7 R7 H);
+fan_out!(Either9, 9; 0 R0 A, 1 R1 B, 2 R2 C, 3 R3 D, 4 R4 E, 5 R5 F, 6 R6 G, 7 R7 H, 8 R8 I);
+fan_out!(Either10, 10; 0 R0 A, 1 R1 B, 2 R2 C, 3 R3 D, 4 R4 E, 5 R5 F, 6 R6 G, 7 R7 H, 8 R8 I, 9 R9 J);
+fan_out!(Either11, 11; 0 R0 A, 1 R1 B, 2 R2 C, 3 R3 D, 4 R4 E, 5 R5 F, 6 R6 G, 7 R7 H, 8 R8 I, 9 R9 J, 10 R10 K);
+fan_out!(Either12, 12; 0 R0 A, 1 R1 B, 2 R2 C, 3 R3 D, 4 R4 E, 5 R5 F, 6 R6 G, 7 R7 H, 8 R8 I, 9 R9 J, 10 R10 K, 11 R11 L);
@@ -0,0 +1,198 @@
+use std::any::Any;
+use std::fmt::{self, Debug, Formatter};
+use std::panic::{self, AssertUnwindSafe};
+
+use mio::Token;
+
+use crate::errors::Result;
+
+use super::{Reaction, Reactor};
+
+/// A panic caught by [`Isolate`] (or by [`Connections::drop_on_panic`]).
+///
+/// [`Isolate`]: struct.Isolate.html
+/// [`Connections::drop_on_panic`]: ../net/connections/struct.Connections.html#method.drop_on_panic
+#[derive(Debug, Clone)]
+pub struct Panic {
+    message: String,
+    token: Option<Token>,
+}
+
+impl Panic {
+    pub(crate) fn new(payload: &(dyn Any + Send), token: Option<Token>) -> Self {
+        let message = match payload.downcast_ref::<&str>() {
+            Some(message) => message.to_string(),
+            None => match payload.downcast_ref::<String>() {
+                Some(message) => message.clone(),
+                None => "unknown panic".into(),
+            },
+        };
+
+        Self { message, token }
+    }
+
+    /// The panic message
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The token of the event the reactor was reacting to, if any
+    pub fn token(&self) -> Option<Token> {
+        self.token
+    }
+}
+
+pub(crate) type PanicHook = Box<dyn FnMut(&Panic)>;
+
+enum Policy<R> {
+    Drop,
+    Restart(Box<dyn FnMut() -> Result<R>>),
+    Escalate,
+}
+
+// -----------------------------------------------------------------------------
+// 		- Isolate -
+// -----------------------------------------------------------------------------
+/// Catch panics in a reactor, rather than letting the panic unwind out of
+/// [`System::start`] and take down every reactor on the thread.
+///
+/// What happens to the reactor after a panic depends on the policy:
+/// * drop the reactor (the default). Events are passed on from then on.
+/// * [`restart`] the reactor, replacing it with a new reactor.
+/// * [`escalate`] the panic, after it's been reported.
+///
+/// Every panic is reported to the [`on_panic`] hook.
+///
+///```
+/// # use std::time::Duration;
+/// # use sonr::prelude::*;
+/// # use sonr::errors::Result;
+/// use sonr::reactor::producers::Mono;
+/// use sonr::reactor::timer::Timeout;
+///
+/// fn main() -> Result<()> {
+///     let system_sig = System::init()?;
+///
+///     let failing = Mono::new(0u32)?
+///         .map(|n| 10 / n)
+///         .isolate()
+///         .on_panic(|panic| eprintln!("reactor failed: {}", panic.message()));
+///
+///     // The System keeps running after the panic
+///     let run = failing.and(Timeout::new(Duration::from_millis(10))?.map(move |_| {
+///         system_sig.send(SystemEvent::Stop);
+///     }));
+///
+///     System::start(run)?;
+///     Ok(())
+/// }
+/// ```
+///
+/// [`System::start`]: ../system/struct.System.html#method.start
+/// [`restart`]: struct.Isolate.html#method.restart
+/// [`escalate`]: struct.Isolate.html#method.escalate
+/// [`on_panic`]: struct.Isolate.html#method.on_panic
+pub struct Isolate<R> {
+    inner: Option<R>,
+    policy: Policy<R>,
+    hook: Option<PanicHook>,
+    panics: usize,
+}
+
+impl<R: Reactor> Isolate<R> {
+    /// Isolate the reactor
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner: Some(inner),
+            policy: Policy::Drop,
+            hook: None,
+            panics: 0,
+        }
+    }
+
+    /// Call the hook with every panic, e.g. to log the panic
+    pub fn on_panic<F: FnMut(&Panic) + 'static>(mut self, hook: F) -> Self {
+        self.hook = Some(Box::new(hook));
+        self
+    }
+
+    /// Replace the reactor with a new reactor after a panic.
+    /// If `new_reactor` returns an error the reactor is dropped.
+    pub fn restart<F: FnMut() -> Result<R> + 'static>(mut self, new_reactor: F) -> Self {
+        self.policy = Policy::Restart(Box::new(new_reactor));
+        self
+    }
+
+    /// Resume the panic once it's been reported
+    pub fn escalate(mut self) -> Self {
+        self.policy = Policy::Escalate;
+        self
+    }
+
+    /// Reference the inner reactor.
+    /// `None` if the reactor was dropped after a panic.
+    pub fn inner(&self) -> Option<&R> {
+        self.inner.as_ref()
+    }
+
+    /// Number of panics caught so far
+    pub fn panics(&self) -> usize {
+        self.panics
+    }
+
+    fn panicked(&mut self, payload: Box<dyn Any + Send>, token: Option<Token>) {
+        self.panics += 1;
+        // Drop the reactor before creating a new one, so tokens can be reused
+        self.inner = None;
+
+        if let Some(hook) = self.hook.as_mut() {
+            hook(&Panic::new(payload.as_ref(), token));
+        }
+
+        match self.policy {
+            Policy::Drop => {}
+            Policy::Restart(ref mut new_reactor) => self.inner = new_reactor().ok(),
+            Policy::Escalate => panic::resume_unwind(payload),
+        }
+    }
+}
+
+impl<R: Reactor> Reactor for Isolate<R> {
+    type Input = R::Input;
+    type Output = R::Output;
+
+    fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
+        let event = match reaction {
+            Reaction::Event(event) => Some(event),
+            _ => None,
+        };
+
+        let inner = match self.inner.as_mut() {
+            Some(inner) => inner,
+            None => return event.map_or(Reaction::Continue, Reaction::Event),
+        };
+
+        match panic::catch_unwind(AssertUnwindSafe(|| inner.react(reaction))) {
+            Ok(reaction) => reaction,
+            Err(payload) => {
+                self.panicked(payload, event.map(|event| event.token()));
+                event.map_or(Reaction::Continue, Reaction::Event)
+            }
+        }
+    }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        if let Some(inner) = self.inner.as_ref() {
+            inner.tokens(tokens);
+        }
+    }
+}
+
+impl<R> Debug for Isolate<R> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("Isolate")
+            .field("running", &self.inner.is_some())
+            .field("panics", &self.panics)
+            .finish()
+    }
+}
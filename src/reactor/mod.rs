@@ -58,7 +58,7 @@
 //! ```
 //!
 //!
-use mio::{Event, Evented, Ready, Token};
+use mio::{Event, Evented, PollOpt, Ready, Token};
 use std::fmt::{self, Debug, Formatter};
 use std::io::{self, ErrorKind::WouldBlock, Read, Write};
 use std::marker::PhantomData;
@@ -67,10 +67,21 @@ use super::system::System;
 use crate::errors::Result;
 
 mod combinators;
+mod fallible;
+mod fanout;
+mod isolate;
 pub mod consumers;
 pub mod producers;
+pub mod timer;
 
-pub use combinators::{And, Chain, Either, Map, Or};
+pub use combinators::{And, Chain, Either, Filter, FilterMap, FlatMap, Inspect, Map, Or, Skip, Take};
+pub use fanout::{
+    all, any, select, All, Any, Either10, Either11, Either12, Either3, Either4, Either5, Either6,
+    Either7, Either8, Either9, Select,
+};
+pub use fallible::{Fallible, MapErr, OrElse, Recover, TakeError, TryChain, TryReactor};
+pub use isolate::{Isolate, Panic};
+pub(crate) use isolate::PanicHook;
 
 /// Input / Output of a [`Reactor`].
 ///
@@ -122,6 +133,27 @@ pub trait Reactor: Sized {
     /// `Reaction::Continue`
     fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output>;
 
+    /// Add the tokens of the evented reactors owned by this reactor.
+    ///
+    /// Reactors with more than one branch ([`And`], [`Or`], [`all`], [`select`] and [`any`])
+    /// use the tokens to pass an event straight to the branch that owns the event's token,
+    /// rather than passing the event to every branch.
+    ///
+    /// Tokens reserved while a branch is reacting are routed to the branch as well,
+    /// as long as the branch adds them here by the time the first event arrives.
+    /// Events for a reserved token that the branch doesn't add (e.g. the reactor
+    /// holding the token was spawned) are passed to every branch.
+    ///
+    /// The default adds no tokens, and an event for a token that no branch
+    /// owns is passed to every branch.
+    ///
+    /// [`And`]: struct.And.html
+    /// [`Or`]: struct.Or.html
+    /// [`all`]: fn.all.html
+    /// [`select`]: fn.select.html
+    /// [`any`]: fn.any.html
+    fn tokens(&self, _tokens: &mut Vec<Token>) {}
+
     /// Chain two reactors together.
     /// The output of the first reactor is the input of the second reactor.
     fn chain<T: Reactor>(self, to: T) -> Chain<Self, T> {
@@ -213,6 +245,66 @@ pub trait Reactor: Sized {
     fn or<T: Reactor>(self, second: T) -> Or<Self, T> {
         Or::new(self, second)
     }
+
+    /// Only output the values for which the predicate returns `true`.
+    /// See [`Filter`].
+    ///
+    /// [`Filter`]: struct.Filter.html
+    fn filter<F>(self, predicate: F) -> Filter<Self, F>
+    where
+        F: FnMut(&Self::Output) -> bool,
+    {
+        Filter::new(self, predicate)
+    }
+
+    /// Map the values, dropping every value for which the closure returns `None`.
+    /// See [`FilterMap`].
+    ///
+    /// [`FilterMap`]: struct.FilterMap.html
+    fn filter_map<F, T>(self, callback: F) -> FilterMap<Self, F, T>
+    where
+        F: FnMut(Self::Output) -> Option<T>,
+    {
+        FilterMap::new(self, callback)
+    }
+
+    /// Turn each value into any number of values.
+    /// See [`FlatMap`].
+    ///
+    /// [`FlatMap`]: struct.FlatMap.html
+    fn flat_map<F, I>(self, callback: F) -> FlatMap<Self, F, I>
+    where
+        F: FnMut(Self::Output) -> I,
+        I: IntoIterator,
+    {
+        FlatMap::new(self, callback)
+    }
+
+    /// Call a closure with a reference to each value.
+    fn inspect<F>(self, callback: F) -> Inspect<Self, F>
+    where
+        F: FnMut(&Self::Output),
+    {
+        Inspect::new(self, callback)
+    }
+
+    /// Output the first `n` values and drop the rest.
+    fn take(self, n: usize) -> Take<Self> {
+        Take::new(self, n)
+    }
+
+    /// Drop the first `n` values and output the rest.
+    fn skip(self, n: usize) -> Skip<Self> {
+        Skip::new(self, n)
+    }
+
+    /// Catch panics in the reactor.
+    /// See [`Isolate`].
+    ///
+    /// [`Isolate`]: struct.Isolate.html
+    fn isolate(self) -> Isolate<Self> {
+        Isolate::new(self)
+    }
 }
 
 // -----------------------------------------------------------------------------
@@ -227,6 +319,9 @@ pub trait Reactor: Sized {
 /// and when it dropps the [`Token`] registered with the `EventedReactor` is freed
 /// to be reused with another `EventedReactor`.
 ///
+/// The reactor is registered with the System's default `PollOpt` (edge triggered
+/// unless set with [`SystemBuilder::poll_opt`]), or the `PollOpt` passed to [`with_opts`].
+///
 /// The `EventedReactor` does not implement [`Reactor`] by it self,
 /// but rather acts as a building block when creating a Reactor that should
 /// also be evented.
@@ -237,10 +332,13 @@ pub trait Reactor: Sized {
 /// [`Stream`]: ../net/stream/struct.Stream.html
 /// [`System`]: ../system/struct.System.html
 /// [`Token`]: ../struct.Token.html
+/// [`SystemBuilder::poll_opt`]: ../system/struct.SystemBuilder.html#method.poll_opt
+/// [`with_opts`]: struct.EventedReactor.html#method.with_opts
 pub struct EventedReactor<E: Evented> {
     inner: E,
     token: Token,
     interest: Ready,
+    opts: PollOpt,
     pub(crate) is_readable: bool,
     pub(crate) is_writable: bool,
     _not_send: PhantomData<*const ()>, // Make the evented reactor !Send
@@ -255,6 +353,7 @@ where
             .field("inner", &self.inner)
             .field("token", &self.token)
             .field("interest", &self.interest)
+            .field("opts", &self.opts)
             .field("is_readable", &self.is_readable)
             .field("is_writable", &self.is_writable)
             .finish()
@@ -264,13 +363,26 @@ where
 impl<E: Evented> EventedReactor<E> {
     /// Create a new instance of an `EventedReactor`.
     pub fn new(inner: E, interest: Ready) -> Result<Self> {
+        Self::with_opts(inner, interest, System::poll_opt())
+    }
+
+    /// Create a new instance of an `EventedReactor`, registered with `opts`
+    /// rather than the System's default.
+    ///
+    /// Note that a oneshot registration has to be reregistered (see [`System::reregister`])
+    /// to receive another event. Streams and listeners reregister once a read, write
+    /// or accept would block.
+    ///
+    /// [`System::reregister`]: ../system/struct.System.html#method.reregister
+    pub fn with_opts(inner: E, interest: Ready, opts: PollOpt) -> Result<Self> {
         let token = System::reserve_token()?;
-        System::register(&inner, interest, token)?;
+        System::register_with_opts(&inner, interest, token, opts)?;
 
         Ok(Self {
             inner,
             token,
             interest,
+            opts,
             is_readable: false,
             is_writable: false,
             _not_send: PhantomData,
@@ -297,11 +409,21 @@ impl<E: Evented> EventedReactor<E> {
     pub fn interest(&self) -> Ready {
         self.interest
     }
+
+    /// Return the `PollOpt` the reactor is registered with
+    pub fn opts(&self) -> PollOpt {
+        self.opts
+    }
 }
 
-impl<E: Evented + Read> Read for EventedReactor<E> {
-    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        let res = self.inner_mut().read(buf);
+impl<E: Evented> EventedReactor<E> {
+    // Run a read operation on the inner type, updating
+    // the readiness the same way `Read::read` does.
+    pub(crate) fn read_with<F>(&mut self, f: F) -> io::Result<usize>
+    where
+        F: FnOnce(&mut E) -> io::Result<usize>,
+    {
+        let res = f(self.inner_mut());
 
         match res {
             Err(ref e) if e.kind() == WouldBlock => {
@@ -324,11 +446,14 @@ impl<E: Evented + Read> Read for EventedReactor<E> {
 
         res
     }
-}
 
-impl<E: Evented + Write> Write for EventedReactor<E> {
-    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        let res = self.inner_mut().write(buf);
+    // Run a write operation on the inner type, updating
+    // the readiness the same way `Write::write` does.
+    pub(crate) fn write_with<F>(&mut self, f: F) -> io::Result<usize>
+    where
+        F: FnOnce(&mut E) -> io::Result<usize>,
+    {
+        let res = f(self.inner_mut());
 
         match res {
             Err(ref e) if e.kind() == WouldBlock => {
@@ -350,6 +475,18 @@ impl<E: Evented + Write> Write for EventedReactor<E> {
 
         res
     }
+}
+
+impl<E: Evented + Read> Read for EventedReactor<E> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.read_with(|inner| inner.read(buf))
+    }
+}
+
+impl<E: Evented + Write> Write for EventedReactor<E> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_with(|inner| inner.write(buf))
+    }
 
     fn flush(&mut self) -> io::Result<()> {
         self.inner_mut().flush()
@@ -26,7 +26,7 @@
 //! [`System`]: ../../system/struct.System.html
 use std::mem;
 use std::collections::VecDeque;
-use mio::{Registration, Ready};
+use mio::{Registration, Ready, Token};
 use crate::errors::Result;
 
 use super::{Reactor, Reaction, EventedReactor};
@@ -93,6 +93,10 @@ impl<T> Reactor for ReactiveGenerator<T> {
             Reaction::Value(_) => Reaction::Continue,
         }
     }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        tokens.push(self.reactor.token());
+    }
 }
 
 /// A [`Mono`] reacts as soon as the [`System`] starts and produces exactly one value
@@ -153,4 +157,8 @@ impl<T> Reactor for Mono<T> {
             Reaction::Value(_) => Reaction::Continue,
         }
     }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        tokens.push(self.reactor.token());
+    }
 }
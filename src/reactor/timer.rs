@@ -0,0 +1,276 @@
+//! Timers driven by the [`System`].
+//!
+//! There are two timers:
+//! *  [`Timeout`]
+//! *  [`Interval`]
+//!
+//! Each timer reserves a [`Token`] from the [`System`], and when the timer expires the
+//! [`System`] passes a `Reaction::Event(event)` with that token (and an empty readiness)
+//! down the reactor chain.
+//!
+//! ```
+//! # use std::time::Duration;
+//! # use sonr::prelude::*;
+//! # use sonr::errors::Result;
+//! use sonr::reactor::timer::Timeout;
+//!
+//! fn main() -> Result<()> {
+//!     let handle = System::init()?;
+//!     let timeout = Timeout::new(Duration::from_millis(10))?
+//!         .map(|_| {
+//!             eprintln!("timed out");
+//!             handle.send(SystemEvent::Stop)
+//!         });
+//!
+//!     System::start(timeout)?;
+//!     Ok(())
+//! }
+//! ```
+//!
+//! [`Timeout`]: struct.Timeout.html
+//! [`Interval`]: struct.Interval.html
+//! [`Token`]: ../../struct.Token.html
+//! [`System`]: ../../system/struct.System.html
+use std::marker::PhantomData;
+use std::time::{Duration, Instant};
+
+use mio::Token;
+
+use crate::errors::Result;
+use crate::system::System;
+
+use super::{Reaction, Reactor};
+
+// -----------------------------------------------------------------------------
+// 		- Timeout -
+// -----------------------------------------------------------------------------
+/// A `Timeout` reacts once when the duration has passed.
+///
+/// The timeout starts as soon as it's created, and can be restarted
+/// by calling [`reset`].
+///
+/// ```
+/// # use std::time::{Duration, Instant};
+/// # use sonr::prelude::*;
+/// # use sonr::errors::Result;
+/// use sonr::reactor::timer::Timeout;
+///
+/// fn main() -> Result<()> {
+///     let handle = System::init()?;
+///     let started = Instant::now();
+///     let timeout = Timeout::new(Duration::from_millis(20))?
+///         .map(|_| {
+///             assert!(started.elapsed() >= Duration::from_millis(20));
+///             handle.send(SystemEvent::Stop)
+///         });
+///
+///     System::start(timeout)?;
+///     Ok(())
+/// }
+/// ```
+///
+/// [`reset`]: struct.Timeout.html#method.reset
+pub struct Timeout {
+    token: Token,
+    duration: Duration,
+    deadline: Option<Instant>,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Timeout {
+    /// Create a new `Timeout` expiring after `duration`.
+    pub fn new(duration: Duration) -> Result<Self> {
+        let token = System::reserve_token()?;
+        let mut timeout = Self {
+            token,
+            duration,
+            deadline: None,
+            _not_send: PhantomData,
+        };
+        timeout.reset();
+        Ok(timeout)
+    }
+
+    /// The `Token` the timer is registered with.
+    pub fn token(&self) -> Token {
+        self.token
+    }
+
+    /// Restart the timeout, expiring `duration` from now.
+    pub fn reset(&mut self) {
+        let deadline = Instant::now() + self.duration;
+        System::schedule_timer(self.token, deadline);
+        self.deadline = Some(deadline);
+    }
+
+    /// Cancel the timeout.
+    /// The timeout will not react unless it's [`reset`].
+    ///
+    /// [`reset`]: struct.Timeout.html#method.reset
+    pub fn cancel(&mut self) {
+        System::cancel_timer(self.token);
+        self.deadline = None;
+    }
+
+    /// The instant the timeout expires, or `None` if the
+    /// timeout has expired or was cancelled.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+}
+
+impl Reactor for Timeout {
+    type Output = ();
+    type Input = ();
+
+    fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
+        match reaction {
+            Reaction::Event(event) => {
+                if event.token() != self.token {
+                    return event.into();
+                }
+                self.deadline = None;
+                Reaction::Value(())
+            }
+            Reaction::Continue => Reaction::Continue,
+            Reaction::Value(_) => Reaction::Continue,
+        }
+    }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        tokens.push(self.token);
+    }
+}
+
+impl Drop for Timeout {
+    fn drop(&mut self) {
+        System::free_token(self.token);
+    }
+}
+
+// -----------------------------------------------------------------------------
+// 		- Interval -
+// -----------------------------------------------------------------------------
+/// An `Interval` reacts every time the duration has passed, outputting
+/// the number of times the interval has reacted so far.
+///
+/// ```
+/// # use std::time::Duration;
+/// # use sonr::prelude::*;
+/// # use sonr::errors::Result;
+/// use sonr::reactor::timer::Interval;
+///
+/// fn main() -> Result<()> {
+///     let handle = System::init()?;
+///     let interval = Interval::new(Duration::from_millis(5))?
+///         .map(|count| {
+///             if count == 3 {
+///                 handle.send(SystemEvent::Stop);
+///             }
+///         });
+///
+///     System::start(interval)?;
+///     Ok(())
+/// }
+/// ```
+pub struct Interval {
+    token: Token,
+    duration: Duration,
+    next: Instant,
+    count: u64,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Interval {
+    /// Create a new `Interval`, the first reaction happens
+    /// after `duration`.
+    pub fn new(duration: Duration) -> Result<Self> {
+        let token = System::reserve_token()?;
+        let next = Instant::now() + duration;
+        System::schedule_timer(token, next);
+        Ok(Self {
+            token,
+            duration,
+            next,
+            count: 0,
+            _not_send: PhantomData,
+        })
+    }
+
+    /// The `Token` the timer is registered with.
+    pub fn token(&self) -> Token {
+        self.token
+    }
+}
+
+impl Reactor for Interval {
+    type Output = u64;
+    type Input = ();
+
+    fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
+        match reaction {
+            Reaction::Event(event) => {
+                if event.token() != self.token {
+                    return event.into();
+                }
+
+                // Schedule from the previous deadline rather than now
+                // to prevent the interval from drifting, but never
+                // schedule in the past.
+                let now = Instant::now();
+                self.next += self.duration;
+                if self.next < now {
+                    self.next = now + self.duration;
+                }
+                System::schedule_timer(self.token, self.next);
+
+                self.count += 1;
+                Reaction::Value(self.count)
+            }
+            Reaction::Continue => Reaction::Continue,
+            Reaction::Value(_) => Reaction::Continue,
+        }
+    }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        tokens.push(self.token);
+    }
+}
+
+impl Drop for Interval {
+    fn drop(&mut self) {
+        System::free_token(self.token);
+    }
+}
+
+// -----------------------------------------------------------------------------
+// 		- Timer token -
+// -----------------------------------------------------------------------------
+// A token used as a timer by a reactor that schedules its own deadlines,
+// freed when dropped.
+pub(crate) struct TimerToken(Token);
+
+impl TimerToken {
+    pub(crate) fn new() -> Result<Self> {
+        Ok(Self(System::reserve_token()?))
+    }
+
+    pub(crate) fn token(&self) -> Token {
+        self.0
+    }
+
+    // Any earlier deadline is replaced
+    pub(crate) fn schedule(&self, deadline: Instant) {
+        System::schedule_timer(self.0, deadline);
+    }
+
+    pub(crate) fn cancel(&self) {
+        System::cancel_timer(self.0);
+    }
+}
+
+impl Drop for TimerToken {
+    fn drop(&mut self) {
+        System::free_token(self.0);
+    }
+}
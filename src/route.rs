@@ -0,0 +1,263 @@
+use std::collections::HashMap;
+
+use mio::{Event, Token};
+
+use crate::reactor::{Reaction, Reactor};
+use crate::system::System;
+
+// The token is reported by more than one branch of the same router,
+// so events for the token are passed to every branch.
+const ALL_BRANCHES: usize = usize::MAX;
+
+// -----------------------------------------------------------------------------
+// 		- RouteTable -
+// -----------------------------------------------------------------------------
+/// Maps a token to the branch that owns the token, for each router
+/// (`And`, `Or`, `all`, `select` and `any`) between the token and the System.
+///
+/// A route is added either when a router is created (for the tokens its reactors
+/// report through `Reactor::tokens`) or when a token is reserved while a router
+/// passes a reaction to one of its branches.
+///
+/// The route of a reserved token is only a guess, as the reactor holding the token
+/// can be moved elsewhere (e.g. spawned). It's confirmed by the first event for the
+/// token if the branch reports the token through `Reactor::tokens`, otherwise
+/// events for the token are passed to every branch.
+///
+/// Tokens without a route are passed to every branch.
+pub(crate) struct RouteTable {
+    routes: HashMap<Token, Vec<Route>>,
+    path: Vec<(usize, usize)>,
+    next_router: usize,
+}
+
+// The branch of a router that owns a token, and whether the branch
+// is known to own the token (rather than reserved it).
+#[derive(Debug, Clone, Copy)]
+struct Route {
+    router: usize,
+    branch: usize,
+    claimed: bool,
+}
+
+impl RouteTable {
+    /// Create an empty route table
+    pub(crate) fn new() -> Self {
+        Self {
+            routes: HashMap::new(),
+            path: Vec::new(),
+            next_router: 0,
+        }
+    }
+
+    /// A unique id for a new router
+    pub(crate) fn next_router(&mut self) -> usize {
+        self.next_router += 1;
+        self.next_router
+    }
+
+    /// Route events for the token to the branch of the router.
+    pub(crate) fn insert(&mut self, token: Token, router: usize, branch: usize) {
+        let routes = self.routes.entry(token).or_default();
+        match routes.iter_mut().find(|route| route.router == router) {
+            Some(route) if route.branch != branch => route.branch = ALL_BRANCHES,
+            Some(route) => route.claimed = true,
+            None => routes.push(Route { router, branch, claimed: true }),
+        }
+    }
+
+    /// The branch of the router that owns the token (if any),
+    /// and whether the branch has claimed the token.
+    pub(crate) fn branch(&self, token: Token, router: usize) -> Option<(usize, bool)> {
+        self.routes
+            .get(&token)?
+            .iter()
+            .find(|route| route.router == router && route.branch != ALL_BRANCHES)
+            .map(|route| (route.branch, route.claimed))
+    }
+
+    /// Confirm the route of a reserved token, or pass
+    /// events for the token to every branch of the router.
+    pub(crate) fn verify(&mut self, token: Token, router: usize, claimed: bool) {
+        let routes = match self.routes.get_mut(&token) {
+            Some(routes) => routes,
+            None => return,
+        };
+
+        if let Some(route) = routes.iter_mut().find(|route| route.router == router) {
+            match claimed {
+                true => route.claimed = true,
+                false => route.branch = ALL_BRANCHES,
+            }
+        }
+    }
+
+    /// The router is passing a reaction to the branch.
+    pub(crate) fn enter(&mut self, router: usize, branch: usize) {
+        self.path.push((router, branch));
+    }
+
+    /// The branch has returned a reaction.
+    pub(crate) fn leave(&mut self) {
+        self.path.pop();
+    }
+
+    /// Route a newly reserved token through the branches currently reacting.
+    pub(crate) fn reserve(&mut self, token: Token) {
+        if self.path.is_empty() {
+            self.routes.remove(&token);
+        } else {
+            let routes = self.path
+                .iter()
+                .map(|&(router, branch)| Route { router, branch, claimed: false })
+                .collect();
+            self.routes.insert(token, routes);
+        }
+    }
+
+    /// Remove the routes of the token.
+    pub(crate) fn remove(&mut self, token: Token) {
+        self.routes.remove(&token);
+    }
+}
+
+/// Returns `true` if the reactor reports the token through `Reactor::tokens`
+pub(crate) fn claims<R: Reactor>(reactor: &R, token: Token) -> bool {
+    let mut tokens = Vec::new();
+    reactor.tokens(&mut tokens);
+    tokens.contains(&token)
+}
+
+// -----------------------------------------------------------------------------
+// 		- Router -
+// -----------------------------------------------------------------------------
+/// Used by the combinators with more than one branch to pass events
+/// straight to the branch that owns the token.
+pub(crate) struct Router {
+    id: usize,
+}
+
+/// Leaves the branch when dropped, even if the reactor panics.
+pub(crate) struct Entered;
+
+impl Drop for Entered {
+    fn drop(&mut self) {
+        System::leave_route();
+    }
+}
+
+impl Router {
+    /// Create a new router.
+    /// Without a System every event is passed to every branch.
+    pub(crate) fn new() -> Self {
+        Self { id: System::next_router() }
+    }
+
+    /// The router of the reactors passed to `System::start` and `System::spawn`.
+    pub(crate) fn with_id(id: usize) -> Self {
+        Self { id }
+    }
+
+    /// Route the tokens reported by the reactor to the branch.
+    pub(crate) fn add<R: Reactor>(&self, branch: usize, reactor: &R) {
+        if self.id == 0 {
+            return;
+        }
+
+        let mut tokens = Vec::new();
+        reactor.tokens(&mut tokens);
+        for token in tokens {
+            System::add_route(token, self.id, branch);
+        }
+    }
+
+    /// The branch that owns the event's token.
+    /// `None` if the event should be passed to every branch.
+    ///
+    /// `claims(branch)` is called the first time a reserved token is routed,
+    /// and returns `true` if the branch reports the token (see [`claims`]).
+    pub(crate) fn branch<F>(&self, event: &Event, claims: F) -> Option<usize>
+    where
+        F: FnOnce(usize) -> bool,
+    {
+        if self.id == 0 {
+            return None;
+        }
+
+        let token = event.token();
+        match System::route(token, self.id)? {
+            (branch, true) => Some(branch),
+            (branch, false) => {
+                let claimed = claims(branch);
+                System::verify_route(token, self.id, claimed);
+                if claimed {
+                    Some(branch)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    /// Pass the reaction to the reactor of the branch.
+    /// Tokens reserved while the reactor reacts are routed to the branch.
+    pub(crate) fn react<R: Reactor>(
+        &self,
+        branch: usize,
+        reactor: &mut R,
+        reaction: Reaction<R::Input>,
+    ) -> Reaction<R::Output> {
+        let _entered = self.enter(branch);
+        reactor.react(reaction)
+    }
+
+    /// Tokens reserved until the returned guard is dropped are routed to the branch.
+    pub(crate) fn enter(&self, branch: usize) -> Entered {
+        System::enter_route(self.id, branch);
+        Entered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn route_reserved_tokens() {
+        let mut table = RouteTable::new();
+        let outer = table.next_router();
+        let inner = table.next_router();
+
+        table.enter(outer, 1);
+        table.enter(inner, 0);
+        table.reserve(Token(5));
+        table.leave();
+        table.leave();
+        table.reserve(Token(6));
+
+        assert_eq!(table.branch(Token(5), outer), Some((1, false)));
+        assert_eq!(table.branch(Token(5), inner), Some((0, false)));
+        assert_eq!(table.branch(Token(6), outer), None);
+
+        // The outer branch reports the token, the inner branch doesn't
+        table.verify(Token(5), outer, true);
+        table.verify(Token(5), inner, false);
+        assert_eq!(table.branch(Token(5), outer), Some((1, true)));
+        assert_eq!(table.branch(Token(5), inner), None);
+
+        table.remove(Token(5));
+        assert_eq!(table.branch(Token(5), outer), None);
+    }
+
+    #[test]
+    fn shared_token_goes_to_all_branches() {
+        let mut table = RouteTable::new();
+        let router = table.next_router();
+        table.insert(Token(1), router, 0);
+        table.insert(Token(1), router, 0);
+        assert_eq!(table.branch(Token(1), router), Some((0, true)));
+
+        table.insert(Token(1), router, 1);
+        assert_eq!(table.branch(Token(1), router), None);
+    }
+}
@@ -0,0 +1,381 @@
+//! Run a reactor on each of a number of worker threads.
+//!
+//! Each worker thread has its own [`System`], running the reactor created for it.
+//! Work is handed to the workers either through a [`ReactiveQueue`] (e.g. the
+//! connections accepted by a listener on the current thread), or by letting
+//! each worker bind its own listener with `SO_REUSEPORT`.
+//!
+//!```no_run
+//! # use std::io::Write;
+//! # use std::time::Duration;
+//! # use sonr::prelude::*;
+//! # use sonr::errors::Result;
+//! use sonr::net::tcp::ReactiveTcpListener;
+//! use sonr::runtime::Runtime;
+//!
+//! fn main() -> Result<()> {
+//!     System::init()?;
+//!
+//!     // Accept connections on this thread, and write "bye"
+//!     // to each connection on one of the four workers
+//!     let (runtime, queue) = Runtime::builder()
+//!         .workers(4)
+//!         .name("writer")
+//!         .start_with_queue(|_, deque| {
+//!             Ok(deque.map(|(mut stream, _): (sonr::net::tcp::TcpStream, _)| {
+//!                 let _ = stream.write(b"bye\n");
+//!             }))
+//!         })?;
+//!
+//!     let listener = ReactiveTcpListener::bind("127.0.0.1:8000")?;
+//!     System::start(listener.chain(queue))?;
+//!
+//!     runtime.shutdown(Duration::from_secs(5));
+//!     runtime.join()?;
+//!     Ok(())
+//! }
+//! ```
+//!
+//! [`System`]: ../system/struct.System.html
+//! [`ReactiveQueue`]: ../sync/queue/struct.ReactiveQueue.html
+use std::panic;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use parking_lot::Mutex;
+
+use crate::errors::Result;
+use crate::reactor::Reactor;
+use crate::sync::queue::{Dequeue, ReactiveDeque, ReactiveQueue};
+use crate::system::{ShutdownSummary, System, SystemBuilder, SystemHandle};
+
+#[cfg(unix)]
+use crate::net::tcp::{ReactiveTcpListener, TcpListenerBuilder};
+
+// -----------------------------------------------------------------------------
+// 		- Runtime builder -
+// -----------------------------------------------------------------------------
+/// Configure and start a [`Runtime`].
+///
+/// [`Runtime`]: struct.Runtime.html
+#[derive(Debug, Clone)]
+pub struct RuntimeBuilder {
+    workers: usize,
+    name: String,
+    stack_size: Option<usize>,
+    pin_cores: bool,
+    system: SystemBuilder,
+}
+
+impl Default for RuntimeBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RuntimeBuilder {
+    /// Create a new builder, with one worker per cpu
+    pub fn new() -> Self {
+        Self {
+            workers: thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+            name: "sonr-worker".into(),
+            stack_size: None,
+            pin_cores: false,
+            system: SystemBuilder::new(),
+        }
+    }
+
+    /// Number of worker threads
+    pub fn workers(mut self, workers: usize) -> Self {
+        self.workers = workers;
+        self
+    }
+
+    /// The name of the worker threads.
+    /// Each thread is named `name-index`, e.g. `sonr-worker-0`.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Stack size of the worker threads, in bytes
+    pub fn stack_size(mut self, stack_size: usize) -> Self {
+        self.stack_size = Some(stack_size);
+        self
+    }
+
+    /// Pin each worker thread to a cpu core (worker `n` to core `n % cores`).
+    ///
+    /// Only supported on Linux, and ignored elsewhere.
+    pub fn pin_cores(mut self, pin_cores: bool) -> Self {
+        self.pin_cores = pin_cores;
+        self
+    }
+
+    /// The configuration of the [`System`] of each worker.
+    /// Leave the builder unnamed to name each System after its worker thread.
+    ///
+    /// [`System`]: ../system/struct.System.html
+    pub fn system(mut self, system: SystemBuilder) -> Self {
+        self.system = system;
+        self
+    }
+
+    /// Start the workers, each running the reactor returned by `new_reactor`.
+    /// `new_reactor` is called on the worker thread with the index of the worker.
+    ///
+    /// Returns once every worker is running, or with the first error
+    /// returned by `new_reactor` (in which case the other workers are stopped).
+    pub fn start<F, R>(self, new_reactor: F) -> Result<Runtime>
+    where
+        F: Fn(usize) -> Result<R> + Send + Sync + 'static,
+        R: Reactor + 'static,
+    {
+        let new_reactor = Arc::new(new_reactor);
+        let handles = Arc::new(Mutex::new(Vec::new()));
+        let mut workers = Vec::with_capacity(self.workers);
+
+        for index in 0..self.workers {
+            let (tx, rx) = mpsc::channel();
+            let new_reactor = new_reactor.clone();
+            let siblings = handles.clone();
+            let pin_cores = self.pin_cores;
+            let system = self.system.clone();
+
+            let mut builder = thread::Builder::new().name(format!("{}-{}", self.name, index));
+            if let Some(stack_size) = self.stack_size {
+                builder = builder.stack_size(stack_size);
+            }
+
+            let thread = builder.spawn(move || -> Result<ShutdownSummary> {
+                if pin_cores {
+                    pin_to_core(index);
+                }
+
+                let reactor = system.init().and_then(|_| new_reactor(index));
+                let reactor = match reactor {
+                    Ok(reactor) => reactor,
+                    Err(e) => {
+                        let _ = tx.send(Err(e));
+                        return Ok(ShutdownSummary::default());
+                    }
+                };
+
+                let _ = tx.send(Ok(System::handle()));
+                let mut guard = StopOnFailure { siblings, armed: true };
+                let summary = System::start(reactor)?;
+                guard.armed = false;
+                Ok(summary)
+            })?;
+
+            // Wait for the worker to start, so the error can be returned
+            let started = rx.recv();
+            match started {
+                Ok(Ok(handle)) => {
+                    handles.lock().push(handle.clone());
+                    workers.push(Worker { handle, thread });
+                }
+                Ok(Err(e)) => {
+                    let _ = thread.join();
+                    Runtime { workers }.abort();
+                    return Err(e);
+                }
+                // The worker panicked
+                Err(_) => {
+                    Runtime { workers }.abort();
+                    if let Err(payload) = thread.join() {
+                        panic::resume_unwind(payload);
+                    }
+                    unreachable!("the worker exited without starting");
+                }
+            }
+        }
+
+        Ok(Runtime { workers })
+    }
+
+    /// Start the workers, each running the reactor returned by `new_reactor`
+    /// with a [`ReactiveDeque`] of the returned [`ReactiveQueue`].
+    ///
+    /// Values pushed to the queue (e.g. by chaining a listener to the queue)
+    /// are picked up by one of the workers.
+    ///
+    /// [`ReactiveDeque`]: ../sync/queue/struct.ReactiveDeque.html
+    /// [`ReactiveQueue`]: ../sync/queue/struct.ReactiveQueue.html
+    pub fn start_with_queue<T, F, R>(self, new_reactor: F) -> Result<(Runtime, ReactiveQueue<T>)>
+    where
+        T: Send + 'static,
+        F: Fn(usize, ReactiveDeque<T>) -> Result<R> + Send + Sync + 'static,
+        R: Reactor + 'static,
+    {
+        let mut queue = ReactiveQueue::unbounded();
+        let deques: Vec<Mutex<Option<Dequeue<T>>>> = (0..self.workers)
+            .map(|_| Mutex::new(Some(queue.deque())))
+            .collect();
+
+        let runtime = self.start(move |index| {
+            let deque = deques[index].lock().take().expect("each worker has a deque");
+            new_reactor(index, ReactiveDeque::new(deque)?)
+        })?;
+
+        Ok((runtime, queue))
+    }
+
+    /// Start the workers, each running the reactor returned by `new_reactor`
+    /// with a [`ReactiveTcpListener`] bound to `addr` with `SO_REUSEPORT`,
+    /// so the kernel balances incoming connections between the workers.
+    ///
+    /// [`ReactiveTcpListener`]: ../net/tcp/struct.ReactiveTcpListener.html
+    #[cfg(unix)]
+    pub fn start_reuse_port<F, R>(self, addr: &str, new_reactor: F) -> Result<Runtime>
+    where
+        F: Fn(usize, ReactiveTcpListener) -> Result<R> + Send + Sync + 'static,
+        R: Reactor + 'static,
+    {
+        let addr = addr.to_string();
+        self.start(move |index| {
+            let listener = TcpListenerBuilder::new(&addr)?.reuse_port(true).build()?;
+            new_reactor(index, listener)
+        })
+    }
+}
+
+// Stop the other workers if this worker fails (returns an error or panics),
+// as there is no way to restart it.
+struct StopOnFailure {
+    siblings: Arc<Mutex<Vec<SystemHandle>>>,
+    armed: bool,
+}
+
+impl Drop for StopOnFailure {
+    fn drop(&mut self) {
+        if self.armed {
+            for handle in self.siblings.lock().iter() {
+                let _ = handle.stop();
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn pin_to_core(index: usize) {
+    let cores = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        libc::CPU_SET(index % cores, &mut set);
+        libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set);
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn pin_to_core(_index: usize) {}
+
+// -----------------------------------------------------------------------------
+// 		- Runtime -
+// -----------------------------------------------------------------------------
+struct Worker {
+    handle: SystemHandle,
+    thread: JoinHandle<Result<ShutdownSummary>>,
+}
+
+/// A number of worker threads, each running a [`System`].
+/// Created with a [`RuntimeBuilder`].
+///
+/// If a worker returns an error or panics the other workers are stopped,
+/// and the error (or panic) is returned from [`join`].
+///
+/// Dropping the runtime detaches the workers.
+///
+/// [`System`]: ../system/struct.System.html
+/// [`RuntimeBuilder`]: struct.RuntimeBuilder.html
+/// [`join`]: struct.Runtime.html#method.join
+pub struct Runtime {
+    workers: Vec<Worker>,
+}
+
+impl Runtime {
+    /// Create a [`RuntimeBuilder`].
+    ///
+    /// [`RuntimeBuilder`]: struct.RuntimeBuilder.html
+    pub fn builder() -> RuntimeBuilder {
+        RuntimeBuilder::new()
+    }
+
+    /// Number of workers
+    pub fn len(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// Returns `true` if the runtime has no workers
+    pub fn is_empty(&self) -> bool {
+        self.workers.is_empty()
+    }
+
+    /// The [`SystemHandle`]s of the workers, in the order the workers were started
+    ///
+    /// [`SystemHandle`]: ../system/struct.SystemHandle.html
+    pub fn handles(&self) -> Vec<SystemHandle> {
+        self.workers.iter().map(|w| w.handle.clone()).collect()
+    }
+
+    /// Stop every worker.
+    /// Workers that already stopped are ignored.
+    pub fn stop(&self) {
+        for worker in &self.workers {
+            let _ = worker.handle.stop();
+        }
+    }
+
+    /// Shut every worker down gracefully.
+    /// Workers that already stopped are ignored.
+    ///
+    /// See [`SystemEvent::Shutdown`].
+    ///
+    /// [`SystemEvent::Shutdown`]: ../system/enum.SystemEvent.html#variant.Shutdown
+    pub fn shutdown(&self, grace: Duration) {
+        for worker in &self.workers {
+            let _ = worker.handle.shutdown(grace);
+        }
+    }
+
+    /// Wait for every worker to stop, and return the summary of each worker.
+    ///
+    /// If a worker returned an error the first error is returned, and if a worker
+    /// panicked the panic is resumed on the current thread.
+    pub fn join(self) -> Result<Vec<ShutdownSummary>> {
+        let mut summaries = Vec::with_capacity(self.workers.len());
+        let mut error = None;
+        let mut panicked = None;
+
+        for worker in self.workers {
+            match worker.thread.join() {
+                Ok(Ok(summary)) => summaries.push(summary),
+                Ok(Err(e)) => {
+                    error.get_or_insert(e);
+                }
+                Err(payload) => {
+                    panicked.get_or_insert(payload);
+                }
+            }
+        }
+
+        if let Some(payload) = panicked {
+            panic::resume_unwind(payload);
+        }
+
+        match error {
+            Some(e) => Err(e),
+            None => Ok(summaries),
+        }
+    }
+
+    // Stop and join the workers, ignoring the result
+    fn abort(self) {
+        self.stop();
+        for worker in self.workers {
+            let _ = worker.thread.join();
+        }
+    }
+}
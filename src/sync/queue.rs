@@ -5,6 +5,7 @@ use crossbeam::deque::{Worker, Stealer, Steal};
 use mio::{Poll, Evented, Ready, PollOpt, Token};
 
 use crate::sync::signal::{SignalReceiver, SignalSender}; 
+use crate::metrics::{Counter, Gauge, Registry};
 use crate::reactor::{Reactor, EventedReactor, Reaction};
 use crate::errors::Result;
 
@@ -104,6 +105,13 @@ impl<T: Send + 'static> ReactiveQueue<T> {
     pub fn deque(&mut self) -> Dequeue<T> {
         self.inner.deque()
     }
+
+    /// See [`Queue::register_metrics`].
+    ///
+    /// [`Queue::register_metrics`]: struct.Queue.html#method.register_metrics
+    pub fn register_metrics(&self, name: &str) {
+        self.inner.register_metrics(name);
+    }
 }
 
 impl<T: Send + 'static> Reactor for ReactiveQueue<T> {
@@ -130,6 +138,8 @@ pub struct Queue<T> {
     inner_stealer: Stealer<T>,
     publishers: Vec<SignalSender<()>>,
     capacity: Capacity,
+    depth: Gauge,
+    pushed: Counter,
 }
 
 impl<T: Send + 'static> Queue<T> {
@@ -142,6 +152,8 @@ impl<T: Send + 'static> Queue<T> {
             inner_stealer,
             publishers: Vec::new(),
             capacity,
+            depth: Gauge::new(),
+            pushed: Counter::new(),
         }
     }
 
@@ -160,6 +172,8 @@ impl<T: Send + 'static> Queue<T> {
 
     /// Push a value onto the queue
     pub fn push(&self, val: T) {
+        self.depth.inc();
+        self.pushed.inc();
         self.worker.push(val);
         // Notify all
         self.publishers.iter().for_each(|p| { 
@@ -172,13 +186,25 @@ impl<T: Send + 'static> Queue<T> {
     /// [`Dequeu`]: struct.Deque.html
     pub fn deque(&mut self) -> Dequeue<T> {
         let stealer = self.inner_stealer.clone();
-        let subscriber = match self.capacity {
+        let mut subscriber = match self.capacity {
             Capacity::Unbounded => Dequeue::unbounded(stealer),
             Capacity::Bounded(cap) => Dequeue::bounded(stealer, cap)
         };
+        subscriber.depth = self.depth.clone();
         self.publishers.push(subscriber.sender());
         subscriber
     }
+
+    /// Record the number of values in the queue, and the number of values
+    /// pushed, in the global metrics [`Registry`], labelled with `queue="name"`.
+    ///
+    /// [`Registry`]: ../../metrics/struct.Registry.html
+    pub fn register_metrics(&self, name: &str) {
+        let registry = Registry::global();
+        let labels = &[("queue", name)];
+        registry.register("sonr_queue_depth", "Number of values in the queue", labels, self.depth.clone());
+        registry.register("sonr_queue_pushed_total", "Number of values pushed", labels, self.pushed.clone());
+    }
 }
 
 // -----------------------------------------------------------------------------
@@ -226,6 +252,10 @@ impl<T> Reactor for ReactiveDeque<T> {
             Reaction::Continue => self.steal(),
         }
     }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        tokens.push(self.inner.token());
+    }
 }
 
 // -----------------------------------------------------------------------------
@@ -235,6 +265,7 @@ impl<T> Reactor for ReactiveDeque<T> {
 pub struct Dequeue<T> {
     signal: SignalReceiver<()>,
     stealer: Stealer<T>,
+    depth: Gauge,
 }
 
 impl<T> Dequeue<T> {
@@ -248,6 +279,7 @@ impl<T> Dequeue<T> {
         Self { 
             signal, 
             stealer,
+            depth: Gauge::new(),
         }
     }
 
@@ -258,6 +290,7 @@ impl<T> Dequeue<T> {
         Self { 
             signal, 
             stealer,
+            depth: Gauge::new(),
         }
     }
 
@@ -273,7 +306,11 @@ impl<T> Dequeue<T> {
             Ok(()) => {},
             Err(_e) => { /* dbg!(e); */ }
         }
-        self.stealer.steal()
+        let stolen = self.stealer.steal();
+        if stolen.is_success() {
+            self.depth.dec();
+        }
+        stolen
     }
 }
 
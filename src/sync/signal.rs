@@ -6,6 +6,7 @@ use crossbeam::channel::{Sender, Receiver, TrySendError};
 use crossbeam::channel::{unbounded as channel, bounded};
 use mio::{Poll, PollOpt, Registration, SetReadiness, Ready, Evented, Token};
 
+use crate::metrics::{Gauge, Registry};
 use crate::reactor::{Reactor, Reaction, EventedReactor};
 use crate::errors;
 
@@ -21,20 +22,41 @@ use super::Capacity;
 pub struct SignalSender<T> {
     sender: Sender<T>,
     set_readiness: SetReadiness,
+    depth: Gauge,
 }
 
 impl<T> SignalSender<T> {
-    fn new(sender: Sender<T>, set_readiness: SetReadiness) -> Self {
+    fn new(sender: Sender<T>, set_readiness: SetReadiness, depth: Gauge) -> Self {
         Self {
             sender,
             set_readiness,
+            depth,
         }
     }
 
     /// Send data to a receiver.
     pub fn send(&self, val: T) -> Result<(), TrySendError<T>>{
+        // Send before setting the readiness, otherwise the receiver
+        // could react before the value is available.
+        // If the channel is full the receiver has to be woken up first,
+        // as sending blocks until the receiver makes room.
+        // The depth is incremented first, as the value can be received right away.
+        self.depth.inc();
+        match self.sender.try_send(val) {
+            Ok(()) => {}
+            Err(TrySendError::Full(val)) => {
+                let _ = self.set_readiness.set_readiness(Ready::readable());
+                if let Err(e) = self.sender.send(val) {
+                    self.depth.dec();
+                    return Err(e.into());
+                }
+            }
+            Err(e) => {
+                self.depth.dec();
+                return Err(e);
+            }
+        }
         let _ = self.set_readiness.set_readiness(Ready::readable());
-        self.sender.send(val)?;
         Ok(())
     }
 }
@@ -44,6 +66,7 @@ impl<T> Clone for SignalSender<T> {
         SignalSender::new(
             self.sender.clone(),
             self.set_readiness.clone(),
+            self.depth.clone(),
         )
     }
 }
@@ -65,6 +88,7 @@ pub struct SignalReceiver<T> {
 
     set_readiness: SetReadiness,
     sender: Sender<T>,
+    depth: Gauge,
 }
 
 impl<T> From<Capacity> for SignalReceiver<T> {
@@ -109,13 +133,15 @@ impl<T> SignalReceiver<T> {
 
             sender,
             set_readiness,
+            depth: Gauge::new(),
         }
     }
 
     /// Try to receive a value from the underlying channel
     pub fn try_recv(&self) -> errors::Result<T> {
-        let res = self.receiver.try_recv();
-        Ok(res?)
+        let val = self.receiver.try_recv()?;
+        self.depth.dec();
+        Ok(val)
     }
 
     /// Create an instance of a sender.
@@ -124,8 +150,22 @@ impl<T> SignalReceiver<T> {
         SignalSender {
             set_readiness: self.set_readiness.clone(),
             sender: self.sender.clone(),
+            depth: self.depth.clone(),
         }
     }
+
+    /// Record the number of values waiting to be received in the global
+    /// metrics [`Registry`], labelled with `signal="name"`.
+    ///
+    /// [`Registry`]: ../../metrics/struct.Registry.html
+    pub fn register_metrics(&self, name: &str) {
+        Registry::global().register(
+            "sonr_signal_depth",
+            "Number of values waiting to be received",
+            &[("signal", name)],
+            self.depth.clone(),
+        );
+    }
 }
 
 
@@ -195,6 +235,13 @@ impl<T> ReactiveSignalReceiver<T> {
     pub fn sender(&self) -> SignalSender<T> {
         self.inner.inner().sender()
     }
+
+    /// See [`SignalReceiver::register_metrics`].
+    ///
+    /// [`SignalReceiver::register_metrics`]: struct.SignalReceiver.html#method.register_metrics
+    pub fn register_metrics(&self, name: &str) {
+        self.inner.inner().register_metrics(name);
+    }
 }
 
 impl<T> Evented for SignalReceiver<T> {
@@ -238,4 +285,8 @@ impl<T: Send + 'static> Reactor for ReactiveSignalReceiver<T> {
             Reaction::Value(_) => Reaction::Continue,
         }
     }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        tokens.push(self.inner.token());
+    }
 }
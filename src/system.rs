@@ -18,12 +18,24 @@
 //! [`Reaction::Event(event)`]: ../reactor/enum.Reaction.html
 //!
 use std::cell::RefCell;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::{self, Debug, Formatter};
+use std::io;
+use std::marker::PhantomData;
+use std::mem;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread::{self, ThreadId};
+use std::time::{Duration, Instant};
 
-use mio::{Evented, Events, Poll, Token, Ready, PollOpt};
+use crossbeam::channel::RecvTimeoutError;
+use mio::{Event, Evented, Events, Poll, Token, Ready, PollOpt};
 
-use crate::PreVec;
+use crate::{Growth, PreVec};
+use crate::metrics::SystemMetrics;
+use crate::route::{claims, RouteTable, Router};
+use crate::wheel::TimerWheel;
 use crate::sync::signal::{SignalReceiver, SignalSender};
-use crate::errors::Result;
+use crate::errors::{Error, Result};
 
 use super::reactor::{Reactor, EventedReactor, Reaction};
 
@@ -39,10 +51,74 @@ thread_local! {
 /// [`System`]: struct.System.html
 /// [`System::start`]: struct.System.html#method.start
 /// [`System::init`]: struct.System.html#method.init
-#[derive(Debug)]
 pub enum SystemEvent {
     /// Stop the System
     Stop,
+
+    /// Shut the System down gracefully.
+    ///
+    /// The reactor receives a shutdown notification: a `Reaction::Event(event)` where
+    /// [`System::is_shutdown`] returns `true` for the event.
+    /// Listeners stop accepting new connections, and the System keeps running until
+    /// every stream is closed or the grace period has passed.
+    ///
+    /// [`System::is_shutdown`]: struct.System.html#method.is_shutdown
+    Shutdown {
+        /// How long in-flight streams are allowed to keep running
+        grace: Duration,
+    },
+
+    /// Run the closure on the System's thread.
+    /// See [`SystemHandle::run`].
+    ///
+    /// [`SystemHandle::run`]: struct.SystemHandle.html#method.run
+    Run(Box<dyn FnOnce() + Send>),
+}
+
+impl Debug for SystemEvent {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            SystemEvent::Stop => write!(f, "Stop"),
+            SystemEvent::Shutdown { grace } => f.debug_struct("Shutdown").field("grace", grace).finish(),
+            SystemEvent::Run(_) => write!(f, "Run"),
+        }
+    }
+}
+
+/// A snapshot of the state of a [`System`].
+///
+/// [`System`]: struct.System.html
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SystemStats {
+    /// Number of reserved tokens (evented reactors and timers)
+    pub tokens: usize,
+
+    /// Number of scheduled timers
+    pub timers: usize,
+
+    /// Number of reactors added with [`System::spawn`]
+    ///
+    /// [`System::spawn`]: struct.System.html#method.spawn
+    pub spawned: usize,
+
+    /// Number of in-flight streams
+    pub in_flight: usize,
+
+    /// `true` if the System is shutting down
+    pub shutting_down: bool,
+}
+
+/// Returned from [`System::start`] once the System stops.
+///
+/// [`System::start`]: struct.System.html#method.start
+#[derive(Debug, Default)]
+pub struct ShutdownSummary {
+    /// Number of in-flight streams that closed during the grace period
+    pub closed: usize,
+
+    /// Tokens of the in-flight streams that were still open when the System stopped,
+    /// and were dropped together with the reactor.
+    pub force_closed: Vec<Token>,
 }
 
 /// `System` is thread local and has to exist for each thread using `Reactors` that react to
@@ -57,10 +133,36 @@ pub struct System {
     reactors: PreVec<()>,
     poll: Poll,
     rx: SignalReceiver<SystemEvent>,
+    timers: TimerWheel,
+    routes: RouteTable,
+    router: usize,
+    spawned: HashMap<usize, Box<dyn Spawned>>,
+    running: HashSet<usize>,
+    next_spawn: usize,
+    in_flight: HashSet<Token>,
+    shutting_down: bool,
+    name: String,
+    metrics: SystemMetrics,
+    events_capacity: usize,
+    max_events: Option<usize>,
+    poll_opt: PollOpt,
 }
 
 static SYSTEM_TOKEN: Token = Token(0);
 
+// Never reserved, as it's outside of the range of the `PreVec`
+// (`usize::MAX` is reserved by mio).
+static SHUTDOWN_TOKEN: Token = Token(usize::MAX - 1);
+
+// Like `with_system!` but does nothing if the System was not initialised,
+// as combinators can be created (and react) without a System.
+fn try_with_system<F, T>(f: F) -> Option<T>
+where
+    F: FnOnce(&mut System) -> T,
+{
+    CURRENT_SYSTEM.with(|cell| cell.borrow_mut().as_mut().map(f))
+}
+
 macro_rules! with_system {
     ($cu:ident, $x:block) => (
         {
@@ -75,12 +177,22 @@ macro_rules! with_system {
 }
 
 impl System {
-    fn new() -> Result<Self> {
-        let capacity = 100_000;
+    fn new(builder: SystemBuilder) -> Result<Self> {
         let rx = SignalReceiver::unbounded();
         let poll = Poll::new()?;
 
-        let mut reactors = PreVec::with_capacity(capacity);
+        // Systems on unnamed threads are numbered
+        static UNNAMED: AtomicUsize = AtomicUsize::new(0);
+        let name = match builder.name.or_else(|| thread::current().name().map(String::from)) {
+            Some(name) => name,
+            None => format!("system-{}", UNNAMED.fetch_add(1, Ordering::Relaxed)),
+        };
+
+        let mut routes = RouteTable::new();
+        let router = routes.next_router();
+
+        let mut reactors = PreVec::with_capacity(builder.token_capacity.max(1));
+        reactors.set_growth(builder.token_growth);
         reactors.insert(())?; // Reserve the first token as it's the SERVER_TOKEN
 
         poll.register(
@@ -94,37 +206,107 @@ impl System {
             reactors,
             poll,
             rx,
+            timers: TimerWheel::new(),
+            routes,
+            router,
+            spawned: HashMap::new(),
+            running: HashSet::new(),
+            next_spawn: 0,
+            in_flight: HashSet::new(),
+            shutting_down: false,
+            metrics: SystemMetrics::new(&name),
+            name,
+            events_capacity: builder.events_capacity.max(1),
+            max_events: builder.max_events,
+            poll_opt: builder.poll_opt,
         })
     }
 
     /// Initialise the system for the current thread.
     /// Should only be called once per thread.
+    ///
+    /// To configure the System use a [`SystemBuilder`].
+    ///
+    /// [`SystemBuilder`]: struct.SystemBuilder.html
     pub fn init() -> Result<SignalSender<SystemEvent>> {
-        CURRENT_SYSTEM.with(|cell| {
-            let mut current = cell.borrow_mut();
-            if let Some(ref mut c) = *current {
-                return Ok(c.rx.sender());
+        SystemBuilder::new().init()
+    }
+
+    /// Create a [`SystemBuilder`].
+    ///
+    /// [`SystemBuilder`]: struct.SystemBuilder.html
+    pub fn builder() -> SystemBuilder {
+        SystemBuilder::new()
+    }
+
+    /// A [`SystemHandle`] to the System of the current thread.
+    ///
+    /// [`SystemHandle`]: struct.SystemHandle.html
+    pub fn handle() -> SystemHandle {
+        with_system!(current, {
+            SystemHandle {
+                sender: current.rx.sender(),
+                name: current.name.clone(),
+                thread: thread::current().id(),
             }
-            let system = Self::new()?;
-            let handle = system.rx.sender();
-            *current = Some(system);
-            Ok(handle)
         })
     }
 
-    /// Register an `Evented` with the System.
+    /// The name of the System on this thread, used to label the System's [`metrics`].
+    ///
+    /// This is the name set with [`SystemBuilder::name`], the name of the thread,
+    /// or `system-<n>` if the thread is unnamed.
+    ///
+    /// [`SystemBuilder::name`]: struct.SystemBuilder.html#method.name
+    ///
+    /// [`metrics`]: ../metrics/index.html
+    pub fn name() -> String {
+        with_system!(current, { current.name.clone() })
+    }
+
+    /// The current state of the System on this thread.
+    /// Use [`SystemHandle::stats`] from other threads.
+    ///
+    /// [`SystemHandle::stats`]: struct.SystemHandle.html#method.stats
+    pub fn stats() -> SystemStats {
+        with_system!(current, {
+            SystemStats {
+                // The first token is reserved by the System
+                tokens: current.reactors.len() - 1,
+                timers: current.timers.len(),
+                spawned: current.running.len(),
+                in_flight: current.in_flight.len(),
+                shutting_down: current.shutting_down,
+            }
+        })
+    }
+
+    /// Register an `Evented` with the System, using the System's default `PollOpt`.
     pub fn register(evented: &impl Evented, interest: Ready, token: Token) -> Result<()> { 
+        Self::register_with_opts(evented, interest, token, Self::poll_opt())
+    }
+
+    /// Register an `Evented` with the System.
+    pub fn register_with_opts(evented: &impl Evented, interest: Ready, token: Token, opts: PollOpt) -> Result<()> {
         with_system! (current, {
             current.poll.register(
                 evented,
                 token,
                 interest,
-                PollOpt::edge()
+                opts
             )?;
             Ok(())
         })
     }
 
+    /// The default `PollOpt` used to register evented reactors.
+    /// See [`SystemBuilder::poll_opt`].
+    ///
+    /// [`SystemBuilder::poll_opt`]: struct.SystemBuilder.html#method.poll_opt
+    pub fn poll_opt() -> PollOpt {
+        with_system!(current, { current.poll_opt })
+    }
+
     /// Reregister an evented reactor.
     pub fn reregister<T: Evented>(evented: &EventedReactor<T>) -> Result<()> {
         with_system! (current, {
@@ -132,27 +314,83 @@ impl System {
                 evented.inner(),
                 evented.token(),
                 evented.interest(),
-                PollOpt::edge()
+                evented.opts()
             )?;
             Ok(())
         })
     }
 
+    /// Deregister an evented reactor.
+    pub fn deregister<T: Evented>(evented: &EventedReactor<T>) -> Result<()> {
+        with_system! (current, {
+            current.poll.deregister(evented.inner())?;
+            Ok(())
+        })
+    }
+
     /// Start the event loop.
-    /// This will run until `SystemEvent::Stop` is sent to the system's `SignalReceiver`.
+    /// This will run until `SystemEvent::Stop` is sent to the system's `SignalReceiver`,
+    /// or until a `SystemEvent::Shutdown` has completed.
     ///
     /// The `SignalReceiver` is returned from `System::init()`.
-    pub fn start<R: Reactor>(mut reactor: R) -> Result<()> {
-        let mut events = Events::with_capacity(1024);
+    ///
+    /// The poll timeout is the time until the next timer expires (see [`timer`]),
+    /// and each expired timer is passed to the reactor as a `Reaction::Event(event)`
+    /// with the timer's token and an empty readiness.
+    ///
+    /// If the number of events per iteration is limited (see
+    /// [`SystemBuilder::max_events_per_iteration`]) the remaining events are passed
+    /// to the reactor in the next iteration, after the expired timers.
+    ///
+    /// [`timer`]: ../reactor/timer/index.html
+    /// [`SystemBuilder::max_events_per_iteration`]: struct.SystemBuilder.html#method.max_events_per_iteration
+    pub fn start<R: Reactor>(mut reactor: R) -> Result<ShutdownSummary> {
+        let (router, metrics, events_capacity, max_events) = with_system!(current, {
+            (current.router, current.metrics.clone(), current.events_capacity, current.max_events)
+        });
+        let router = Router::with_id(router);
+        router.add(0, &reactor);
+
+        let mut events = Events::with_capacity(events_capacity);
+        let mut pending: VecDeque<Event> = VecDeque::new();
+        let mut grace_deadline: Option<Instant> = None;
+        let mut in_flight_at_shutdown = 0;
 
         'system: loop {
-            with_system!(current, { current.poll.poll(&mut events, None) })?;
+            with_system!(current, {
+                let now = Instant::now();
+                let timeout = current.timers.timeout(now);
+                let timeout = match grace_deadline {
+                    Some(deadline) => {
+                        let remaining = deadline.saturating_duration_since(now);
+                        Some(timeout.map_or(remaining, |t| t.min(remaining)))
+                    }
+                    None => timeout,
+                };
+                // Don't wait for new events while there are events left from the last iteration
+                let timeout = if pending.is_empty() { timeout } else { Some(Duration::from_millis(0)) };
+
+                // The first token is reserved by the System
+                metrics.tokens.set(current.reactors.len() as i64 - 1);
+                metrics.timers.set(current.timers.len() as i64);
+                metrics.spawned.set(current.running.len() as i64);
+                metrics.in_flight.set(current.in_flight.len() as i64);
+
+                current.poll.poll(&mut events, timeout)
+            })?;
+
+            let polled = events.iter().count();
+            metrics.polls.inc();
+            metrics.events.add(polled as u64);
+            metrics.events_per_poll.observe(polled as f64);
 
-            for event in &events {
+            pending.extend(events.iter());
+            let batch = max_events.map_or(pending.len(), |max| max.min(pending.len()));
+            for event in pending.drain(..batch) {
                 if event.token() == SYSTEM_TOKEN { 
                     let sys_events = with_system!(current, {
                         let mut sys_events = Vec::new();
-                        if let Ok(sys_event) = current.rx.try_recv() {
+                        while let Ok(sys_event) = current.rx.try_recv() {
                             sys_events.push(sys_event);
                         }
                         sys_events
@@ -161,39 +399,569 @@ impl System {
                     for sys_event in sys_events {
                         match sys_event {
                             SystemEvent::Stop => break 'system,
+                            SystemEvent::Run(task) => task(),
+                            SystemEvent::Shutdown { grace } => {
+                                if grace_deadline.is_some() {
+                                    continue;
+                                }
+                                grace_deadline = Some(Instant::now() + grace);
+                                in_flight_at_shutdown = with_system!(current, {
+                                    current.shutting_down = true;
+                                    current.in_flight.len()
+                                });
+                                Self::dispatch(&router, &mut reactor, Event::new(Ready::empty(), SHUTDOWN_TOKEN));
+                            }
                         }
                     }
                 } else {
-                    let reaction = reactor.react(Reaction::Event(event));
+                    let started = Instant::now();
+                    Self::dispatch(&router, &mut reactor, event);
+                    metrics.react_seconds.observe_duration(started.elapsed());
+                }
+            }
+
+            let expired = with_system!(current, { current.timers.poll(Instant::now()) });
+            for token in expired {
+                let started = Instant::now();
+                Self::dispatch(&router, &mut reactor, Event::new(Ready::empty(), token));
+                metrics.react_seconds.observe_duration(started.elapsed());
+            }
 
-                    if let Reaction::Value(_) = reaction {
-                        while let Reaction::Value(_) = reactor.react(Reaction::Continue) { }
-                    } 
+            if let Some(deadline) = grace_deadline {
+                let done = with_system!(current, { current.in_flight.is_empty() });
+                if done || Instant::now() >= deadline {
+                    break 'system;
                 }
             }
         }
 
-        Ok(())
+        let force_closed: Vec<Token> = with_system!(current, {
+            current.shutting_down = false;
+            current.in_flight.iter().cloned().collect()
+        });
+
+        // Spawned reactors are dropped together with the reactor
+        let spawned = with_system!(current, {
+            current.running.clear();
+            mem::take(&mut current.spawned)
+        });
+        drop(spawned);
+
+        Ok(ShutdownSummary {
+            closed: in_flight_at_shutdown.saturating_sub(force_closed.len()),
+            force_closed,
+        })
     } 
 
+    // Pass the event to the reactor and the spawned reactors, or only the one
+    // that owns the event's token.
+    //
+    // A spawned reactor is taken out of the System while it reacts,
+    // as it's free to call the System (and spawn or cancel reactors).
+    fn dispatch<R: Reactor>(router: &Router, reactor: &mut R, event: Event) {
+        let route = router.branch(&event, |branch| match branch {
+            0 => claims(reactor, event.token()),
+            id => with_system!(current, {
+                current.spawned.get(&id).map(|spawned| spawned.claims(event.token())).unwrap_or(false)
+            }),
+        });
+        if matches!(route, None | Some(0)) {
+            let _entered = router.enter(0);
+            Self::react(reactor, event);
+        }
+
+        let ids: Vec<usize> = match route {
+            Some(0) => Vec::new(),
+            Some(id) => vec![id],
+            None => with_system!(current, { current.spawned.keys().cloned().collect() }),
+        };
+
+        for id in ids {
+            let spawned = with_system!(current, { current.spawned.remove(&id) });
+            if let Some(mut spawned) = spawned {
+                {
+                    let _entered = router.enter(id);
+                    spawned.react(event);
+                }
+
+                let cancelled = with_system!(current, {
+                    if current.running.contains(&id) {
+                        current.spawned.insert(id, spawned);
+                        None
+                    } else {
+                        Some(spawned)
+                    }
+                });
+                drop(cancelled);
+            }
+        }
+    }
+
+    fn react<R: Reactor>(reactor: &mut R, event: Event) {
+        let reaction = reactor.react(Reaction::Event(event));
+
+        if let Reaction::Value(_) = reaction {
+            while let Reaction::Value(_) = reactor.react(Reaction::Continue) { }
+        } 
+    }
+
+    /// Add a reactor to the System, next to the reactor passed to [`System::start`].
+    ///
+    /// The reactor reacts to events like any other reactor, and the output is discarded.
+    /// `spawn` can be called before the System is started, or from within `react`
+    /// while the System is running (e.g. to start a listener on a new port).
+    ///
+    /// The reactor runs until it's cancelled through the returned [`SpawnHandle`],
+    /// or until the System stops.
+    ///
+    ///```
+    /// # use std::time::Duration;
+    /// # use sonr::prelude::*;
+    /// # use sonr::errors::Result;
+    /// use sonr::reactor::producers::Mono;
+    /// use sonr::reactor::timer::Timeout;
+    ///
+    /// fn main() -> Result<()> {
+    ///     let system_sig = System::init()?;
+    ///
+    ///     let run = Mono::new(Duration::from_millis(10))?.map(move |duration| {
+    ///         let timeout = Timeout::new(duration).unwrap();
+    ///         let stop = system_sig.clone();
+    ///         System::spawn(timeout.map(move |_| stop.send(SystemEvent::Stop)));
+    ///     });
+    ///
+    ///     System::start(run)?;
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// [`System::start`]: struct.System.html#method.start
+    /// [`SpawnHandle`]: struct.SpawnHandle.html
+    pub fn spawn<R: Reactor + 'static>(reactor: R) -> SpawnHandle {
+        let (id, router) = with_system!(current, {
+            current.next_spawn += 1;
+            current.running.insert(current.next_spawn);
+            (current.next_spawn, current.router)
+        });
+
+        Router::with_id(router).add(id, &reactor);
+        with_system!(current, { current.spawned.insert(id, Box::new(reactor)) });
+
+        SpawnHandle {
+            id,
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Schedule a timer for the token, expiring at `deadline`.
+    /// Any existing timer for the token is replaced.
+    ///
+    /// Prefer using [`Timeout`] or [`Interval`] rather than calling this directly.
+    ///
+    /// [`Timeout`]: ../reactor/timer/struct.Timeout.html
+    /// [`Interval`]: ../reactor/timer/struct.Interval.html
+    pub fn schedule_timer(token: Token, deadline: Instant) {
+        with_system!(current, { current.timers.insert(token, deadline) })
+    }
+
+    /// Cancel the timer for the token (if any).
+    pub fn cancel_timer(token: Token) {
+        with_system!(current, { current.timers.remove(token) })
+    }
+
     /// The token can be registered with another reactor.
     /// This is called when an [`EventedReactor`] is dropped.
     ///
     /// [`EventedReactor`]: ../reactor/struct.EventedReactor.html
     pub fn free_token(token: Token) {
-        let _ = with_system!(current, { current.reactors.remove(token.0); });
+        with_system!(current, {
+            current.timers.remove(token);
+            current.in_flight.remove(&token);
+            current.routes.remove(token);
+            current.reactors.remove(token.0);
+        });
+    }
+
+    /// Track the token as in-flight.
+    /// During a graceful shutdown the System keeps running until every in-flight
+    /// token is freed (or the grace period has passed).
+    ///
+    /// Every [`Stream`] is in-flight.
+    ///
+    /// [`Stream`]: ../net/stream/struct.Stream.html
+    pub fn track_in_flight(token: Token) {
+        with_system!(current, { current.in_flight.insert(token); });
+    }
+
+    /// Returns `true` if the event is the shutdown notification
+    /// sent to the reactor when the System receives `SystemEvent::Shutdown`.
+    pub fn is_shutdown(event: &Event) -> bool {
+        event.token() == SHUTDOWN_TOKEN
+    }
+
+    /// Returns `true` if the System is shutting down.
+    pub fn is_shutting_down() -> bool {
+        with_system!(current, { current.shutting_down })
     }
 
     /// Reserve a token
     pub fn reserve_token() -> Result<Token> {
         with_system!(current, { 
-            let token = current.reactors.insert(())?;
-            Ok(Token(token))
+            let token = Token(current.reactors.insert(())?);
+            current.routes.reserve(token);
+            Ok(token)
         })
     } 
 
+    // -----------------------------------------------------------------------------
+    // 		- Routes -
+    // 		See `RouteTable`
+    // -----------------------------------------------------------------------------
+    pub(crate) fn next_router() -> usize {
+        try_with_system(|current| current.routes.next_router()).unwrap_or(0)
+    }
+
+    pub(crate) fn add_route(token: Token, router: usize, branch: usize) {
+        try_with_system(|current| current.routes.insert(token, router, branch));
+    }
+
+    pub(crate) fn route(token: Token, router: usize) -> Option<(usize, bool)> {
+        try_with_system(|current| current.routes.branch(token, router)).flatten()
+    }
+
+    pub(crate) fn verify_route(token: Token, router: usize, claimed: bool) {
+        try_with_system(|current| current.routes.verify(token, router, claimed));
+    }
+
+    pub(crate) fn enter_route(router: usize, branch: usize) {
+        try_with_system(|current| current.routes.enter(router, branch));
+    }
+
+    pub(crate) fn leave_route() {
+        try_with_system(|current| current.routes.leave());
+    }
+
     /// Send a system event to the current system.
     pub fn send(sys_event: SystemEvent) {
         let _ = with_system!(current, { current.rx.sender().send(sys_event) });
     } 
 }
+
+// -----------------------------------------------------------------------------
+// 		- System builder -
+// -----------------------------------------------------------------------------
+/// Configure the [`System`] of the current thread, e.g. to run a small daemon with
+/// a few hundred tokens, or a proxy with a million connections.
+///
+///```
+/// # use std::time::Duration;
+/// # use sonr::prelude::*;
+/// # use sonr::errors::Result;
+/// use sonr::Growth;
+/// use sonr::reactor::timer::Timeout;
+///
+/// fn main() -> Result<()> {
+///     let system_sig = System::builder()
+///         .token_capacity(64)
+///         .token_growth(Growth::Fixed)
+///         .events_capacity(16)
+///         .max_events_per_iteration(8)
+///         .name("tiny")
+///         .init()?;
+///
+///     let run = Timeout::new(Duration::from_millis(1))?.map(move |_| {
+///         system_sig.send(SystemEvent::Stop);
+///     });
+///     System::start(run)?;
+///     Ok(())
+/// }
+/// ```
+///
+/// [`System`]: struct.System.html
+#[derive(Debug, Clone)]
+pub struct SystemBuilder {
+    token_capacity: usize,
+    token_growth: Growth,
+    events_capacity: usize,
+    max_events: Option<usize>,
+    poll_opt: PollOpt,
+    name: Option<String>,
+}
+
+impl Default for SystemBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SystemBuilder {
+    /// Create a new builder with the default configuration
+    pub fn new() -> Self {
+        Self {
+            token_capacity: 100_000,
+            token_growth: Growth::Double,
+            events_capacity: 1024,
+            max_events: None,
+            poll_opt: PollOpt::edge(),
+            name: None,
+        }
+    }
+
+    /// Number of tokens allocated up front (including the token reserved by the System).
+    /// Default is 100 000.
+    pub fn token_capacity(mut self, capacity: usize) -> Self {
+        self.token_capacity = capacity;
+        self
+    }
+
+    /// How the tokens grow once the capacity is reached.
+    /// With `Growth::Fixed` reserving a token above the capacity
+    /// returns `Error::NoCapacity`.
+    /// Default is `Growth::Double`.
+    pub fn token_growth(mut self, growth: Growth) -> Self {
+        self.token_growth = growth;
+        self
+    }
+
+    /// Maximum number of events returned by each poll.
+    /// Default is 1024.
+    pub fn events_capacity(mut self, capacity: usize) -> Self {
+        self.events_capacity = capacity;
+        self
+    }
+
+    /// Maximum number of events passed to the reactor per iteration,
+    /// before the System receives [`SystemEvent`]s and fires timers again.
+    /// The remaining events are passed on in the next iteration.
+    ///
+    /// Default is no limit.
+    ///
+    /// [`SystemEvent`]: enum.SystemEvent.html
+    pub fn max_events_per_iteration(mut self, max: usize) -> Self {
+        self.max_events = Some(max.max(1));
+        self
+    }
+
+    /// The `PollOpt` evented reactors are registered with, unless registered with
+    /// [`EventedReactor::with_opts`]. Default is `PollOpt::edge()`.
+    ///
+    /// [`EventedReactor::with_opts`]: ../reactor/struct.EventedReactor.html#method.with_opts
+    pub fn poll_opt(mut self, opts: PollOpt) -> Self {
+        self.poll_opt = opts;
+        self
+    }
+
+    /// The name of the System, used to label the System's metrics.
+    /// Default is the name of the thread.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Initialise the System for the current thread.
+    ///
+    /// If the System was already initialised the configuration is ignored,
+    /// and a sender to the existing System is returned.
+    pub fn init(self) -> Result<SignalSender<SystemEvent>> {
+        CURRENT_SYSTEM.with(|cell| {
+            let mut current = cell.borrow_mut();
+            if let Some(ref mut c) = *current {
+                return Ok(c.rx.sender());
+            }
+            let system = System::new(self)?;
+            let handle = system.rx.sender();
+            *current = Some(system);
+            Ok(handle)
+        })
+    }
+}
+
+// -----------------------------------------------------------------------------
+// 		- Spawned reactors -
+// -----------------------------------------------------------------------------
+// A spawned reactor, reacting to events with its output discarded
+trait Spawned {
+    fn react(&mut self, event: Event);
+
+    fn claims(&self, token: Token) -> bool;
+}
+
+impl<R: Reactor> Spawned for R {
+    fn react(&mut self, event: Event) {
+        System::react(self, event);
+    }
+
+    fn claims(&self, token: Token) -> bool {
+        claims(self, token)
+    }
+}
+
+/// A handle to a reactor spawned with [`System::spawn`].
+///
+/// Dropping the handle does not stop the reactor,
+/// use [`cancel`] to remove the reactor from the System.
+///
+/// [`System::spawn`]: struct.System.html#method.spawn
+/// [`cancel`]: struct.SpawnHandle.html#method.cancel
+#[derive(Debug)]
+pub struct SpawnHandle {
+    id: usize,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl SpawnHandle {
+    /// Remove the reactor from the System and drop it.
+    ///
+    /// If the reactor is reacting (e.g. the reactor cancels itself)
+    /// it's dropped once it returns.
+    pub fn cancel(&self) {
+        let removed = with_system!(current, {
+            if current.running.remove(&self.id) {
+                current.spawned.remove(&self.id)
+            } else {
+                None
+            }
+        });
+        drop(removed);
+    }
+
+    /// Returns `true` until the reactor is cancelled or the System stops.
+    pub fn is_running(&self) -> bool {
+        with_system!(current, { current.running.contains(&self.id) })
+    }
+}
+
+// -----------------------------------------------------------------------------
+// 		- System handle -
+// -----------------------------------------------------------------------------
+/// A handle to a [`System`] that can be sent to, and shared between, other threads.
+///
+/// Closures passed to the handle run on the System's thread
+/// the next time the System polls for events.
+///
+///```
+/// # use std::thread;
+/// # use std::time::Duration;
+/// # use sonr::prelude::*;
+/// # use sonr::errors::Result;
+/// use sonr::reactor::timer::Timeout;
+/// use sonr::system::SystemHandle;
+///
+/// fn main() -> Result<()> {
+///     let (tx, rx) = std::sync::mpsc::channel::<SystemHandle>();
+///
+///     let worker = thread::spawn(move || -> Result<()> {
+///         System::init()?;
+///         tx.send(System::handle()).unwrap();
+///         let idle = Timeout::new(Duration::from_secs(10))?;
+///         System::start(idle)?;
+///         Ok(())
+///     });
+///
+///     let handle = rx.recv().unwrap();
+///
+///     // Spawn a reactor on the worker's System
+///     handle.spawn(|| {
+///         Timeout::new(Duration::from_millis(5)).unwrap().map(|_| {
+///             System::send(SystemEvent::Stop);
+///         })
+///     })?;
+///
+///     worker.join().unwrap()?;
+///     Ok(())
+/// }
+/// ```
+///
+/// [`System`]: struct.System.html
+#[derive(Clone)]
+pub struct SystemHandle {
+    sender: SignalSender<SystemEvent>,
+    name: String,
+    thread: ThreadId,
+}
+
+impl SystemHandle {
+    /// The name of the System.
+    /// See [`System::name`].
+    ///
+    /// [`System::name`]: struct.System.html#method.name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Send a [`SystemEvent`] to the System.
+    ///
+    /// [`SystemEvent`]: enum.SystemEvent.html
+    pub fn send(&self, sys_event: SystemEvent) -> Result<()> {
+        self.sender.send(sys_event).map_err(|_| Error::SystemGone)
+    }
+
+    /// Stop the System.
+    pub fn stop(&self) -> Result<()> {
+        self.send(SystemEvent::Stop)
+    }
+
+    /// Shut the System down gracefully.
+    /// See [`SystemEvent::Shutdown`].
+    ///
+    /// [`SystemEvent::Shutdown`]: enum.SystemEvent.html#variant.Shutdown
+    pub fn shutdown(&self, grace: Duration) -> Result<()> {
+        self.send(SystemEvent::Shutdown { grace })
+    }
+
+    /// Run the closure on the System's thread.
+    pub fn run<F>(&self, task: F) -> Result<()>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.send(SystemEvent::Run(Box::new(task)))
+    }
+
+    /// Create a reactor on the System's thread, and [`spawn`] it.
+    ///
+    /// Reactors are not `Send`, so rather than the reactor the closure
+    /// creating the reactor is sent to the System.
+    ///
+    /// [`spawn`]: struct.System.html#method.spawn
+    pub fn spawn<F, R>(&self, new_reactor: F) -> Result<()>
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Reactor + 'static,
+    {
+        self.run(move || {
+            System::spawn(new_reactor());
+        })
+    }
+
+    /// The current state of the System.
+    ///
+    /// Called from another thread this blocks until the System has handled the
+    /// request, or the timeout has passed (e.g. the System is not running), in which
+    /// case an `io::ErrorKind::TimedOut` error is returned.
+    /// Called from the System's own thread the stats are returned right away.
+    pub fn stats(&self, timeout: Duration) -> Result<SystemStats> {
+        if thread::current().id() == self.thread {
+            return Ok(System::stats());
+        }
+
+        let (tx, rx) = crossbeam::channel::bounded(1);
+        self.run(move || {
+            let _ = tx.send(System::stats());
+        })?;
+
+        match rx.recv_timeout(timeout) {
+            Ok(stats) => Ok(stats),
+            Err(RecvTimeoutError::Timeout) => Err(Error::Io(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "the System did not answer in time",
+            ))),
+            Err(RecvTimeoutError::Disconnected) => Err(Error::SystemGone),
+        }
+    }
+}
+
+impl Debug for SystemHandle {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("SystemHandle").field("name", &self.name).finish()
+    }
+}
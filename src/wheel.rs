@@ -0,0 +1,289 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use mio::Token;
+
+// Each level has 64 slots, one bit per slot in the `occupied` mask.
+const SLOT_BITS: usize = 6;
+const SLOTS: usize = 1 << SLOT_BITS;
+const LEVELS: usize = 6;
+
+// The largest number of ticks (milliseconds) a timer can be scheduled ahead.
+// Anything further away is clamped to this value.
+const MAX_TICKS: u64 = (1 << (SLOT_BITS * LEVELS)) - 1;
+
+struct Entry {
+    token: Token,
+    tick: u64,
+    id: u64,
+}
+
+struct Level {
+    slots: Vec<Vec<Entry>>,
+    occupied: u64,
+}
+
+impl Level {
+    fn new() -> Self {
+        Self {
+            slots: (0..SLOTS).map(|_| Vec::new()).collect(),
+            occupied: 0,
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// 		- TimerWheel -
+// -----------------------------------------------------------------------------
+/// A hierarchical timer wheel with a resolution of one millisecond.
+///
+/// Each token can have at most one timer. Scheduling a new timer for a token
+/// replaces the previous one.
+///
+/// Timers are placed in one of six levels, each level covering 64 times the
+/// range of the level below. As time advances, timers in the higher levels
+/// cascade down until they reach the lowest level and expire.
+pub(crate) struct TimerWheel {
+    start: Instant,
+    elapsed: u64,
+    levels: Vec<Level>,
+    active: HashMap<Token, (u64, u64)>,
+    expired: Vec<Token>,
+    next_id: u64,
+}
+
+impl TimerWheel {
+    /// Create an empty timer wheel, starting now.
+    pub(crate) fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            elapsed: 0,
+            levels: (0..LEVELS).map(|_| Level::new()).collect(),
+            active: HashMap::new(),
+            expired: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    // Round up to the nearest tick, a timer should never fire early.
+    fn deadline_tick(&self, deadline: Instant) -> u64 {
+        if deadline <= self.start {
+            return 0;
+        }
+        let since = deadline - self.start;
+        since.as_nanos().div_ceil(1_000_000) as u64
+    }
+
+    fn now_tick(&self, now: Instant) -> u64 {
+        if now <= self.start {
+            return 0;
+        }
+        (now - self.start).as_millis() as u64
+    }
+
+    /// Schedule a timer for the token.
+    /// Any existing timer for the token is replaced.
+    pub(crate) fn insert(&mut self, token: Token, deadline: Instant) {
+        let tick = self.deadline_tick(deadline);
+        let tick = tick.min(self.elapsed + MAX_TICKS);
+        let id = self.next_id;
+        self.next_id += 1;
+        self.active.insert(token, (tick, id));
+        self.place(Entry { token, tick, id });
+    }
+
+    /// Cancel the timer for the token (if any).
+    ///
+    /// The entry is left in its slot and discarded once the slot is processed.
+    pub(crate) fn remove(&mut self, token: Token) {
+        self.active.remove(&token);
+    }
+
+    /// Number of scheduled timers.
+    pub(crate) fn len(&self) -> usize {
+        self.active.len()
+    }
+
+    fn place(&mut self, entry: Entry) {
+        if entry.tick <= self.elapsed {
+            self.expire(entry);
+            return;
+        }
+
+        let level = level_for(self.elapsed, entry.tick);
+        let slot = slot_for(entry.tick, level);
+        let level = &mut self.levels[level];
+        level.occupied |= 1 << slot;
+        level.slots[slot].push(entry);
+    }
+
+    fn expire(&mut self, entry: Entry) {
+        if self.active.get(&entry.token) == Some(&(entry.tick, entry.id)) {
+            self.active.remove(&entry.token);
+            self.expired.push(entry.token);
+        }
+    }
+
+    // The level, slot and tick of the next slot to process.
+    fn next_slot(&self) -> Option<(usize, usize, u64)> {
+        for (index, level) in self.levels.iter().enumerate() {
+            if level.occupied == 0 {
+                continue;
+            }
+
+            let current = slot_for(self.elapsed, index);
+            let distance = level.occupied.rotate_right(current as u32).trailing_zeros() as usize;
+            let slot = (current + distance) % SLOTS;
+
+            let slot_range = 1u64 << (SLOT_BITS * index);
+            let level_range = slot_range << SLOT_BITS;
+            let level_start = self.elapsed & !(level_range - 1);
+            let mut tick = level_start + slot as u64 * slot_range;
+            if tick < self.elapsed {
+                tick += level_range;
+            }
+
+            return Some((index, slot, tick));
+        }
+
+        None
+    }
+
+    /// Time until the next timer needs processing.
+    /// Returns `None` if there are no timers.
+    pub(crate) fn timeout(&self, now: Instant) -> Option<Duration> {
+        if !self.expired.is_empty() {
+            return Some(Duration::from_millis(0));
+        }
+
+        self.next_slot().map(|(_, _, tick)| {
+            let now = self.now_tick(now);
+            Duration::from_millis(tick.saturating_sub(now))
+        })
+    }
+
+    /// Advance the wheel up to `now` and return the tokens of all
+    /// expired timers.
+    pub(crate) fn poll(&mut self, now: Instant) -> Vec<Token> {
+        let now = self.now_tick(now);
+
+        while let Some((level, slot, tick)) = self.next_slot() {
+            if tick > now {
+                break;
+            }
+
+            self.elapsed = tick;
+            let level = &mut self.levels[level];
+            level.occupied &= !(1 << slot);
+            let entries = std::mem::take(&mut level.slots[slot]);
+            for entry in entries {
+                self.place(entry);
+            }
+        }
+
+        if now > self.elapsed {
+            self.elapsed = now;
+        }
+
+        std::mem::take(&mut self.expired)
+    }
+}
+
+fn level_for(elapsed: u64, tick: u64) -> usize {
+    let significant = 63 - ((elapsed ^ tick) | (SLOTS as u64 - 1)).leading_zeros() as usize;
+    (significant / SLOT_BITS).min(LEVELS - 1)
+}
+
+fn slot_for(tick: u64, level: usize) -> usize {
+    ((tick >> (level * SLOT_BITS)) as usize) & (SLOTS - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn expire_in_order() {
+        let mut wheel = TimerWheel::new();
+        let start = wheel.start;
+        wheel.insert(Token(1), start + ms(10));
+        wheel.insert(Token(2), start + ms(5000));
+        wheel.insert(Token(3), start + ms(70));
+
+        assert!(wheel.poll(start + ms(9)).is_empty());
+        assert_eq!(wheel.poll(start + ms(10)), vec![Token(1)]);
+        assert!(wheel.poll(start + ms(69)).is_empty());
+        assert_eq!(wheel.poll(start + ms(70)), vec![Token(3)]);
+        assert!(wheel.poll(start + ms(4999)).is_empty());
+        assert_eq!(wheel.poll(start + ms(5000)), vec![Token(2)]);
+        assert!(wheel.active.is_empty());
+    }
+
+    #[test]
+    fn expire_far_future() {
+        let mut wheel = TimerWheel::new();
+        let start = wheel.start;
+        wheel.insert(Token(1), start + ms(3_600_000));
+        assert!(wheel.poll(start + ms(3_599_999)).is_empty());
+        assert_eq!(wheel.poll(start + ms(3_600_000)), vec![Token(1)]);
+    }
+
+    #[test]
+    fn expire_past_deadline() {
+        let mut wheel = TimerWheel::new();
+        let start = wheel.start;
+        wheel.poll(start + ms(100));
+        wheel.insert(Token(1), start + ms(50));
+        assert_eq!(wheel.timeout(start + ms(100)), Some(ms(0)));
+        assert_eq!(wheel.poll(start + ms(100)), vec![Token(1)]);
+    }
+
+    #[test]
+    fn remove_timer() {
+        let mut wheel = TimerWheel::new();
+        let start = wheel.start;
+        wheel.insert(Token(1), start + ms(10));
+        wheel.remove(Token(1));
+        assert!(wheel.poll(start + ms(20)).is_empty());
+    }
+
+    #[test]
+    fn replace_timer() {
+        let mut wheel = TimerWheel::new();
+        let start = wheel.start;
+        wheel.insert(Token(1), start + ms(10));
+        wheel.insert(Token(1), start + ms(30));
+        assert!(wheel.poll(start + ms(20)).is_empty());
+        assert_eq!(wheel.poll(start + ms(30)), vec![Token(1)]);
+    }
+
+    #[test]
+    fn timeout_until_next_slot() {
+        let mut wheel = TimerWheel::new();
+        let start = wheel.start;
+        assert_eq!(wheel.timeout(start), None);
+        wheel.insert(Token(1), start + ms(40));
+        assert_eq!(wheel.timeout(start + ms(15)), Some(ms(25)));
+    }
+
+    #[test]
+    fn timeout_never_overshoots() {
+        let mut wheel = TimerWheel::new();
+        let start = wheel.start;
+        wheel.insert(Token(1), start + ms(1_000));
+
+        // Follow the timeouts as the system would
+        let mut now = start;
+        let mut expired = Vec::new();
+        while expired.is_empty() {
+            now += wheel.timeout(now).unwrap();
+            assert!(now <= start + ms(1_000));
+            expired = wheel.poll(now);
+        }
+        assert_eq!(now, start + ms(1_000));
+    }
+}
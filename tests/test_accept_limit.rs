@@ -0,0 +1,203 @@
+use std::cell::Cell;
+use std::net::{SocketAddr, TcpStream as StdStream};
+use std::rc::Rc;
+use std::thread;
+use std::time::Duration;
+
+use sonr::errors::Result;
+use sonr::net::permit::{Permit, Permits};
+use sonr::net::tcp::{ReactiveTcpListener, ReactiveTcpStream, TcpStream};
+use sonr::prelude::*;
+use sonr::reactor::timer::Timeout;
+use sonr::sync::signal::SignalSender;
+
+// -----------------------------------------------------------------------------
+// 		- Holder -
+// 		Holds on to the first connection until the timeout,
+// 		and records the number of connections accepted before
+// 		the first one was dropped. Stops the system once the
+// 		second connection is accepted.
+// -----------------------------------------------------------------------------
+struct Holder {
+    held: Option<ReactiveTcpStream>,
+    accepted: usize,
+    accepted_while_held: Rc<Cell<Option<usize>>>,
+    timeout: Timeout,
+    system_sig: SignalSender<SystemEvent>,
+}
+
+impl Reactor for Holder {
+    type Input = (TcpStream, SocketAddr, Permit);
+    type Output = ();
+
+    fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
+        match reaction {
+            Reaction::Value((stream, _, permit)) => {
+                self.accepted += 1;
+                if self.accepted == 1 {
+                    let mut stream = ReactiveTcpStream::new(stream).unwrap();
+                    stream.set_permit(permit);
+                    self.held = Some(stream);
+                    return Reaction::Continue;
+                }
+                self.system_sig.send(SystemEvent::Stop);
+                Reaction::Continue
+            }
+            Reaction::Event(event) => {
+                if let Reaction::Value(()) = self.timeout.react(Reaction::Event(event)) {
+                    self.accepted_while_held.set(Some(self.accepted));
+                    self.held = None;
+                }
+                Reaction::Continue
+            }
+            Reaction::Continue => Reaction::Continue,
+        }
+    }
+}
+
+#[test]
+fn test_connection_limit() -> Result<()> {
+    let system_sig = System::init()?;
+
+    let listener = ReactiveTcpListener::bind("127.0.0.1:5586")?.limit(Permits::new(1))?;
+
+    thread::spawn(|| {
+        let _first = StdStream::connect("127.0.0.1:5586");
+        let _second = StdStream::connect("127.0.0.1:5586");
+        thread::sleep(Duration::from_millis(500));
+    });
+
+    let accepted_while_held = Rc::new(Cell::new(None));
+    let holder = Holder {
+        held: None,
+        accepted: 0,
+        accepted_while_held: accepted_while_held.clone(),
+        timeout: Timeout::new(Duration::from_millis(100))?,
+        system_sig,
+    };
+
+    System::start(listener.chain(holder))?;
+
+    // The second connection was only accepted once the first one was dropped
+    assert_eq!(accepted_while_held.get(), Some(1));
+    Ok(())
+}
+
+#[test]
+fn test_permit_dropped_on_another_thread() -> Result<()> {
+    let system_sig = System::init()?;
+
+    let listener = ReactiveTcpListener::bind("127.0.0.1:5620")?.limit(Permits::new(1))?;
+    let permits = listener.permits().clone();
+
+    thread::spawn(|| {
+        let _first = StdStream::connect("127.0.0.1:5620");
+        let _second = StdStream::connect("127.0.0.1:5620");
+        thread::sleep(Duration::from_millis(500));
+    });
+
+    // The permits are released by a worker thread
+    let (tx, rx) = std::sync::mpsc::channel::<(TcpStream, Permit)>();
+    let worker = thread::spawn(move || {
+        for (_stream, _permit) in rx {
+            thread::sleep(Duration::from_millis(50));
+        }
+    });
+
+    let mut accepted = 0;
+    let run = listener.map(|(stream, _, permit)| {
+        accepted += 1;
+        let _ = tx.send((stream, permit));
+        if accepted == 2 {
+            let _ = system_sig.send(SystemEvent::Stop);
+        }
+    });
+
+    System::start(run)?;
+    assert_eq!(accepted, 2);
+    drop(tx);
+    worker.join().unwrap();
+    assert_eq!(permits.available(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_max_accepts_per_iteration() -> Result<()> {
+    let system_sig = System::init()?;
+
+    let mut listener = ReactiveTcpListener::bind("127.0.0.1:5587")?;
+    listener.set_max_accepts_per_iteration(Some(1));
+
+    thread::spawn(|| {
+        let _streams = (0..3)
+            .map(|_| StdStream::connect("127.0.0.1:5587"))
+            .collect::<Vec<_>>();
+        thread::sleep(Duration::from_millis(500));
+    });
+
+    let mut accepted = 0;
+    let run = listener.map(|_| {
+        accepted += 1;
+        if accepted == 3 {
+            system_sig.send(SystemEvent::Stop);
+        }
+    });
+
+    System::start(run)?;
+    assert_eq!(accepted, 3);
+    Ok(())
+}
+
+#[test]
+fn test_listeners_sharing_permits() -> Result<()> {
+    let system_sig = System::init()?;
+
+    let permits = Permits::new(1);
+    let first = ReactiveTcpListener::bind("127.0.0.1:5622")?.limit(permits.clone())?;
+    let second = ReactiveTcpListener::bind("127.0.0.1:5623")?.limit(permits.clone())?;
+
+    // Both listeners have a connection waiting while the first permit is held
+    thread::spawn(|| {
+        let _first = StdStream::connect("127.0.0.1:5622");
+        thread::sleep(Duration::from_millis(20));
+        let _second = StdStream::connect("127.0.0.1:5623");
+        let _third = StdStream::connect("127.0.0.1:5622");
+        thread::sleep(Duration::from_secs(1));
+    });
+
+    // The permits are released by a worker thread
+    let (tx, rx) = std::sync::mpsc::channel::<(TcpStream, Permit)>();
+    let worker = thread::spawn(move || {
+        for (_stream, _permit) in rx {
+            thread::sleep(Duration::from_millis(100));
+        }
+    });
+
+    let stop = system_sig.clone();
+    thread::spawn(move || {
+        thread::sleep(Duration::from_secs(2));
+        let _ = stop.send(SystemEvent::Stop);
+    });
+
+    let accepted = Rc::new(Cell::new(0));
+    let accept = |tx: std::sync::mpsc::Sender<_>, accepted: Rc<Cell<usize>>, system_sig: SignalSender<_>| {
+        move |(stream, _, permit): (TcpStream, SocketAddr, Permit)| {
+            accepted.set(accepted.get() + 1);
+            let _ = tx.send((stream, permit));
+            if accepted.get() == 3 {
+                let _ = system_sig.send(SystemEvent::Stop);
+            }
+        }
+    };
+    let run = first
+        .map(accept(tx.clone(), accepted.clone(), system_sig.clone()))
+        .and(second.map(accept(tx, accepted.clone(), system_sig)));
+
+    System::start(run)?;
+    worker.join().unwrap();
+
+    // Every returned permit wakes both listeners
+    assert_eq!(accepted.get(), 3);
+    assert_eq!(permits.available(), 1);
+    Ok(())
+}
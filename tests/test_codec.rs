@@ -0,0 +1,139 @@
+use std::cell::Cell;
+use std::io::Write;
+use std::net::TcpListener as StdListener;
+use std::rc::Rc;
+use std::thread;
+
+use sonr::errors::Result;
+use sonr::net::codec::{Framed, LengthDelimited, LengthField, LineCodec};
+use sonr::net::tcp::{ReactiveTcpStream, TcpStream};
+use sonr::prelude::*;
+
+#[test]
+fn test_line_codec() -> Result<()> {
+    let system_sig = System::init()?;
+
+    let listener = StdListener::bind("127.0.0.1:5571")?;
+    let handle = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        stream.write_all(b"hello\r\nwor").unwrap();
+        stream.flush().unwrap();
+        thread::sleep_ms(20);
+        stream.write_all(b"ld\n").unwrap();
+    });
+
+    let stream = TcpStream::connect(&"127.0.0.1:5571".parse()?)?;
+    let framed = Framed::new(ReactiveTcpStream::new(stream)?, LineCodec::new());
+
+    let mut lines = Vec::new();
+    let run = framed.map(|line| {
+        lines.push(line);
+        if lines.len() == 2 {
+            system_sig.send(SystemEvent::Stop);
+        }
+    });
+
+    System::start(run)?;
+    handle.join().unwrap();
+
+    assert_eq!(lines, vec!["hello".to_string(), "world".to_string()]);
+    Ok(())
+}
+
+#[test]
+fn test_length_delimited_echo() -> Result<()> {
+    let system_sig = System::init()?;
+
+    // Echo everything back
+    let listener = StdListener::bind("127.0.0.1:5572")?;
+    let handle = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut reader = stream.try_clone().unwrap();
+        std::io::copy(&mut reader, &mut stream).unwrap();
+    });
+
+    let stream = TcpStream::connect(&"127.0.0.1:5572".parse()?)?;
+    let mut framed = Framed::new(
+        ReactiveTcpStream::new(stream)?,
+        LengthDelimited::new(LengthField::U32Be),
+    );
+    framed.send(b"ping".to_vec())?;
+
+    let mut frame = None;
+    let run = framed.map(|f| {
+        frame = Some(f);
+        system_sig.send(SystemEvent::Stop);
+    });
+
+    System::start(run)?;
+    drop(handle);
+
+    assert_eq!(frame, Some(b"ping".to_vec()));
+    Ok(())
+}
+
+// Record the most bytes the `Framed` held without decoding them
+struct Watch {
+    framed: Framed<ReactiveTcpStream, LineCodec>,
+    max_pending: Rc<Cell<usize>>,
+}
+
+impl Reactor for Watch {
+    type Input = ();
+    type Output = String;
+
+    fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
+        let reaction = match reaction {
+            Reaction::Event(event) => self.framed.react(Reaction::Event(event)),
+            Reaction::Continue => self.framed.react(Reaction::Continue),
+            Reaction::Value(()) => Reaction::Continue,
+        };
+        let pending = self.framed.pending_reads().max(self.max_pending.get());
+        self.max_pending.set(pending);
+        reaction
+    }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        self.framed.tokens(tokens);
+    }
+}
+
+#[test]
+fn test_read_buffer_is_bounded() -> Result<()> {
+    let system_sig = System::init()?;
+
+    // Write a megabyte of lines at once
+    let listener = StdListener::bind("127.0.0.1:5577")?;
+    let handle = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let line = [b'x'; 99];
+        let mut data = Vec::new();
+        for _ in 0..10_000 {
+            data.extend_from_slice(&line);
+            data.push(b'\n');
+        }
+        stream.write_all(&data).unwrap();
+    });
+
+    let stream = TcpStream::connect(&"127.0.0.1:5577".parse()?)?;
+    let max_pending = Rc::new(Cell::new(0));
+    let watch = Watch {
+        framed: Framed::new(ReactiveTcpStream::new(stream)?, LineCodec::with_max_length(99)),
+        max_pending: max_pending.clone(),
+    };
+
+    let mut lines = 0;
+    let run = watch.map(|_| {
+        lines += 1;
+        if lines == 10_000 {
+            system_sig.send(SystemEvent::Stop);
+        }
+    });
+
+    System::start(run)?;
+    handle.join().unwrap();
+
+    // No more than a line and a read
+    assert!(max_pending.get() <= 100 + 4096);
+    Ok(())
+}
@@ -0,0 +1,161 @@
+use sonr::errors::Result;
+use sonr::prelude::*;
+use sonr::reactor::consumers::Consume;
+use sonr::reactor::producers::{Mono, ReactiveGenerator};
+use sonr::Ready;
+
+#[test]
+fn test_filter() -> Result<()> {
+    let system_sig = System::init()?;
+
+    let mut values = Vec::new();
+    let run = ReactiveGenerator::new(vec![1u32, 2, 3, 4, 5])?
+        .filter(|n| n % 2 == 1)
+        .map(|n| {
+            values.push(n);
+            if n == 5 {
+                system_sig.send(SystemEvent::Stop);
+            }
+        });
+
+    System::start(run)?;
+
+    assert_eq!(values, vec![1, 3, 5]);
+    Ok(())
+}
+
+#[test]
+fn test_filter_map() -> Result<()> {
+    let system_sig = System::init()?;
+
+    let mut values = Vec::new();
+    let run = ReactiveGenerator::new(vec!["1", "x", "3"])?
+        .filter_map(|s: &str| s.parse::<u32>().ok())
+        .map(|n| {
+            values.push(n);
+            if n == 3 {
+                system_sig.send(SystemEvent::Stop);
+            }
+        });
+
+    System::start(run)?;
+
+    assert_eq!(values, vec![1, 3]);
+    Ok(())
+}
+
+#[test]
+fn test_flat_map() -> Result<()> {
+    let system_sig = System::init()?;
+
+    let mut values = Vec::new();
+    let run = ReactiveGenerator::new(vec![2u32, 0, 3])?
+        .flat_map(|n| 0..n)
+        .map(|n| {
+            values.push(n);
+            if values.len() == 5 {
+                system_sig.send(SystemEvent::Stop);
+            }
+        });
+
+    System::start(run)?;
+
+    assert_eq!(values, vec![0, 1, 0, 1, 2]);
+    Ok(())
+}
+
+#[test]
+fn test_flat_map_in_chain() -> Result<()> {
+    let system_sig = System::init()?;
+
+    // The flat map receives its values from the chain
+    // rather than from its own source
+    let mut values = Vec::new();
+    let words = Consume::new()
+        .flat_map(|s: &str| s.split(' ').map(String::from).collect::<Vec<_>>())
+        .map(|word| {
+            values.push(word);
+            if values.len() == 3 {
+                system_sig.send(SystemEvent::Stop);
+            }
+        });
+
+    System::start(Mono::new("a b c")?.chain(words))?;
+
+    assert_eq!(values, vec!["a", "b", "c"]);
+    Ok(())
+}
+
+#[test]
+fn test_inspect_take_skip() -> Result<()> {
+    let system_sig = System::init()?;
+
+    let mut inspected = Vec::new();
+    let mut values = Vec::new();
+    let run = ReactiveGenerator::new(vec![1u32, 2, 3, 4, 5])?
+        .inspect(|n| inspected.push(*n))
+        .skip(1)
+        .take(2)
+        .map(|n| {
+            values.push(n);
+            if n == 3 {
+                system_sig.send(SystemEvent::Stop);
+            }
+        });
+
+    System::start(run)?;
+
+    assert_eq!(values, vec![2, 3]);
+    // Values beyond `take` are still drained from the source
+    assert_eq!(inspected, vec![1, 2, 3, 4, 5]);
+    Ok(())
+}
+
+// Returns a value on every `Reaction::Continue` until it runs out,
+// and ignores its input
+struct Drain(Vec<u32>);
+
+impl Reactor for Drain {
+    type Input = u32;
+    type Output = u32;
+
+    fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
+        match reaction {
+            Reaction::Continue if !self.0.is_empty() => Reaction::Value(self.0.remove(0)),
+            Reaction::Event(event) => event.into(),
+            _ => Reaction::Continue,
+        }
+    }
+}
+
+#[test]
+fn test_chain_passes_on_values_while_draining() {
+    let mut chain = Drain(Vec::new()).chain(Drain(vec![1, 2]));
+
+    let mut values = Vec::new();
+    while let Reaction::Value(val) = chain.react(Reaction::Continue) {
+        values.push(val);
+    }
+
+    assert_eq!(values, vec![1, 2]);
+}
+
+#[test]
+fn test_and_drains_both_reactors() -> Result<()> {
+    let _system_sig = System::init()?;
+
+    let mut first = Vec::new();
+    let mut second = Vec::new();
+    let mut and = Drain(vec![1, 2])
+        .map(|val| first.push(val))
+        .and(Drain(vec![3]).map(|val| second.push(val)));
+
+    let event = Event::new(Ready::readable(), Token(1000));
+    let reaction = and.react(Reaction::Event(event));
+    assert!(matches!(reaction, Reaction::Event(_)));
+    drop(and);
+
+    assert_eq!(first, vec![1, 2]);
+    assert_eq!(second, vec![3]);
+    Ok(())
+}
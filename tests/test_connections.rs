@@ -0,0 +1,60 @@
+use std::io::{Read, Write};
+use std::io::ErrorKind::WouldBlock;
+use std::net::TcpStream as StdStream;
+use std::thread;
+
+use sonr::errors::Result;
+use sonr::net::connections::{Connections, Handled};
+use sonr::net::tcp::{ReactiveTcpListener, ReactiveTcpStream};
+use sonr::prelude::*;
+
+// Read everything from the stream, outputting one byte at a time
+fn read_bytes(stream: &mut ReactiveTcpStream) -> Handled<u8> {
+    let mut buf = [0u8; 1];
+    if !stream.readable() {
+        return Handled::Continue;
+    }
+    match stream.read(&mut buf) {
+        Ok(0) => Handled::Close,
+        Ok(_) => Handled::Value(buf[0]),
+        Err(ref e) if e.kind() == WouldBlock => Handled::Continue,
+        Err(_) => Handled::Close,
+    }
+}
+
+#[test]
+fn test_connections() -> Result<()> {
+    let system_sig = System::init()?;
+
+    let listener = ReactiveTcpListener::bind("127.0.0.1:5573")?
+        .map(|(stream, _)| ReactiveTcpStream::new(stream).unwrap());
+
+    let clients = thread::spawn(|| {
+        let mut first = StdStream::connect("127.0.0.1:5573").unwrap();
+        let mut second = StdStream::connect("127.0.0.1:5573").unwrap();
+        first.write_all(b"ab").unwrap();
+        second.write_all(b"c").unwrap();
+        thread::sleep_ms(50);
+    });
+
+    let mut received = Vec::new();
+    let mut tokens = Vec::new();
+    let connections = Connections::new(|_, stream: &mut ReactiveTcpStream| read_bytes(stream))
+        .map(|(token, byte)| {
+            received.push(byte);
+            if !tokens.contains(&token) {
+                tokens.push(token);
+            }
+            if received.len() == 3 {
+                system_sig.send(SystemEvent::Stop);
+            }
+        });
+
+    System::start(listener.chain(connections))?;
+    clients.join().unwrap();
+
+    received.sort();
+    assert_eq!(received, b"abc".to_vec());
+    assert_eq!(tokens.len(), 2);
+    Ok(())
+}
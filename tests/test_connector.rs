@@ -0,0 +1,105 @@
+use std::cell::RefCell;
+use std::net::{SocketAddr, TcpListener as StdListener, TcpStream as StdStream};
+use std::rc::Rc;
+use std::time::Duration;
+
+use net2::TcpBuilder;
+use sonr::errors::Result;
+use sonr::net::connect::{Connect, ConnectError, TcpConnector};
+use sonr::prelude::*;
+use sonr::reactor::producers::Mono;
+
+type R = Result<()>;
+
+// Connect to the addresses, and return the outcome
+fn connect(connector: TcpConnector, addrs: Vec<SocketAddr>) -> Result<Connect> {
+    let system_sig = System::init()?;
+    let outcome = Rc::new(RefCell::new(None));
+    let result = outcome.clone();
+
+    let run = Mono::new(addrs)?.chain(connector).map(move |connect: Connect| {
+        *result.borrow_mut() = Some(connect);
+        let _ = system_sig.send(SystemEvent::Stop);
+    });
+
+    System::start(run)?;
+    let outcome = outcome.borrow_mut().take().expect("no outcome");
+    Ok(outcome)
+}
+
+#[test]
+fn test_connect() -> R {
+    let listener = StdListener::bind("127.0.0.1:5592")?;
+    let addr = listener.local_addr()?;
+
+    let stream = connect(TcpConnector::new(), vec![addr])?.expect("connected");
+    assert_eq!(stream.inner().peer_addr()?, addr);
+    assert!(stream.writable());
+    Ok(())
+}
+
+#[test]
+fn test_connection_refused() -> R {
+    // Nothing is listening on the port
+    let addr: SocketAddr = "127.0.0.1:5593".parse()?;
+
+    match connect(TcpConnector::new(), vec![addr])? {
+        Err(ConnectError::Failed(errors)) => {
+            assert_eq!(errors.len(), 1);
+            assert_eq!(errors[0].0, addr);
+            assert_eq!(errors[0].1.kind(), std::io::ErrorKind::ConnectionRefused);
+        }
+        res => panic!("expected Failed, got {:?}", res.map(|_| ())),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_next_address_after_failure() -> R {
+    let listener = StdListener::bind("127.0.0.1:5594")?;
+    let addr = listener.local_addr()?;
+    let refused: SocketAddr = "127.0.0.1:5595".parse()?;
+
+    let connector = TcpConnector::new().attempt_delay(Duration::from_secs(5));
+    let stream = connect(connector, vec![refused, addr])?.expect("connected");
+    assert_eq!(stream.inner().peer_addr()?, addr);
+    Ok(())
+}
+
+#[test]
+fn test_timeouts_that_never_expire() -> R {
+    let listener = StdListener::bind("127.0.0.1:5624")?;
+    let addr = listener.local_addr()?;
+    let refused: SocketAddr = "127.0.0.1:5625".parse()?;
+
+    let connector = TcpConnector::new().timeout(Duration::MAX).attempt_delay(Duration::MAX);
+    let stream = connect(connector, vec![refused, addr])?.expect("connected");
+    assert_eq!(stream.inner().peer_addr()?, addr);
+    Ok(())
+}
+
+#[test]
+fn test_no_addresses() -> R {
+    match connect(TcpConnector::new(), vec![])? {
+        Err(ConnectError::NoAddresses) => Ok(()),
+        res => panic!("expected NoAddresses, got {:?}", res.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_connect_timeout() -> R {
+    // Fill the accept queue of a listener that never accepts,
+    // so further connections are never established.
+    let listener = TcpBuilder::new_v4()?.bind("127.0.0.1:5596")?.listen(0)?;
+    let addr = listener.local_addr()?;
+    let _queued: Vec<_> = (0..4)
+        .filter_map(|_| StdStream::connect_timeout(&addr, Duration::from_millis(50)).ok())
+        .collect();
+
+    let connector = TcpConnector::new().timeout(Duration::from_millis(50));
+    match connect(connector, vec![addr])? {
+        Err(ConnectError::TimedOut(errors)) => assert!(errors.is_empty()),
+        res => panic!("expected TimedOut, got {:?}", res.map(|_| ())),
+    }
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use std::time::Duration;
+
+use sonr::errors::Result;
+use sonr::prelude::*;
+use sonr::reactor::consumers::Consume;
+use sonr::reactor::producers::{Mono, ReactiveGenerator};
+use sonr::reactor::timer::Timeout;
+use sonr::reactor::{all, any, select, Either, Either4};
+
+#[test]
+fn test_all_preserves_output() -> Result<()> {
+    let system_sig = System::init()?;
+
+    let mut numbers = Vec::new();
+    let mut words = Vec::new();
+    let mut timed_out = false;
+    let run = all((
+        ReactiveGenerator::new(vec![1u32, 2])?,
+        ReactiveGenerator::new(vec!["a", "b"])?,
+        Mono::new(3u32)?,
+        Timeout::new(Duration::from_millis(20))?,
+    ))
+    .map(|val| match val {
+        Either4::A(n) | Either4::C(n) => numbers.push(n),
+        Either4::B(s) => words.push(s),
+        Either4::D(()) => {
+            timed_out = true;
+            system_sig.send(SystemEvent::Stop);
+        }
+    });
+
+    System::start(run)?;
+
+    numbers.sort();
+    assert_eq!(numbers, vec![1, 2, 3]);
+    assert_eq!(words, vec!["a", "b"]);
+    assert!(timed_out);
+    Ok(())
+}
+
+#[test]
+fn test_all_passes_events_on() -> Result<()> {
+    let system_sig = System::init()?;
+
+    // The timeout further down the chain still receives its event
+    let timeout = Timeout::new(Duration::from_millis(10))?;
+    let mut outputs = 0;
+    let run = all((Mono::new(1u8)?, Mono::new(2u8)?))
+        .map(|_| outputs += 1)
+        .chain(Consume::<()>::new().and(timeout.map(|_| system_sig.send(SystemEvent::Stop))));
+
+    System::start(run)?;
+    assert_eq!(outputs, 2);
+    Ok(())
+}
+
+#[test]
+fn test_select() -> Result<()> {
+    let system_sig = System::init()?;
+
+    let mut values = Vec::new();
+    let run = select((
+        ReactiveGenerator::new(vec![1u32, 2, 3])?,
+        Mono::new(4u32)?.map(|n| n * 10),
+    ))
+    .map(|n| {
+        values.push(n);
+        if values.len() == 4 {
+            system_sig.send(SystemEvent::Stop);
+        }
+    });
+
+    System::start(run)?;
+
+    values.sort();
+    assert_eq!(values, vec![1, 2, 3, 40]);
+    Ok(())
+}
+
+#[test]
+fn test_any_routes_values() -> Result<()> {
+    let system_sig = System::init()?;
+
+    let producer = ReactiveGenerator::new(vec![1u32, 2, 3, 4])?.map(|n| {
+        if n % 2 == 0 {
+            Either::A(n)
+        } else {
+            Either::B(n)
+        }
+    });
+
+    let mut values = Vec::new();
+    let consumers = any((
+        Consume::new().map(|n: u32| format!("even {}", n)),
+        Consume::new().map(|n: u32| format!("odd {}", n)),
+    ))
+    .map(|s| {
+        values.push(s);
+        if values.len() == 4 {
+            system_sig.send(SystemEvent::Stop);
+        }
+    });
+
+    System::start(producer.chain(consumers))?;
+
+    assert_eq!(values, vec!["odd 1", "even 2", "odd 3", "even 4"]);
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use std::fs::{remove_file, File};
+use std::io::ErrorKind;
+use std::net::TcpListener as StdTcpListener;
+use std::os::unix::io::{AsRawFd, OwnedFd};
+use std::thread;
+
+use sonr::errors::{Error, Result};
+use sonr::net::fd::{into_tcp_listener, ReactiveFdReceiver, MAX_FDS};
+use sonr::net::uds::{ReactiveUdsListener, ReactiveUdsStream, UnixStream};
+use sonr::prelude::*;
+use sonr::reactor::TakeError;
+use sonr::Ready;
+
+#[test]
+fn test_fd_passing() -> Result<()> {
+    let system_sig = System::init()?;
+
+    let _ = remove_file("/tmp/sonr-test-fd-passing.sock");
+    let listener = ReactiveUdsListener::bind("/tmp/sonr-test-fd-passing.sock")?;
+
+    let handle = thread::spawn(|| -> Result<()> {
+        System::init()?;
+        let tcp_listener = StdTcpListener::bind("127.0.0.1:5583")?;
+        let mut stream = ReactiveUdsStream::connect("/tmp/sonr-test-fd-passing.sock")?;
+        stream.send_with_fds(b"listener", &[tcp_listener.as_raw_fd()])?;
+        Ok(())
+    });
+
+    let mut received = None;
+    let run = listener
+        .map(|(stream, _)| ReactiveUdsStream::new(stream).unwrap())
+        .chain(ReactiveFdReceiver::new())
+        .map(|(data, mut fds): (Vec<u8>, Vec<OwnedFd>)| {
+            let listener = into_tcp_listener(fds.remove(0)).unwrap();
+            received = Some((data, listener.local_addr().unwrap()));
+            system_sig.send(SystemEvent::Stop);
+        });
+
+    System::start(run)?;
+    handle.join().unwrap()?;
+
+    let (data, addr) = received.unwrap();
+    assert_eq!(data, b"listener".to_vec());
+    assert_eq!(addr, "127.0.0.1:5583".parse()?);
+    Ok(())
+}
+
+#[test]
+fn test_too_many_fds() -> Result<()> {
+    System::init()?;
+
+    let (left, right) = UnixStream::pair()?;
+    let mut sender = ReactiveUdsStream::new(left)?;
+    let mut receiver = ReactiveUdsStream::new(right)?;
+
+    let file = File::open("/dev/null")?;
+    sender.send_with_fds(b"fds", &vec![file.as_raw_fd(); MAX_FDS + 1])?;
+
+    let mut buf = [0u8; 8];
+    let mut fds = Vec::new();
+    let err = receiver.recv_with_fds(&mut buf, &mut fds).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert!(fds.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_fd_receiver_error() -> Result<()> {
+    System::init()?;
+
+    let (left, right) = UnixStream::pair()?;
+    let mut sender = ReactiveUdsStream::new(left)?;
+    let receiver = ReactiveUdsStream::new(right)?;
+    let token = receiver.token();
+
+    let file = File::open("/dev/null")?;
+    sender.send_with_fds(b"fds", &vec![file.as_raw_fd(); MAX_FDS + 1])?;
+
+    let mut fd_receiver = ReactiveFdReceiver::new();
+    fd_receiver.react(Reaction::Value(receiver));
+    let reaction = fd_receiver.react(Reaction::Event(Event::new(Ready::readable(), token)));
+    assert!(matches!(reaction, Reaction::Continue));
+
+    // The stream is dropped, and the reason is kept
+    match fd_receiver.take_error() {
+        Some(Error::Io(e)) => assert_eq!(e.kind(), ErrorKind::InvalidData),
+        e => panic!("expected an io error, got {:?}", e),
+    }
+    let mut tokens = Vec::new();
+    fd_receiver.tokens(&mut tokens);
+    assert!(tokens.is_empty());
+    Ok(())
+}
@@ -0,0 +1,273 @@
+#![cfg(feature = "http")]
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream as StdStream;
+use std::thread;
+use std::time::Duration;
+
+use sonr::errors::Result;
+use sonr::net::connections::{Connections, Handled};
+use sonr::net::http::{HttpConnection, Response};
+use sonr::net::tcp::{ReactiveTcpListener, ReactiveTcpStream};
+use sonr::prelude::*;
+
+type R = Result<()>;
+
+const CHUNK: usize = 16 * 1024;
+
+// A response read by the blocking client
+struct ClientResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl ClientResponse {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+fn read_line(reader: &mut impl BufRead) -> String {
+    let mut line = String::new();
+    reader.read_line(&mut line).unwrap();
+    line.trim_end().to_string()
+}
+
+// Read a response with either a content length or a chunked body
+fn read_response(reader: &mut impl BufRead) -> ClientResponse {
+    let status_line = read_line(reader);
+    let status = status_line.split(' ').nth(1).unwrap().parse().unwrap();
+
+    let mut headers = Vec::new();
+    loop {
+        let line = read_line(reader);
+        if line.is_empty() {
+            break;
+        }
+        let pos = line.find(':').unwrap();
+        headers.push((line[..pos].to_string(), line[pos + 1..].trim().to_string()));
+    }
+
+    let mut response = ClientResponse { status, headers, body: Vec::new() };
+    if response.header("transfer-encoding") == Some("chunked") {
+        loop {
+            let size = usize::from_str_radix(&read_line(reader), 16).unwrap();
+            let mut chunk = vec![0u8; size + 2];
+            reader.read_exact(&mut chunk).unwrap();
+            if size == 0 {
+                break;
+            }
+            response.body.extend_from_slice(&chunk[..size]);
+        }
+    } else if let Some(len) = response.header("content-length") {
+        response.body = vec![0u8; len.parse().unwrap()];
+        reader.read_exact(&mut response.body).unwrap();
+    }
+    response
+}
+
+// Answer `/stream?n` with `n` chunks of `CHUNK` bytes (written as the stream
+// catches up), and everything else with the method, path and body of the request.
+fn server(port: u16) -> Result<impl Reactor<Input = (), Output = (Token, ())>> {
+    let listener = ReactiveTcpListener::bind(format!("127.0.0.1:{}", port).as_str())?;
+    let mut streaming = HashMap::new();
+
+    let connections = Connections::new(move |token, conn: &mut HttpConnection| {
+        loop {
+            if let Some(remaining) = streaming.get_mut(&token) {
+                conn.flush();
+                while *remaining > 0 && !conn.write_buffer_full() {
+                    let _ = conn.write_chunk(&[b'x'; CHUNK]);
+                    *remaining -= 1;
+                }
+                if *remaining > 0 {
+                    break;
+                }
+                streaming.remove(&token);
+                let _ = conn.finish();
+            }
+
+            let request = match conn.next_request() {
+                Some(request) => request,
+                None => break,
+            };
+
+            let res = match request.path() {
+                "/stream" => {
+                    let chunks = request.query().unwrap().parse::<usize>().unwrap();
+                    streaming.insert(token, chunks);
+                    conn.respond_chunked(Response::new(200))
+                }
+                "/inject" => {
+                    // The invalid response is rejected and the request can still be answered
+                    let injected = Response::new(200).header("X-A", "b\r\nSet-Cookie: c");
+                    assert!(conn.respond(injected).is_err());
+                    assert!(conn.respond_chunked(Response::new(200).header("X A", "b")).is_err());
+                    conn.respond(Response::new(400))
+                }
+                path => {
+                    let mut body = format!("{} {} ", request.method(), path).into_bytes();
+                    body.extend_from_slice(request.body());
+                    conn.respond(Response::new(200).header("Content-Type", "text/plain").body(body))
+                }
+            };
+            if res.is_err() {
+                return Handled::Close;
+            }
+        }
+
+        match conn.is_closed() {
+            true => Handled::Close,
+            false => Handled::<()>::Continue,
+        }
+    });
+
+    let run = listener
+        .map(|(stream, _)| {
+            let stream = ReactiveTcpStream::new(stream).unwrap();
+            HttpConnection::new(stream).max_body_size(1024)
+        })
+        .chain(connections);
+    Ok(run)
+}
+
+// Run the client on a thread, and stop the system once it's done
+fn run_client<F, T>(port: u16, client: F) -> Result<T>
+where
+    F: FnOnce(StdStream) -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let system_sig = System::init()?;
+    let server = server(port)?;
+
+    let handle = thread::spawn(move || {
+        let stream = StdStream::connect(("127.0.0.1", port)).unwrap();
+        stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        let res = client(stream);
+        let _ = system_sig.send(SystemEvent::Stop);
+        res
+    });
+
+    System::start(server)?;
+    Ok(handle.join().unwrap())
+}
+
+#[test]
+fn test_keep_alive() -> R {
+    let responses = run_client(5610, |mut stream| {
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        stream.write_all(b"GET /first HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+        let first = read_response(&mut reader);
+        stream.write_all(b"POST /second HTTP/1.1\r\nContent-Length: 4\r\n\r\nbody").unwrap();
+        let second = read_response(&mut reader);
+        (first, second)
+    })?;
+
+    let (first, second) = responses;
+    assert_eq!(first.status, 200);
+    assert_eq!(first.body, b"GET /first ");
+    assert_eq!(first.header("content-type"), Some("text/plain"));
+    assert_eq!(first.header("connection"), None);
+    assert_eq!(second.body, b"POST /second body");
+    Ok(())
+}
+
+#[test]
+fn test_pipelining_and_close() -> R {
+    let (responses, rest) = run_client(5611, |mut stream| {
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        stream
+            .write_all(
+                b"GET /a HTTP/1.1\r\n\r\n\
+                  POST /b HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n3\r\nabc\r\n0\r\n\r\n\
+                  GET /c HTTP/1.1\r\nConnection: close\r\n\r\n",
+            )
+            .unwrap();
+        let responses = (0..3).map(|_| read_response(&mut reader)).collect::<Vec<_>>();
+
+        // The server closes the connection after the last response
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).unwrap();
+        (responses, rest)
+    })?;
+
+    let bodies = responses.iter().map(|r| r.body.clone()).collect::<Vec<_>>();
+    assert_eq!(bodies, vec![b"GET /a ".to_vec(), b"POST /b abc".to_vec(), b"GET /c ".to_vec()]);
+    assert_eq!(responses[2].header("connection"), Some("close"));
+    assert!(rest.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_invalid_response_header() -> R {
+    let response = run_client(5627, |mut stream| {
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        stream.write_all(b"GET /inject HTTP/1.1\r\n\r\n").unwrap();
+        read_response(&mut reader)
+    })?;
+
+    assert_eq!(response.status, 400);
+    assert_eq!(response.header("x-a"), None);
+    assert_eq!(response.header("set-cookie"), None);
+    Ok(())
+}
+
+#[test]
+fn test_body_too_large() -> R {
+    let response = run_client(5612, |mut stream| {
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        stream.write_all(b"POST / HTTP/1.1\r\nContent-Length: 2048\r\n\r\n").unwrap();
+        read_response(&mut reader)
+    })?;
+
+    assert_eq!(response.status, 413);
+    assert_eq!(response.header("connection"), Some("close"));
+    Ok(())
+}
+
+#[test]
+fn test_chunked_response_backpressure() -> R {
+    let chunks = 1024;
+    let response = run_client(5613, move |mut stream| {
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        stream.write_all(format!("GET /stream?{} HTTP/1.1\r\n\r\n", chunks).as_bytes()).unwrap();
+
+        // Let the server fill up the socket before reading anything
+        thread::sleep(Duration::from_millis(100));
+        let response = read_response(&mut reader);
+
+        // The connection is still usable after the streamed response
+        stream.write_all(b"GET /after HTTP/1.1\r\n\r\n").unwrap();
+        (response, read_response(&mut reader))
+    })?;
+
+    let (streamed, after) = response;
+    assert_eq!(streamed.header("transfer-encoding"), Some("chunked"));
+    assert_eq!(streamed.body.len(), chunks * CHUNK);
+    assert!(streamed.body.iter().all(|b| *b == b'x'));
+    assert_eq!(after.body, b"GET /after ");
+    Ok(())
+}
+
+#[test]
+fn test_http_10() -> R {
+    let (response, rest) = run_client(5614, |mut stream| {
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        stream.write_all(b"HEAD /old HTTP/1.0\r\n\r\n").unwrap();
+        let response = read_line(&mut reader);
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).unwrap();
+        (response, String::from_utf8(rest).unwrap())
+    })?;
+
+    // No body for a `HEAD` request, and the connection closes
+    assert_eq!(response, "HTTP/1.1 200 OK");
+    assert!(rest.contains("Content-Length: 10\r\n"));
+    assert!(rest.contains("Connection: close\r\n"));
+    assert!(rest.ends_with("\r\n\r\n"));
+    Ok(())
+}
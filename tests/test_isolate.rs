@@ -0,0 +1,147 @@
+use std::cell::{Cell, RefCell};
+use std::io::ErrorKind::WouldBlock;
+use std::io::{Read, Write};
+use std::net::TcpStream as StdStream;
+use std::panic;
+use std::rc::Rc;
+use std::thread;
+use std::time::Duration;
+
+use sonr::errors::Result;
+use sonr::net::connections::{Connections, Handled};
+use sonr::net::tcp::{ReactiveTcpListener, ReactiveTcpStream};
+use sonr::prelude::*;
+use sonr::reactor::producers::Mono;
+use sonr::reactor::timer::{Interval, Timeout};
+use sonr::reactor::Panic;
+
+type R = Result<()>;
+
+fn stop_after(millis: u64) -> Result<impl Reactor> {
+    let system_sig = System::init()?;
+    Ok(Timeout::new(Duration::from_millis(millis))?.map(move |_| {
+        let _ = system_sig.send(SystemEvent::Stop);
+    }))
+}
+
+#[test]
+fn test_drop_on_panic() -> R {
+    System::init()?;
+    let panics = Rc::new(RefCell::new(Vec::<Panic>::new()));
+    let reported = panics.clone();
+
+    let mono = Mono::new(1u8)?;
+    let mut tokens = Vec::new();
+    mono.tokens(&mut tokens);
+    let failing = mono
+        .map(|_: u8| -> u8 { panic!("failing reactor") })
+        .isolate()
+        .on_panic(move |panic| reported.borrow_mut().push(panic.clone()));
+
+    System::start(failing.and(stop_after(20)?))?;
+
+    let panics = panics.borrow();
+    assert_eq!(panics.len(), 1);
+    assert_eq!(panics[0].message(), "failing reactor");
+    assert_eq!(panics[0].token(), Some(tokens[0]));
+    Ok(())
+}
+
+#[test]
+fn test_restart_on_panic() -> R {
+    let system_sig = System::init()?;
+    let restarts = Rc::new(Cell::new(0));
+    let counter = restarts.clone();
+
+    let new_interval = || -> Result<_> {
+        Ok(Interval::new(Duration::from_millis(2))?.map(|_| -> u64 { panic!("tick") }))
+    };
+
+    let failing = new_interval()?
+        .isolate()
+        .restart(move || {
+            counter.set(counter.get() + 1);
+            new_interval()
+        })
+        .on_panic({
+            let mut panics = 0;
+            move |_| {
+                panics += 1;
+                if panics == 3 {
+                    let _ = system_sig.send(SystemEvent::Stop);
+                }
+            }
+        });
+
+    System::start(failing)?;
+    assert!(restarts.get() >= 3);
+    Ok(())
+}
+
+#[test]
+fn test_escalate_panic() -> R {
+    System::init()?;
+    let reported = Rc::new(Cell::new(false));
+    let hook_called = reported.clone();
+
+    let failing = Mono::new(1u8)?
+        .map(|_: u8| -> u8 { panic!("escalated") })
+        .isolate()
+        .escalate()
+        .on_panic(move |_| hook_called.set(true));
+
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| System::start(failing)));
+    assert!(result.is_err());
+    assert!(reported.get());
+    Ok(())
+}
+
+#[test]
+fn test_connections_drop_on_panic() -> R {
+    let system_sig = System::init()?;
+
+    let listener = ReactiveTcpListener::bind("127.0.0.1:5589")?
+        .map(|(stream, _)| ReactiveTcpStream::new(stream).unwrap());
+
+    let clients = thread::spawn(|| {
+        let mut failing = StdStream::connect("127.0.0.1:5589").unwrap();
+        failing.write_all(b"boom").unwrap();
+        let mut buf = [0u8; 4];
+        // The connection is closed once the handler panics
+        assert_eq!(failing.read(&mut buf).unwrap(), 0);
+
+        let mut echo = StdStream::connect("127.0.0.1:5589").unwrap();
+        echo.write_all(b"ping").unwrap();
+        echo.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"ping");
+    });
+
+    let panics = Rc::new(Cell::new(0));
+    let counter = panics.clone();
+    let connections = Connections::new(|_, stream: &mut ReactiveTcpStream| {
+        let mut buf = [0u8; 4];
+        if stream.readable() {
+            match stream.read(&mut buf) {
+                Ok(0) => return Handled::Close,
+                Ok(_) if &buf == b"boom" => panic!("boom"),
+                Ok(n) => {
+                    let _ = stream.write(&buf[..n]);
+                    return Handled::Value(());
+                }
+                Err(ref e) if e.kind() == WouldBlock => {}
+                Err(_) => return Handled::Close,
+            }
+        }
+        Handled::Continue
+    })
+    .drop_on_panic(move |_| counter.set(counter.get() + 1))
+    .map(|_| {
+        let _ = system_sig.send(SystemEvent::Stop);
+    });
+
+    System::start(listener.chain(connections))?;
+    clients.join().unwrap();
+
+    assert_eq!(panics.get(), 1);
+    Ok(())
+}
@@ -0,0 +1,129 @@
+use std::io::{Read, Write};
+use std::net::TcpStream as StdStream;
+use std::thread;
+use std::time::Duration;
+
+use sonr::errors::Result;
+use sonr::metrics::{self, MetricsEndpoint, Value};
+use sonr::net::tcp::ReactiveTcpListener;
+use sonr::prelude::*;
+use sonr::reactor::producers::Mono;
+use sonr::reactor::timer::Timeout;
+use sonr::sync::queue::ReactiveQueue;
+use sonr::sync::signal::SignalReceiver;
+
+type R = Result<()>;
+
+#[test]
+fn test_system_metrics() -> R {
+    let system_sig = System::init()?;
+    let name = System::name();
+
+    let stop = Mono::new(())?.map(move |_| {
+        let _ = system_sig.send(SystemEvent::Stop);
+    });
+    System::start(stop.and(Timeout::new(Duration::from_secs(10))?))?;
+
+    let snapshot = metrics::snapshot();
+    let labels = &[("system", name.as_str())];
+    match snapshot.get("sonr_system_polls_total", labels) {
+        Some(Value::Counter(polls)) => assert!(*polls >= 1),
+        value => panic!("unexpected value: {:?}", value),
+    }
+    match snapshot.get("sonr_system_react_seconds", labels) {
+        Some(Value::Histogram(histogram)) => assert!(histogram.count >= 1),
+        value => panic!("unexpected value: {:?}", value),
+    }
+    assert_eq!(snapshot.get("sonr_system_timers", labels), Some(&Value::Gauge(1)));
+    Ok(())
+}
+
+#[test]
+fn test_queue_and_signal_depth() -> R {
+    let mut queue = ReactiveQueue::unbounded();
+    queue.register_metrics("test-queue");
+    let deque = queue.deque();
+    for n in 0..3 {
+        queue.push(n);
+    }
+    assert!(deque.steal().is_success());
+
+    let signal = SignalReceiver::unbounded();
+    signal.register_metrics("test-signal");
+    let sender = signal.sender();
+    sender.send(1u8).unwrap();
+    sender.send(2u8).unwrap();
+    signal.try_recv()?;
+
+    let snapshot = metrics::snapshot();
+    assert_eq!(snapshot.get("sonr_queue_depth", &[("queue", "test-queue")]), Some(&Value::Gauge(2)));
+    assert_eq!(snapshot.get("sonr_queue_pushed_total", &[("queue", "test-queue")]), Some(&Value::Counter(3)));
+    assert_eq!(snapshot.get("sonr_signal_depth", &[("signal", "test-signal")]), Some(&Value::Gauge(1)));
+
+    // Dropped metrics are removed
+    drop(queue);
+    drop(deque);
+    assert_eq!(metrics::snapshot().get("sonr_queue_depth", &[("queue", "test-queue")]), None);
+    Ok(())
+}
+
+#[test]
+fn test_metrics_endpoint() -> R {
+    let system_sig = System::init()?;
+
+    let mut listener = ReactiveTcpListener::bind("127.0.0.1:5590")?;
+    listener.register_metrics("test-listener");
+    System::spawn(MetricsEndpoint::bind("127.0.0.1:5591")?);
+
+    let client = thread::spawn(move || {
+        let _connection = StdStream::connect("127.0.0.1:5590").unwrap();
+
+        // The connection might not have been accepted yet
+        let mut response = String::new();
+        for _ in 0..100 {
+            let mut stream = StdStream::connect("127.0.0.1:5591").unwrap();
+            stream.write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+            response.clear();
+            stream.read_to_string(&mut response).unwrap();
+            if response.contains("sonr_listener_accepted_total{listener=\"test-listener\"} 1") {
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        let _ = system_sig.send(SystemEvent::Stop);
+        response
+    });
+
+    System::start(listener.map(|_| ()))?;
+    let response = client.join().unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(response.contains("# TYPE sonr_listener_accepted_total counter\n"));
+    assert!(response.contains("sonr_listener_accepted_total{listener=\"test-listener\"} 1\n"));
+    Ok(())
+}
+
+#[test]
+fn test_metrics_endpoint_request_too_large() -> R {
+    let system_sig = System::init()?;
+    let endpoint = MetricsEndpoint::bind("127.0.0.1:5592")?;
+
+    let client = thread::spawn(move || {
+        let mut stream = StdStream::connect("127.0.0.1:5592").unwrap();
+        stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+
+        // A request without the end of the headers, the write fails once
+        // the endpoint closes the connection
+        let _ = stream.write_all(&[b'x'; 64 * 1024]);
+        let mut response = Vec::new();
+        let _ = stream.read_to_end(&mut response);
+        let _ = system_sig.send(SystemEvent::Stop);
+        response
+    });
+
+    System::start(endpoint)?;
+    let response = client.join().unwrap();
+
+    assert!(response.is_empty());
+    Ok(())
+}
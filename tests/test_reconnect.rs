@@ -0,0 +1,134 @@
+use std::cell::RefCell;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpListener as StdListener};
+use std::rc::Rc;
+use std::thread;
+use std::time::Duration;
+
+use sonr::errors::Result;
+use sonr::net::reconnect::{Backoff, Link, ReconnectingStream, WritePolicy};
+use sonr::prelude::*;
+use sonr::reactor::timer::Timeout;
+use sonr::sync::signal::SignalSender;
+
+type R = Result<()>;
+
+// Record the links of the stream, and stop the System after the second connect
+struct Client {
+    stream: ReconnectingStream<SocketAddr>,
+    links: Rc<RefCell<Vec<&'static str>>>,
+    received: Rc<RefCell<Vec<u8>>>,
+    system_sig: SignalSender<SystemEvent>,
+}
+
+impl Reactor for Client {
+    type Input = ();
+    type Output = ();
+
+    fn react(&mut self, reaction: Reaction<()>) -> Reaction<()> {
+        match self.stream.react(reaction) {
+            Reaction::Value(Link::Connected) => {
+                self.links.borrow_mut().push("connected");
+                if self.links.borrow().len() == 3 {
+                    let _ = self.stream.write_all(b"again");
+                    let _ = self.system_sig.send(SystemEvent::Stop);
+                }
+                Reaction::Value(())
+            }
+            Reaction::Value(Link::Disconnected(_)) => {
+                self.links.borrow_mut().push("disconnected");
+                Reaction::Value(())
+            }
+            Reaction::Value(Link::Ready) => {
+                let mut buf = [0u8; 64];
+                while self.stream.readable() {
+                    match self.stream.read(&mut buf) {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => self.received.borrow_mut().extend_from_slice(&buf[..n]),
+                    }
+                }
+                Reaction::Value(())
+            }
+            Reaction::Event(event) => Reaction::Event(event),
+            Reaction::Continue => Reaction::Continue,
+        }
+    }
+}
+
+#[test]
+fn test_reconnect() -> R {
+    let system_sig = System::init()?;
+    let listener = StdListener::bind("127.0.0.1:5597")?;
+    let addr = listener.local_addr()?;
+
+    // Read a message and reply, then drop the connection
+    let server = thread::spawn(move || {
+        let mut messages = Vec::new();
+        for _ in 0..2 {
+            let (mut connection, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 5];
+            connection.read_exact(&mut buf).unwrap();
+            let _ = connection.write_all(b"ok");
+            messages.push(buf);
+        }
+        messages
+    });
+
+    let mut stream = ReconnectingStream::new(addr)?;
+    stream.set_backoff(Backoff { initial: Duration::from_millis(10), jitter: 0.0, ..Backoff::default() });
+
+    // Written once the stream is connected
+    stream.write_all(b"hello")?;
+    assert_eq!(stream.buffered(), 5);
+
+    let links = Rc::new(RefCell::new(Vec::new()));
+    let received = Rc::new(RefCell::new(Vec::new()));
+    let client = Client { stream, links: links.clone(), received: received.clone(), system_sig };
+    System::start(client)?;
+
+    let messages = server.join().unwrap();
+    assert_eq!(&messages, &[*b"hello", *b"again"]);
+    // The second connection might be dropped by the server before the System stops
+    assert_eq!(links.borrow()[..3], ["connected", "disconnected", "connected"]);
+    assert!(received.borrow().starts_with(b"ok"));
+    Ok(())
+}
+
+#[test]
+fn test_write_policy() -> R {
+    System::init()?;
+
+    // Nothing is listening on the port
+    let addr: SocketAddr = "127.0.0.1:5598".parse()?;
+    let mut stream = ReconnectingStream::new(addr)?;
+    assert!(!stream.is_connected());
+
+    stream.set_write_policy(WritePolicy::Buffer { max: 4 });
+    assert_eq!(stream.write(b"abc")?, 3);
+    assert_eq!(stream.write(b"de").unwrap_err().kind(), ErrorKind::WouldBlock);
+    assert_eq!(stream.buffered(), 3);
+
+    stream.set_write_policy(WritePolicy::Reject);
+    assert_eq!(stream.write(b"de").unwrap_err().kind(), ErrorKind::NotConnected);
+    assert_eq!(stream.read(&mut [0u8; 4]).unwrap_err().kind(), ErrorKind::NotConnected);
+    Ok(())
+}
+
+#[test]
+fn test_delays_that_never_expire() -> R {
+    let system_sig = System::init()?;
+
+    // Nothing is listening, so the stream waits for the backoff
+    let mut stream = ReconnectingStream::new("127.0.0.1:5626".parse::<SocketAddr>()?)?;
+    stream.set_connect_timeout(Duration::MAX);
+    stream.set_backoff(Backoff { initial: Duration::MAX, max: Duration::MAX, ..Backoff::default() });
+
+    let stop = Timeout::new(Duration::from_millis(100))?.map(|_| {
+        let _ = system_sig.send(SystemEvent::Stop);
+    });
+    let mut links = 0;
+    System::start(stream.map(|_| links += 1).and(stop))?;
+
+    assert_eq!(links, 0);
+    Ok(())
+}
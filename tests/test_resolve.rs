@@ -0,0 +1,107 @@
+use std::cell::RefCell;
+use std::io::ErrorKind;
+use std::net::{SocketAddr, TcpListener as StdListener};
+use std::rc::Rc;
+use std::time::Duration;
+
+use sonr::errors::Result;
+use sonr::net::connect::{Connect, TcpConnector};
+use sonr::net::resolve::{Hosts, Resolution, Resolver};
+use sonr::prelude::*;
+use sonr::reactor::producers::ReactiveGenerator;
+
+type R = Result<()>;
+
+// Resolve the names, and return the resolutions
+fn resolve(hosts: Hosts, names: &[&str]) -> Result<Vec<Resolution>> {
+    resolve_with(|resolver| resolver.hosts(hosts), names)
+}
+
+fn resolve_with(configure: impl FnOnce(Resolver) -> Resolver, names: &[&str]) -> Result<Vec<Resolution>> {
+    let system_sig = System::init()?;
+    let resolver = configure(Resolver::new()?);
+    let resolutions = Rc::new(RefCell::new(Vec::new()));
+    let result = resolutions.clone();
+    let count = names.len();
+
+    let names = names.iter().map(|name| name.to_string()).collect();
+    let run = ReactiveGenerator::new(names)?.chain(resolver).map(move |resolution| {
+        result.borrow_mut().push(resolution);
+        if result.borrow().len() == count {
+            let _ = system_sig.send(SystemEvent::Stop);
+        }
+    });
+
+    System::start(run)?;
+    let resolutions = resolutions.replace(Vec::new());
+    Ok(resolutions)
+}
+
+#[test]
+fn test_resolve_hosts() -> R {
+    let hosts = Hosts::parse("10.0.0.5 db.internal\n");
+    let resolutions = resolve(hosts, &["db.internal:5432", "127.0.0.1:80", "[::1]:81"])?;
+
+    let addrs: Vec<Vec<SocketAddr>> = resolutions.into_iter().map(|r| r.addrs.unwrap()).collect();
+    assert_eq!(addrs, vec![
+        vec!["10.0.0.5:5432".parse()?],
+        vec!["127.0.0.1:80".parse()?],
+        vec!["[::1]:81".parse()?],
+    ]);
+    Ok(())
+}
+
+#[test]
+fn test_resolve_on_pool() -> R {
+    // Resolved by the operating system, once for both names
+    let resolutions = resolve(Hosts::default(), &["localhost:80", "LOCALHOST:81"])?;
+
+    assert_eq!(resolutions.len(), 2);
+    for resolution in resolutions {
+        let addrs = resolution.addrs?;
+        assert!(!addrs.is_empty());
+        assert!(addrs.iter().all(|addr| addr.ip().is_loopback()));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_cache_forever() -> R {
+    let resolutions = resolve_with(|resolver| resolver.ttl(Duration::MAX), &["localhost:80"])?;
+    assert!(!resolutions[0].addrs.as_ref().unwrap().is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_invalid_name() -> R {
+    let resolutions = resolve(Hosts::default(), &["localhost"])?;
+    assert_eq!(resolutions[0].name, "localhost");
+    match resolutions[0].addrs {
+        Err(ref e) => assert_eq!(e.kind(), ErrorKind::InvalidInput),
+        ref res => panic!("expected an error, got {:?}", res),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_resolve_and_connect() -> R {
+    let system_sig = System::init()?;
+    let listener = StdListener::bind("127.0.0.1:5599")?;
+    let connected = Rc::new(RefCell::new(None));
+    let result = connected.clone();
+
+    let hosts = Hosts::parse("127.0.0.1 db.internal\n");
+    let run = ReactiveGenerator::new(vec!["db.internal:5599".to_string()])?
+        .chain(Resolver::new()?.hosts(hosts))
+        .map(|resolution: Resolution| resolution.addrs.unwrap_or_default())
+        .chain(TcpConnector::new())
+        .map(move |connect: Connect| {
+            *result.borrow_mut() = Some(connect.map(|stream| stream.inner().peer_addr().ok()));
+            let _ = system_sig.send(SystemEvent::Stop);
+        });
+
+    System::start(run)?;
+    let peer = connected.borrow_mut().take().expect("no outcome").expect("connected");
+    assert_eq!(peer, Some(listener.local_addr()?));
+    Ok(())
+}
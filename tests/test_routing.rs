@@ -0,0 +1,179 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use sonr::errors::Result;
+use sonr::prelude::*;
+use sonr::reactor::all;
+use sonr::reactor::producers::Mono;
+use sonr::reactor::timer::Timeout;
+
+type R = Result<()>;
+type Seen = Rc<RefCell<Vec<Token>>>;
+
+// -----------------------------------------------------------------------------
+// 		- Record the tokens of every event a reactor is given -
+// -----------------------------------------------------------------------------
+struct Recorder<T> {
+    inner: T,
+    seen: Seen,
+}
+
+fn record<T: Reactor>(inner: T) -> (Recorder<T>, Seen) {
+    let seen = Seen::default();
+    (Recorder { inner, seen: seen.clone() }, seen)
+}
+
+impl<T: Reactor> Reactor for Recorder<T> {
+    type Input = T::Input;
+    type Output = T::Output;
+
+    fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
+        if let Reaction::Event(ref event) = reaction {
+            self.seen.borrow_mut().push(event.token());
+        }
+        self.inner.react(reaction)
+    }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        self.inner.tokens(tokens);
+    }
+}
+
+// -----------------------------------------------------------------------------
+// 		- Hold timeouts created while reacting, and add their tokens if `claim` -
+// -----------------------------------------------------------------------------
+struct Holder {
+    timeouts: Vec<Timeout>,
+    claim: bool,
+}
+
+impl Reactor for Holder {
+    type Input = Timeout;
+    type Output = ();
+
+    fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
+        match reaction {
+            Reaction::Value(timeout) => {
+                self.timeouts.push(timeout);
+                Reaction::Continue
+            }
+            Reaction::Event(event) => {
+                for timeout in &mut self.timeouts {
+                    if let Reaction::Value(()) = timeout.react(Reaction::Event(event)) {
+                        System::send(SystemEvent::Stop);
+                        return Reaction::Continue;
+                    }
+                }
+                event.into()
+            }
+            Reaction::Continue => Reaction::Continue,
+        }
+    }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        if self.claim {
+            tokens.extend(self.timeouts.iter().map(Timeout::token));
+        }
+    }
+}
+
+#[test]
+fn test_and_routes_events_to_owner() -> R {
+    let system_sig = System::init()?;
+
+    let first = Timeout::new(Duration::from_millis(5))?;
+    let first_token = first.token();
+    let (first, first_seen) = record(first);
+
+    let second = Timeout::new(Duration::from_millis(20))?;
+    let second_token = second.token();
+    let (second, second_seen) = record(second.map(move |_| system_sig.send(SystemEvent::Stop)));
+
+    System::start(first.and(second))?;
+
+    assert_eq!(*first_seen.borrow(), vec![first_token]);
+    assert_eq!(*second_seen.borrow(), vec![second_token]);
+    Ok(())
+}
+
+#[test]
+fn test_all_routes_events_to_owner() -> R {
+    let system_sig = System::init()?;
+
+    let (first, first_seen) = record(Timeout::new(Duration::from_millis(5))?);
+    let (second, second_seen) = record(Timeout::new(Duration::from_millis(10))?);
+    let (third, third_seen) = record(Timeout::new(Duration::from_millis(20))?);
+
+    let mut expired = 0;
+    let run = all((first, second, third)).map(move |_| {
+        expired += 1;
+        if expired == 3 {
+            let _ = system_sig.send(SystemEvent::Stop);
+        }
+    });
+
+    System::start(run)?;
+
+    assert_eq!(first_seen.borrow().len(), 1);
+    assert_eq!(second_seen.borrow().len(), 1);
+    assert_eq!(third_seen.borrow().len(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_tokens_reserved_while_reacting_are_routed() -> R {
+    System::init()?;
+
+    // The timeout is created when the `Mono` reacts
+    let first = Mono::new(())?
+        .map(|_| Timeout::new(Duration::from_millis(10)).unwrap())
+        .chain(Holder { timeouts: Vec::new(), claim: true });
+
+    let (second, second_seen) = record(Timeout::new(Duration::from_secs(10))?);
+
+    System::start(first.and(second))?;
+
+    assert!(second_seen.borrow().is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_unclaimed_reserved_tokens_are_passed_to_every_branch() -> R {
+    System::init()?;
+
+    // The timeout is reserved while the first branch reacts,
+    // but the `Holder` doesn't add its token.
+    let (first, first_seen) = record(
+        Mono::new(())?
+            .map(|_| Timeout::new(Duration::from_millis(10)).unwrap())
+            .chain(Holder { timeouts: Vec::new(), claim: false }),
+    );
+
+    let (second, second_seen) = record(Timeout::new(Duration::from_secs(10))?);
+
+    System::start(first.and(second))?;
+
+    // The last event is the timeout
+    let timeout_token = *first_seen.borrow().last().unwrap();
+    assert_eq!(*second_seen.borrow(), vec![timeout_token]);
+    Ok(())
+}
+
+#[test]
+fn test_unknown_tokens_are_passed_to_every_branch() -> R {
+    System::init()?;
+
+    let timeout = Timeout::new(Duration::from_millis(10))?;
+    let token = timeout.token();
+    let holder = Holder { timeouts: vec![timeout], claim: false };
+    let (first, first_seen) = record(holder);
+
+    let (second, second_seen) = record(Timeout::new(Duration::from_secs(10))?);
+
+    System::start(second.and(first))?;
+
+    assert_eq!(*first_seen.borrow(), vec![token]);
+    assert_eq!(*second_seen.borrow(), vec![token]);
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use std::io::Error as IoError;
+use std::net::TcpStream as StdStream;
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use sonr::errors::{Error, Result};
+use sonr::prelude::*;
+use sonr::reactor::producers::Mono;
+use sonr::reactor::timer::Timeout;
+use sonr::runtime::Runtime;
+
+type R = Result<()>;
+
+fn wait_for(counter: &AtomicUsize, expected: usize) {
+    let started = Instant::now();
+    while counter.load(Ordering::SeqCst) < expected {
+        assert!(started.elapsed() < Duration::from_secs(5), "timed out");
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+fn idle() -> Result<Timeout> {
+    Timeout::new(Duration::from_secs(10))
+}
+
+#[test]
+fn test_queue_distributes_work() -> R {
+    let received = Arc::new(AtomicUsize::new(0));
+    let counter = received.clone();
+
+    let (runtime, queue) = Runtime::builder().workers(3).start_with_queue(move |_, deque| {
+        let counter = counter.clone();
+        Ok(deque.map(move |n: usize| {
+            counter.fetch_add(n, Ordering::SeqCst);
+        }))
+    })?;
+    assert_eq!(runtime.len(), 3);
+
+    for _ in 0..10 {
+        queue.push(1);
+    }
+    wait_for(&received, 10);
+
+    runtime.stop();
+    assert_eq!(runtime.join()?.len(), 3);
+    Ok(())
+}
+
+#[test]
+fn test_named_workers() -> R {
+    let runtime = Runtime::builder()
+        .workers(2)
+        .name("named")
+        .stack_size(256 * 1024)
+        .pin_cores(true)
+        .start(|index| {
+            assert_eq!(thread::current().name(), Some(format!("named-{}", index).as_str()));
+            idle()
+        })?;
+
+    runtime.shutdown(Duration::from_millis(10));
+    runtime.join()?;
+    Ok(())
+}
+
+#[test]
+fn test_start_error() {
+    let started = Arc::new(AtomicUsize::new(0));
+    let counter = started.clone();
+
+    let result = Runtime::builder().workers(3).start(move |index| {
+        if index == 1 {
+            return Err(Error::Io(IoError::other("failed to start")));
+        }
+        counter.fetch_add(1, Ordering::SeqCst);
+        idle()
+    });
+
+    assert!(result.is_err());
+    assert_eq!(started.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_worker_panic_is_propagated() -> R {
+    let runtime = Runtime::builder().workers(2).start(|index| {
+        let mono = Mono::new(index)?.map(|index: usize| {
+            if index == 1 {
+                panic!("worker failed");
+            }
+        });
+        Ok(mono.and(idle()?))
+    })?;
+
+    // The panic stops the other worker, so join returns
+    let joined = panic::catch_unwind(panic::AssertUnwindSafe(|| runtime.join()));
+    assert!(joined.is_err());
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_reuse_port() -> R {
+    let accepted = Arc::new(AtomicUsize::new(0));
+    let counter = accepted.clone();
+
+    let runtime = Runtime::builder().workers(2).start_reuse_port("127.0.0.1:5588", move |_, listener| {
+        let counter = counter.clone();
+        Ok(listener.map(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        }))
+    })?;
+
+    let clients = (0..4).map(|_| StdStream::connect("127.0.0.1:5588")).collect::<std::io::Result<Vec<_>>>()?;
+    wait_for(&accepted, clients.len());
+
+    runtime.stop();
+    runtime.join()?;
+    Ok(())
+}
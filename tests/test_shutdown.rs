@@ -0,0 +1,100 @@
+use std::io::Read;
+use std::io::ErrorKind::WouldBlock;
+use std::net::TcpStream as StdStream;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use sonr::errors::Result;
+use sonr::net::connections::{Connections, Handled};
+use sonr::net::tcp::{ReactiveTcpListener, ReactiveTcpStream};
+use sonr::prelude::*;
+
+fn shutdown_after(system_sig: sonr::sync::signal::SignalSender<SystemEvent>, grace: Duration) {
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        system_sig.send(SystemEvent::Shutdown { grace });
+    });
+}
+
+fn drain(stream: &mut ReactiveTcpStream) -> Handled<()> {
+    let mut buf = [0u8; 64];
+    while stream.readable() {
+        match stream.read(&mut buf) {
+            Ok(0) => return Handled::Close,
+            Ok(_) => {}
+            Err(ref e) if e.kind() == WouldBlock => break,
+            Err(_) => return Handled::Close,
+        }
+    }
+    Handled::Continue
+}
+
+#[test]
+fn test_graceful_shutdown() -> Result<()> {
+    let system_sig = System::init()?;
+
+    let listener = ReactiveTcpListener::bind("127.0.0.1:5574")?
+        .map(|(stream, _)| ReactiveTcpStream::new(stream).unwrap());
+    let _client = StdStream::connect("127.0.0.1:5574")?;
+
+    // Close connections once the system is shutting down
+    let connections = Connections::new(|_, stream: &mut ReactiveTcpStream| {
+        match drain(stream) {
+            Handled::Continue if System::is_shutting_down() => Handled::Close,
+            handled => handled,
+        }
+    });
+
+    shutdown_after(system_sig, Duration::from_secs(5));
+    let started = Instant::now();
+    let summary = System::start(listener.chain(connections))?;
+
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert_eq!(summary.closed, 1);
+    assert!(summary.force_closed.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_forced_shutdown() -> Result<()> {
+    let system_sig = System::init()?;
+
+    let listener = ReactiveTcpListener::bind("127.0.0.1:5575")?
+        .map(|(stream, _)| ReactiveTcpStream::new(stream).unwrap());
+    let _client = StdStream::connect("127.0.0.1:5575")?;
+
+    // Never close the connection
+    let connections = Connections::new(|_, stream: &mut ReactiveTcpStream| drain(stream));
+
+    shutdown_after(system_sig, Duration::from_millis(100));
+    let started = Instant::now();
+    let summary = System::start(listener.chain(connections))?;
+
+    assert!(started.elapsed() >= Duration::from_millis(150));
+    assert_eq!(summary.closed, 0);
+    assert_eq!(summary.force_closed.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_listener_closes_on_shutdown() -> Result<()> {
+    let system_sig = System::init()?;
+
+    let listener = ReactiveTcpListener::bind("127.0.0.1:5576")?
+        .map(|(stream, _)| ReactiveTcpStream::new(stream).unwrap());
+    let _client = StdStream::connect("127.0.0.1:5576")?;
+
+    // Connect while the open connection keeps the system running
+    let refused = thread::spawn(|| {
+        thread::sleep(Duration::from_millis(150));
+        StdStream::connect("127.0.0.1:5576").is_err()
+    });
+
+    let connections = Connections::new(|_, stream: &mut ReactiveTcpStream| drain(stream));
+
+    shutdown_after(system_sig, Duration::from_millis(300));
+    System::start(listener.chain(connections))?;
+
+    assert!(refused.join().unwrap());
+    Ok(())
+}
@@ -24,3 +24,34 @@ fn test_signal_receiver() {
 
     handle.join();
 }
+
+#[test]
+fn test_value_is_available_when_receiver_wakes() {
+    let rx: SignalReceiver<u32> = SignalReceiver::unbounded();
+    let tx = rx.sender();
+    let (ack_tx, ack_rx) = std::sync::mpsc::channel();
+
+    let handle = thread::spawn(move || {
+        let handle = System::init().unwrap();
+        let rx = ReactiveSignalReceiver::new(rx).unwrap();
+
+        let run = rx.map(|val| {
+            ack_tx.send(val).unwrap();
+            if val == 999 {
+                handle.send(SystemEvent::Stop);
+            }
+        });
+        System::start(run).unwrap();
+    });
+
+    // Every value is sent to an empty channel, and the next value is only
+    // sent once the previous one is received, so the readiness of a value
+    // can't be picked up by an earlier event.
+    for val in 0..1000 {
+        tx.send(val).unwrap();
+        let received = ack_rx.recv_timeout(std::time::Duration::from_secs(5));
+        assert_eq!(received, Ok(val));
+    }
+
+    handle.join().unwrap();
+}
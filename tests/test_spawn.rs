@@ -0,0 +1,124 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::Duration;
+
+use sonr::errors::Result;
+use sonr::prelude::*;
+use sonr::reactor::producers::Mono;
+use sonr::reactor::timer::{Interval, Timeout};
+use sonr::system::SpawnHandle;
+
+type R = Result<()>;
+
+#[test]
+fn test_spawn_from_react() -> R {
+    let system_sig = System::init()?;
+
+    let handles = Rc::new(RefCell::new(Vec::new()));
+    let spawned = handles.clone();
+    let expired = Rc::new(Cell::new(0));
+    let counter = expired.clone();
+
+    // Spawn two timeouts once the `Mono` reacts
+    let run = Mono::new(())?.map(move |_| {
+        for millis in &[5, 10] {
+            let counter = counter.clone();
+            let system_sig = system_sig.clone();
+            let timeout = Timeout::new(Duration::from_millis(*millis)).unwrap().map(move |_| {
+                counter.set(counter.get() + 1);
+                if counter.get() == 2 {
+                    let _ = system_sig.send(SystemEvent::Stop);
+                }
+            });
+            spawned.borrow_mut().push(System::spawn(timeout));
+        }
+    });
+
+    System::start(run)?;
+
+    assert_eq!(expired.get(), 2);
+
+    // Spawned reactors are dropped when the System stops
+    assert!(handles.borrow().iter().all(|handle| !handle.is_running()));
+    Ok(())
+}
+
+#[test]
+fn test_cancel_spawned() -> R {
+    let system_sig = System::init()?;
+
+    let ticks = Rc::new(Cell::new(0));
+    let counter = ticks.clone();
+    let interval = Interval::new(Duration::from_millis(2))?.map(move |_| counter.set(counter.get() + 1));
+    let handle = Rc::new(System::spawn(interval));
+    assert!(handle.is_running());
+
+    let ticks_at_cancel = Rc::new(Cell::new(0));
+    let (cancel_handle, cancel_ticks, cancel_at) = (handle.clone(), ticks.clone(), ticks_at_cancel.clone());
+    let cancel = Timeout::new(Duration::from_millis(20))?.map(move |_| {
+        cancel_handle.cancel();
+        cancel_at.set(cancel_ticks.get());
+    });
+    let stop = Timeout::new(Duration::from_millis(40))?.map(move |_| {
+        let _ = system_sig.send(SystemEvent::Stop);
+    });
+
+    System::start(cancel.and(stop))?;
+
+    assert!(!handle.is_running());
+    assert!(ticks_at_cancel.get() > 0);
+    assert_eq!(ticks.get(), ticks_at_cancel.get());
+    Ok(())
+}
+
+// -----------------------------------------------------------------------------
+// 		- A reactor that cancels itself -
+// -----------------------------------------------------------------------------
+struct CancelSelf {
+    timeout: Timeout,
+    handle: Rc<RefCell<Option<SpawnHandle>>>,
+    reacted: Rc<Cell<usize>>,
+}
+
+impl Reactor for CancelSelf {
+    type Input = ();
+    type Output = ();
+
+    fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
+        if let Reaction::Value(()) = self.timeout.react(reaction) {
+            self.reacted.set(self.reacted.get() + 1);
+            self.timeout.reset();
+            if let Some(handle) = self.handle.borrow().as_ref() {
+                handle.cancel();
+            }
+        }
+        Reaction::Continue
+    }
+
+    fn tokens(&self, tokens: &mut Vec<Token>) {
+        self.timeout.tokens(tokens);
+    }
+}
+
+#[test]
+fn test_spawned_reactor_cancels_itself() -> R {
+    let system_sig = System::init()?;
+
+    let handle = Rc::new(RefCell::new(None));
+    let reacted = Rc::new(Cell::new(0));
+    let reactor = CancelSelf {
+        timeout: Timeout::new(Duration::from_millis(2))?,
+        handle: handle.clone(),
+        reacted: reacted.clone(),
+    };
+    *handle.borrow_mut() = Some(System::spawn(reactor));
+
+    let stop = Timeout::new(Duration::from_millis(30))?.map(move |_| {
+        let _ = system_sig.send(SystemEvent::Stop);
+    });
+    System::start(stop)?;
+
+    assert_eq!(reacted.get(), 1);
+    assert!(!handle.borrow().as_ref().unwrap().is_running());
+    Ok(())
+}
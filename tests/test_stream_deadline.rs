@@ -0,0 +1,142 @@
+use std::io::{ErrorKind, Read, Write};
+use std::net::TcpStream as StdStream;
+use std::time::{Duration, Instant};
+
+use sonr::errors::Result;
+use sonr::net::stream::Deadline;
+use sonr::net::tcp::{ReactiveTcpListener, ReactiveTcpStream, TcpStream};
+use sonr::prelude::*;
+
+// -----------------------------------------------------------------------------
+// 		- Idle connection -
+// 		Holds on to a stream that never receives any data
+// 		and outputs the deadline once the stream times out.
+// 		If `fill` is set the stream writes until it blocks.
+// -----------------------------------------------------------------------------
+struct Idle {
+    stream: Option<ReactiveTcpStream>,
+    setup: fn(&mut ReactiveTcpStream),
+    fill: bool,
+}
+
+impl Reactor for Idle {
+    type Input = TcpStream;
+    type Output = (Deadline, ErrorKind);
+
+    fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
+        match reaction {
+            Reaction::Value(stream) => {
+                let mut stream = ReactiveTcpStream::new(stream).unwrap();
+                (self.setup)(&mut stream);
+                self.stream = Some(stream);
+                Reaction::Continue
+            }
+            Reaction::Event(event) => {
+                if let Some(stream) = self.stream.as_mut() {
+                    if event.token() == stream.token() {
+                        stream.react(event.into());
+                        while self.fill && stream.writable() {
+                            if let Err(e) = stream.write(&[0u8; 4096]) {
+                                if let Some(deadline) = stream.timed_out() {
+                                    self.stream = None;
+                                    return Reaction::Value((deadline, e.kind()));
+                                }
+                                break;
+                            }
+                        }
+
+                        let mut buf = [0u8; 16];
+                        while stream.readable() {
+                            if let Err(e) = stream.read(&mut buf) {
+                                if let Some(deadline) = stream.timed_out() {
+                                    self.stream = None;
+                                    return Reaction::Value((deadline, e.kind()));
+                                }
+                                break;
+                            }
+                        }
+                        return Reaction::Continue;
+                    }
+                }
+                event.into()
+            }
+            Reaction::Continue => Reaction::Continue,
+        }
+    }
+}
+
+#[test]
+fn test_read_idle_timeout() -> Result<()> {
+    let system_sig = System::init()?;
+
+    let listener = ReactiveTcpListener::bind("127.0.0.1:5570")?.map(|(s, _)| s);
+    let _client = StdStream::connect("127.0.0.1:5570")?;
+
+    let mut timed_out = None;
+    let setup = |stream: &mut ReactiveTcpStream| {
+        stream.set_read_idle_timeout(Some(Duration::from_millis(50)));
+    };
+    let idle = Idle { stream: None, setup, fill: false }.map(|t| {
+        timed_out = Some(t);
+        system_sig.send(SystemEvent::Stop);
+    });
+
+    let started = Instant::now();
+    System::start(listener.chain(idle))?;
+
+    assert!(started.elapsed() >= Duration::from_millis(50));
+    assert_eq!(timed_out, Some((Deadline::ReadIdle, ErrorKind::TimedOut)));
+    Ok(())
+}
+
+#[test]
+fn test_write_idle_timeout_without_pending_writes() -> Result<()> {
+    let system_sig = System::init()?;
+
+    let listener = ReactiveTcpListener::bind("127.0.0.1:5578")?.map(|(s, _)| s);
+    let _client = StdStream::connect("127.0.0.1:5578")?;
+
+    // Nothing is written, so only the read idle timeout applies
+    let setup = |stream: &mut ReactiveTcpStream| {
+        stream.set_write_idle_timeout(Some(Duration::from_millis(20)));
+        stream.set_read_idle_timeout(Some(Duration::from_millis(150)));
+    };
+
+    let mut timed_out = None;
+    let idle = Idle { stream: None, setup, fill: false }.map(|t| {
+        timed_out = Some(t);
+        system_sig.send(SystemEvent::Stop);
+    });
+
+    let started = Instant::now();
+    System::start(listener.chain(idle))?;
+
+    assert!(started.elapsed() >= Duration::from_millis(150));
+    assert_eq!(timed_out, Some((Deadline::ReadIdle, ErrorKind::TimedOut)));
+    Ok(())
+}
+
+#[test]
+fn test_write_idle_timeout_while_blocked() -> Result<()> {
+    let system_sig = System::init()?;
+
+    let listener = ReactiveTcpListener::bind("127.0.0.1:5579")?.map(|(s, _)| s);
+    // The client never reads, so the server's writes block
+    let _client = StdStream::connect("127.0.0.1:5579")?;
+
+    let setup = |stream: &mut ReactiveTcpStream| {
+        stream.set_write_idle_timeout(Some(Duration::from_millis(50)));
+        stream.set_read_idle_timeout(Some(Duration::from_secs(5)));
+    };
+
+    let mut timed_out = None;
+    let idle = Idle { stream: None, setup, fill: true }.map(|t| {
+        timed_out = Some(t);
+        system_sig.send(SystemEvent::Stop);
+    });
+
+    System::start(listener.chain(idle))?;
+
+    assert_eq!(timed_out, Some((Deadline::WriteIdle, ErrorKind::TimedOut)));
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use sonr::errors::{Error, Result};
+use sonr::prelude::*;
+use sonr::reactor::EventedReactor;
+use sonr::sync::signal::{ReactiveSignalReceiver, SignalReceiver};
+use sonr::{Growth, PollOpt, Ready};
+
+type R = Result<()>;
+
+#[test]
+fn test_fixed_token_capacity() -> R {
+    System::builder().token_capacity(3).token_growth(Growth::Fixed).init()?;
+
+    // The first token is reserved by the System
+    System::reserve_token()?;
+    System::reserve_token()?;
+    match System::reserve_token() {
+        Err(Error::NoCapacity) => {}
+        res => panic!("expected NoCapacity, got {:?}", res),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_max_events_per_iteration() -> R {
+    let system_sig = System::builder().max_events_per_iteration(1).events_capacity(2).init()?;
+    let received = Rc::new(Cell::new(0));
+
+    // Each receiver has a value ready before the System starts
+    let receiver = || -> Result<_> {
+        let rx = ReactiveSignalReceiver::new(SignalReceiver::unbounded())?;
+        rx.sender().send(()).unwrap();
+        let received = received.clone();
+        let system_sig = system_sig.clone();
+        Ok(rx.map(move |_| {
+            received.set(received.get() + 1);
+            if received.get() == 3 {
+                let _ = system_sig.send(SystemEvent::Stop);
+            }
+        }))
+    };
+
+    System::start(receiver()?.and(receiver()?).and(receiver()?))?;
+    assert_eq!(received.get(), 3);
+    Ok(())
+}
+
+#[test]
+fn test_poll_opt() -> R {
+    System::builder().poll_opt(PollOpt::level()).name("level-triggered").init()?;
+    assert_eq!(System::poll_opt(), PollOpt::level());
+    assert_eq!(System::name(), "level-triggered");
+
+    let level = EventedReactor::new(SignalReceiver::<()>::unbounded(), Ready::readable())?;
+    assert_eq!(level.opts(), PollOpt::level());
+
+    let oneshot = EventedReactor::with_opts(SignalReceiver::<()>::unbounded(), Ready::readable(), PollOpt::oneshot())?;
+    assert_eq!(oneshot.opts(), PollOpt::oneshot());
+    Ok(())
+}
@@ -0,0 +1,115 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use sonr::errors::{Error, Result};
+use sonr::prelude::*;
+use sonr::reactor::timer::Timeout;
+use sonr::system::SystemHandle;
+
+type R = Result<()>;
+
+fn assert_send_sync<T: Send + Sync + Clone>() {}
+
+// Run an idle System on a new thread, and return its handle
+fn worker() -> (SystemHandle, thread::JoinHandle<Result<()>>) {
+    let (tx, rx) = mpsc::channel();
+    let worker = thread::spawn(move || -> Result<()> {
+        System::init()?;
+        tx.send(System::handle()).unwrap();
+        System::start(Timeout::new(Duration::from_secs(10))?)?;
+        Ok(())
+    });
+    (rx.recv().unwrap(), worker)
+}
+
+#[test]
+fn test_handle_is_send_and_sync() {
+    assert_send_sync::<SystemHandle>();
+}
+
+#[test]
+fn test_run_on_system_thread() -> R {
+    let (handle, worker) = worker();
+    let worker_id = worker.thread().id();
+
+    let ran = Arc::new(AtomicUsize::new(0));
+    for _ in 0..3 {
+        let ran = ran.clone();
+        handle.run(move || {
+            assert_eq!(thread::current().id(), worker_id);
+            ran.fetch_add(1, Ordering::SeqCst);
+        })?;
+    }
+
+    handle.stop()?;
+    worker.join().unwrap()?;
+    assert_eq!(ran.load(Ordering::SeqCst), 3);
+    Ok(())
+}
+
+#[test]
+fn test_spawn_and_stats() -> R {
+    let (handle, worker) = worker();
+
+    let stats = handle.stats(Duration::from_secs(5))?;
+    assert_eq!(stats.spawned, 0);
+    assert_eq!(stats.timers, 1);
+
+    handle.spawn(|| Timeout::new(Duration::from_secs(10)).unwrap())?;
+    let stats = handle.stats(Duration::from_secs(5))?;
+    assert_eq!(stats.spawned, 1);
+    assert_eq!(stats.timers, 2);
+    assert!(!stats.shutting_down);
+
+    handle.stop()?;
+    worker.join().unwrap()?;
+    Ok(())
+}
+
+#[test]
+fn test_system_gone() -> R {
+    let (handle, worker) = worker();
+    handle.stop()?;
+    worker.join().unwrap()?;
+
+    match handle.run(|| {}) {
+        Err(Error::SystemGone) => {}
+        res => panic!("expected SystemGone, got {:?}", res),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_stats_on_system_thread() -> R {
+    System::init()?;
+
+    // Answered right away, rather than waiting for the System to run
+    let stats = System::handle().stats(Duration::from_secs(5))?;
+    assert_eq!(stats.spawned, 0);
+    Ok(())
+}
+
+#[test]
+fn test_stats_timeout() -> R {
+    let (tx, rx) = mpsc::channel();
+    let (done_tx, done_rx) = mpsc::channel::<()>();
+    let idle = thread::spawn(move || {
+        System::init().unwrap();
+        tx.send(System::handle()).unwrap();
+        let _ = done_rx.recv();
+    });
+
+    // The System is never started
+    let handle = rx.recv().unwrap();
+    match handle.stats(Duration::from_millis(50)) {
+        Err(Error::Io(ref e)) if e.kind() == std::io::ErrorKind::TimedOut => {}
+        res => panic!("expected a timeout, got {:?}", res),
+    }
+
+    done_tx.send(()).unwrap();
+    idle.join().unwrap();
+    Ok(())
+}
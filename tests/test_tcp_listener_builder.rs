@@ -0,0 +1,59 @@
+use std::net::TcpStream as StdStream;
+use std::thread;
+use std::time::Duration;
+
+use sonr::errors::Result;
+use sonr::net::tcp::{ReactiveTcpListener, TcpListenerBuilder, TcpStream};
+use sonr::prelude::*;
+
+#[test]
+fn test_accepted_stream_options() -> Result<()> {
+    let system_sig = System::init()?;
+
+    let listener = ReactiveTcpListener::builder("127.0.0.1:5584")?
+        .backlog(16)
+        .nodelay(true)
+        .linger(Some(Duration::from_secs(1)))
+        .build()?;
+
+    thread::spawn(|| {
+        let _stream = StdStream::connect("127.0.0.1:5584");
+        thread::sleep(Duration::from_millis(200));
+    });
+
+    let mut options = None;
+    let run = listener.map(|(stream, _): (TcpStream, _)| {
+        options = Some((stream.nodelay().unwrap(), stream.linger().unwrap()));
+        system_sig.send(SystemEvent::Stop);
+    });
+
+    System::start(run)?;
+
+    assert_eq!(options, Some((true, Some(Duration::from_secs(1)))));
+    Ok(())
+}
+
+#[test]
+fn test_reuse_port() -> Result<()> {
+    System::init()?;
+
+    let first = ReactiveTcpListener::builder("127.0.0.1:5585")?.reuse_port(true).build()?;
+    let second = ReactiveTcpListener::builder("127.0.0.1:5585")?.reuse_port(true).build()?;
+    assert_eq!(first.local_addr()?, second.local_addr()?);
+
+    // Without SO_REUSEPORT the address is taken
+    assert!(ReactiveTcpListener::builder("127.0.0.1:5585")?.build().is_err());
+    Ok(())
+}
+
+#[test]
+fn test_from_std() -> Result<()> {
+    System::init()?;
+
+    let std_listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+    let addr = std_listener.local_addr()?;
+    let listener = TcpListenerBuilder::from_std(std_listener).nodelay(true).build()?;
+    assert_eq!(listener.local_addr()?, addr);
+    assert_eq!(listener.stream_options().nodelay, Some(true));
+    Ok(())
+}
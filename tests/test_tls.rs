@@ -0,0 +1,192 @@
+#![cfg(feature = "tls")]
+use std::cell::RefCell;
+use std::convert::TryInto;
+use std::io::{ErrorKind, Read, Write};
+use std::net::TcpStream as StdStream;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::thread;
+
+use sonr::errors::Result;
+use sonr::net::connect::{Connect, TcpConnector};
+use sonr::net::connections::{Connections, Handled};
+use sonr::net::tcp::ReactiveTcpListener;
+use sonr::net::tls::rustls::{ClientConfig, ClientConnection, ServerConnection, StreamOwned};
+use sonr::net::tls::{self, CertificateDer, PrivateKeyDer, SniCertificates, TlsAcceptor, TlsStream};
+use sonr::prelude::*;
+use sonr::reactor::producers::Mono;
+
+type R = Result<()>;
+
+// A self signed certificate for the names
+fn certificate(names: &[&str]) -> (CertificateDer<'static>, PrivateKeyDer<'static>) {
+    let names = names.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+    let certified = rcgen::generate_simple_self_signed(names).unwrap();
+    let key = PrivateKeyDer::Pkcs8(certified.key_pair.serialize_der().into());
+    (certified.cert.der().clone(), key)
+}
+
+// Connect with a blocking client, write `hello` and read the echo
+fn blocking_client(port: u16, config: Arc<ClientConfig>, name: &str) -> (Vec<u8>, Option<Vec<u8>>, CertificateDer<'static>) {
+    let tls = ClientConnection::new(config, name.to_string().try_into().unwrap()).unwrap();
+    let mut stream = StreamOwned::new(tls, StdStream::connect(("127.0.0.1", port)).unwrap());
+    stream.write_all(b"hello").unwrap();
+    let mut echo = vec![0u8; 5];
+    stream.read_exact(&mut echo).unwrap();
+
+    let alpn = stream.conn.alpn_protocol().map(<[u8]>::to_vec);
+    let cert = stream.conn.peer_certificates().unwrap()[0].clone();
+    (echo, alpn, cert)
+}
+
+// Accept tls connections, echo everything back, and record the
+// server name and ALPN protocol of each connection
+fn echo_server(listener: ReactiveTcpListener, acceptor: TlsAcceptor) -> (impl Reactor<Input = (), Output = (Token, ())>, Rc<RefCell<Vec<String>>>) {
+    let names = Rc::new(RefCell::new(Vec::new()));
+    let seen = names.clone();
+    let connections = Connections::new(move |_, stream: &mut TlsStream| {
+        let mut buf = [0u8; 1024];
+        loop {
+            match stream.read(&mut buf) {
+                Ok(0) => return Handled::Close,
+                Ok(n) => {
+                    let name = stream.server_name().unwrap_or("").to_string();
+                    let alpn = String::from_utf8_lossy(stream.alpn_protocol().unwrap_or(b"")).to_string();
+                    seen.borrow_mut().push(format!("{} {}", name, alpn));
+                    let _ = stream.write(&buf[..n]);
+                }
+                Err(ref e) if e.kind() == ErrorKind::WouldBlock => return Handled::Continue,
+                Err(_) => return Handled::Close,
+            }
+        }
+    });
+
+    let run = listener.chain(acceptor).map(|(stream, _)| stream).chain(connections);
+    (run, names)
+}
+
+#[test]
+fn test_tls_echo() -> R {
+    let system_sig = System::init()?;
+    let (cert, key) = certificate(&["localhost"]);
+
+    let config = tls::server_config(vec![cert.clone()], key, &[b"sonr/1"])?;
+    let listener = ReactiveTcpListener::bind("127.0.0.1:5600")?;
+    let (server, names) = echo_server(listener, TlsAcceptor::new(config));
+
+    let client_config = tls::client_config(&[cert], &[b"other", b"sonr/1"])?;
+    let client = thread::spawn(move || {
+        let res = blocking_client(5600, client_config, "localhost");
+        let _ = system_sig.send(SystemEvent::Stop);
+        res
+    });
+
+    System::start(server)?;
+    let (echo, alpn, _) = client.join().unwrap();
+    assert_eq!(echo, b"hello");
+    assert_eq!(alpn.as_deref(), Some(&b"sonr/1"[..]));
+    assert_eq!(*names.borrow(), vec!["localhost sonr/1"]);
+    Ok(())
+}
+
+#[test]
+fn test_sni() -> R {
+    let system_sig = System::init()?;
+    let (a_cert, a_key) = certificate(&["a.test"]);
+    let (b_cert, b_key) = certificate(&["b.test"]);
+
+    let mut certificates = SniCertificates::new();
+    certificates.add("a.test", vec![a_cert.clone()], &a_key)?;
+    certificates.add("B.test", vec![b_cert.clone()], &b_key)?;
+    let listener = ReactiveTcpListener::bind("127.0.0.1:5601")?;
+    let (server, names) = echo_server(listener, TlsAcceptor::new(certificates.server_config(&[])?));
+
+    let roots = [a_cert.clone(), b_cert.clone()];
+    let client_config = tls::client_config(&roots, &[])?;
+    let client = thread::spawn(move || {
+        let a = blocking_client(5601, client_config.clone(), "a.test");
+        let b = blocking_client(5601, client_config, "b.test");
+        let _ = system_sig.send(SystemEvent::Stop);
+        (a, b)
+    });
+
+    System::start(server)?;
+    let ((_, _, a), (_, _, b)) = client.join().unwrap();
+    assert_eq!(a, a_cert);
+    assert_eq!(b, b_cert);
+    assert_eq!(*names.borrow(), vec!["a.test ", "b.test "]);
+    Ok(())
+}
+
+// Connect a `TlsStream` client to a blocking server, and
+// return what the client read (or the error)
+fn connect_client(port: u16, config: Arc<ClientConfig>, name: &'static str) -> Result<std::io::Result<Vec<u8>>> {
+    let system_sig = System::init()?;
+    let outcome = Rc::new(RefCell::new(None));
+    let result = outcome.clone();
+    let mut sent = false;
+
+    let connections = Connections::new(move |_, stream: &mut TlsStream| {
+        // Written data is buffered until the handshake is complete,
+        // and reading drives the handshake.
+        if !sent {
+            sent = stream.write(b"ping").is_ok();
+        }
+
+        let mut buf = [0u8; 4];
+        let res = match stream.read(&mut buf) {
+            Err(ref e) if e.kind() == ErrorKind::WouldBlock => return Handled::Continue,
+            res => res.map(|n| buf[..n].to_vec()),
+        };
+        *result.borrow_mut() = Some(res);
+        let _ = system_sig.send(SystemEvent::Stop);
+        Handled::<()>::Close
+    });
+
+    let addr = format!("127.0.0.1:{}", port).parse()?;
+    let run = Mono::new(vec![addr])?
+        .chain(TcpConnector::new())
+        .map(move |connect: Connect| TlsStream::client(connect.unwrap(), config.clone(), name).unwrap())
+        .chain(connections);
+
+    System::start(run)?;
+    let outcome = outcome.borrow_mut().take().expect("no outcome");
+    Ok(outcome)
+}
+
+// A blocking server answering `ping` with `pong`
+fn blocking_server(port: u16, cert: CertificateDer<'static>, key: PrivateKeyDer<'static>) -> Result<thread::JoinHandle<()>> {
+    let config = tls::server_config(vec![cert], key, &[])?;
+    let listener = std::net::TcpListener::bind(("127.0.0.1", port))?;
+    Ok(thread::spawn(move || {
+        let (socket, _) = listener.accept().unwrap();
+        let mut stream = StreamOwned::new(ServerConnection::new(config).unwrap(), socket);
+        let mut buf = [0u8; 4];
+        if stream.read_exact(&mut buf).is_ok() {
+            let _ = stream.write_all(b"pong");
+        }
+    }))
+}
+
+#[test]
+fn test_client() -> R {
+    let (cert, key) = certificate(&["localhost"]);
+    let server = blocking_server(5602, cert.clone(), key)?;
+
+    let read = connect_client(5602, tls::client_config(&[cert], &[])?, "localhost")?;
+    assert_eq!(read?, b"pong");
+    server.join().unwrap();
+    Ok(())
+}
+
+#[test]
+fn test_client_verifies_server() -> R {
+    let (cert, _) = certificate(&["localhost"]);
+    let (untrusted, key) = certificate(&["localhost"]);
+    let _server = blocking_server(5603, untrusted, key)?;
+
+    // Only the first certificate is trusted
+    let read = connect_client(5603, tls::client_config(&[cert], &[])?, "localhost")?;
+    assert_eq!(read.unwrap_err().kind(), ErrorKind::InvalidData);
+    Ok(())
+}
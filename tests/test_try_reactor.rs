@@ -0,0 +1,142 @@
+use std::io;
+
+use sonr::errors::{Error, Result};
+use sonr::prelude::*;
+use sonr::reactor::consumers::Consume;
+use sonr::reactor::producers::ReactiveGenerator;
+use sonr::reactor::TakeError;
+
+// -----------------------------------------------------------------------------
+// 		- Flaky -
+// 		Passes on even numbers, and stores an error
+// 		for every odd number.
+// -----------------------------------------------------------------------------
+struct Flaky {
+    numbers: ReactiveGenerator<u32>,
+    error: Option<Error>,
+}
+
+impl Reactor for Flaky {
+    type Input = ();
+    type Output = u32;
+
+    fn react(&mut self, reaction: Reaction<Self::Input>) -> Reaction<Self::Output> {
+        match self.numbers.react(reaction) {
+            Reaction::Value(n) if n % 2 == 1 => {
+                self.error = Some(io::Error::new(io::ErrorKind::Other, "odd").into());
+                Reaction::Continue
+            }
+            reaction => reaction,
+        }
+    }
+}
+
+impl TakeError for Flaky {
+    fn take_error(&mut self) -> Option<Error> {
+        self.error.take()
+    }
+}
+
+#[test]
+fn test_map_err_and_recover() -> Result<()> {
+    let system_sig = System::init()?;
+
+    let mut errors = Vec::new();
+    let mut values = Vec::new();
+    let run = ReactiveGenerator::new(vec!["1", "x", "3"])?
+        .map(|s: &str| s.parse::<u32>())
+        .map_err(|e| e.to_string())
+        .recover(|e| {
+            errors.push(e);
+            None
+        })
+        .map(|n| {
+            values.push(n);
+            if n == 3 {
+                let _ = system_sig.send(SystemEvent::Stop);
+            }
+        });
+
+    System::start(run)?;
+
+    assert_eq!(values, vec![1, 3]);
+    assert_eq!(errors.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_or_else() -> Result<()> {
+    let system_sig = System::init()?;
+
+    let mut values = Vec::new();
+    let run = ReactiveGenerator::new(vec!["x", "2"])?
+        .map(|s: &str| s.parse::<u32>())
+        .or_else(|_| Ok::<u32, ()>(0))
+        .map(|n| {
+            values.push(n);
+            if values.len() == 2 {
+                let _ = system_sig.send(SystemEvent::Stop);
+            }
+        });
+
+    System::start(run)?;
+
+    assert_eq!(values, vec![Ok(0), Ok(2)]);
+    Ok(())
+}
+
+#[test]
+fn test_try_chain() -> Result<()> {
+    let system_sig = System::init()?;
+
+    let small = Consume::new().map(|n: u32| if n < 10 { Ok(n) } else { Err(format!("{} is too large", n)) });
+
+    let mut outputs = Vec::new();
+    let run = ReactiveGenerator::new(vec![Ok(1), Err("failed".to_string()), Ok(20)])?
+        .try_chain(small)
+        .map(|out| {
+            outputs.push(out);
+            if outputs.len() == 3 {
+                let _ = system_sig.send(SystemEvent::Stop);
+            }
+        });
+
+    System::start(run)?;
+
+    assert_eq!(
+        outputs,
+        vec![Ok(1), Err("failed".to_string()), Err("20 is too large".to_string())]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_fallible() -> Result<()> {
+    let system_sig = System::init()?;
+
+    let flaky = Flaky {
+        numbers: ReactiveGenerator::new(vec![0, 1, 2])?,
+        error: None,
+    };
+
+    let mut errors = 0;
+    let mut values = Vec::new();
+    let run = flaky
+        .fallible()
+        .recover(|_| {
+            errors += 1;
+            None
+        })
+        .map(|n| {
+            values.push(n);
+            if n == 2 {
+                let _ = system_sig.send(SystemEvent::Stop);
+            }
+        });
+
+    System::start(run)?;
+
+    assert_eq!(values, vec![0, 2]);
+    assert_eq!(errors, 1);
+    Ok(())
+}
@@ -0,0 +1,116 @@
+use std::net::UdpSocket as StdSocket;
+use std::thread;
+use std::time::Duration;
+
+use sonr::errors::Result;
+use sonr::net::udp::ReactiveUdpSocket;
+use sonr::prelude::*;
+use sonr::reactor::TakeError;
+
+#[test]
+fn test_udp_send_receive() -> Result<()> {
+    let system_sig = System::init()?;
+
+    let receiver = ReactiveUdpSocket::bind("127.0.0.1:5581")?;
+    let mut sender = ReactiveUdpSocket::bind("127.0.0.1:0")?;
+    let sender_addr = sender.local_addr()?;
+    sender.send_to(b"first".to_vec(), "127.0.0.1:5581".parse()?)?;
+    sender.send_to(b"second".to_vec(), "127.0.0.1:5581".parse()?)?;
+
+    let mut received = Vec::new();
+    let run = receiver
+        .map(|(payload, addr)| {
+            assert_eq!(addr, sender_addr);
+            received.push(payload);
+            if received.len() == 2 {
+                system_sig.send(SystemEvent::Stop);
+            }
+        })
+        .and(sender);
+
+    System::start(run)?;
+
+    assert_eq!(received, vec![b"first".to_vec(), b"second".to_vec()]);
+    Ok(())
+}
+
+#[test]
+fn test_udp_connected() -> Result<()> {
+    let system_sig = System::init()?;
+
+    let peer = StdSocket::bind("127.0.0.1:5582")?;
+    let mut socket = ReactiveUdpSocket::bind("127.0.0.1:0")?;
+    socket.connect("127.0.0.1:5582".parse()?)?;
+    socket.send(b"ping".to_vec())?;
+
+    let mut buf = [0u8; 16];
+    let (n, addr) = peer.recv_from(&mut buf)?;
+    assert_eq!(&buf[..n], b"ping");
+    peer.send_to(b"pong", addr)?;
+
+    let mut received = None;
+    let run = socket.map(|(payload, _)| {
+        received = Some(payload);
+        system_sig.send(SystemEvent::Stop);
+    });
+
+    System::start(run)?;
+
+    assert_eq!(received, Some(b"pong".to_vec()));
+    Ok(())
+}
+
+#[test]
+fn test_udp_receive_after_error() -> Result<()> {
+    let system_sig = System::init()?;
+
+    // Nothing is listening when the ping arrives,
+    // so the socket has a pending "connection refused"
+    let peer = StdSocket::bind("127.0.0.1:5585")?;
+    let mut socket = ReactiveUdpSocket::bind("127.0.0.1:0")?;
+    socket.connect("127.0.0.1:5585".parse()?)?;
+    drop(peer);
+    socket.send(b"ping".to_vec())?;
+    thread::sleep(Duration::from_millis(20));
+
+    let peer = StdSocket::bind("127.0.0.1:5585")?;
+    peer.send_to(b"pong", socket.local_addr()?)?;
+
+    let stop = system_sig.clone();
+    thread::spawn(move || {
+        thread::sleep(Duration::from_secs(2));
+        let _ = stop.send(SystemEvent::Stop);
+    });
+
+    let mut received = None;
+    let run = socket.map(|(payload, _)| {
+        received = Some(payload);
+        system_sig.send(SystemEvent::Stop);
+    });
+
+    System::start(run)?;
+
+    assert_eq!(received, Some(b"pong".to_vec()));
+    Ok(())
+}
+
+#[test]
+fn test_udp_send_after_error() -> Result<()> {
+    let _system_sig = System::init()?;
+
+    let peer = StdSocket::bind("127.0.0.1:5621")?;
+    peer.set_read_timeout(Some(Duration::from_secs(2)))?;
+    let mut socket = ReactiveUdpSocket::bind("127.0.0.1:0")?;
+
+    // Broadcasting is not enabled, so the first datagram fails to send.
+    // The error is kept for `take_error` and the next datagram is still sent.
+    socket.send_to(b"broadcast".to_vec(), "255.255.255.255:5621".parse()?)?;
+    socket.send_to(b"ping".to_vec(), "127.0.0.1:5621".parse()?)?;
+    assert!(socket.take_error().is_some());
+    assert_eq!(socket.queued(), 0);
+
+    let mut buf = [0u8; 16];
+    let (n, _) = peer.recv_from(&mut buf)?;
+    assert_eq!(&buf[..n], b"ping");
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use std::fs::remove_file;
+use std::os::unix::net::SocketAddr;
+
+use sonr::errors::Result;
+use sonr::net::uds::ReactiveUdsDatagram;
+use sonr::prelude::*;
+
+#[test]
+fn test_uds_datagram() -> Result<()> {
+    let system_sig = System::init()?;
+
+    let _ = remove_file("/tmp/sonr-test-dgram-rx.sock");
+    let _ = remove_file("/tmp/sonr-test-dgram-tx.sock");
+    let receiver = ReactiveUdsDatagram::bind("/tmp/sonr-test-dgram-rx.sock")?;
+    let mut sender = ReactiveUdsDatagram::bind("/tmp/sonr-test-dgram-tx.sock")?;
+    sender.send_to(b"hello".to_vec(), "/tmp/sonr-test-dgram-rx.sock")?;
+
+    let mut received = None;
+    let run = receiver
+        .map(|(payload, addr): (Vec<u8>, SocketAddr)| {
+            received = Some((payload, addr.as_pathname().map(|p| p.to_path_buf())));
+            system_sig.send(SystemEvent::Stop);
+        })
+        .and(sender);
+
+    System::start(run)?;
+
+    let (payload, path) = received.unwrap();
+    assert_eq!(payload, b"hello".to_vec());
+    assert_eq!(path, Some("/tmp/sonr-test-dgram-tx.sock".into()));
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_uds_datagram_abstract() -> Result<()> {
+    let system_sig = System::init()?;
+
+    let receiver = ReactiveUdsDatagram::bind_abstract(b"sonr-test-abstract")?;
+    let mut sender = ReactiveUdsDatagram::connect_abstract(b"sonr-test-abstract")?;
+    sender.send(b"hello".to_vec())?;
+
+    let mut received = None;
+    let run = receiver
+        .map(|(payload, _)| {
+            received = Some(payload);
+            system_sig.send(SystemEvent::Stop);
+        })
+        .and(sender);
+
+    System::start(run)?;
+
+    assert_eq!(received, Some(b"hello".to_vec()));
+    Ok(())
+}